@@ -0,0 +1,95 @@
+//! Abstraction over the screen a container is presented to and the input it reads.
+
+use crate::event::{Event, MouseMode};
+use crate::geometry::Point;
+use crate::image::Pixels;
+use std::fmt;
+use std::io;
+use std::time::Duration;
+
+/// Something that can show characters on a grid and report input events, such as a
+/// terminal.
+pub trait Backend {
+    /// Returns the width and height of the screen.
+    fn size(&mut self) -> io::Result<(u16, u16)>;
+
+    /// Waits up to `timeout` for an input event, returning None if there wasn't one.
+    fn read_event(&mut self, timeout: Duration) -> io::Result<Option<Event>>;
+
+    /// Writes a character at the given position, or blanks it out if None is given.
+    /// Nothing is guaranteed to appear until [`flush`](Self::flush) is called.
+    fn write_cell(&mut self, p: Point, cell: Option<&dyn fmt::Display>) -> io::Result<()>;
+
+    /// Returns true if the backend can show sixel graphics.
+    fn supports_sixel(&self) -> bool {
+        false
+    }
+
+    /// Writes a sixel sequence with its top left at the given position. Backends
+    /// that can't show sixel graphics ignore it.
+    fn write_sixel(&mut self, p: Point, sixel: &str) -> io::Result<()> {
+        let _ = (p, sixel);
+        Ok(())
+    }
+
+    /// Returns true if the backend can show images with the kitty graphics protocol.
+    fn supports_kitty_graphics(&self) -> bool {
+        false
+    }
+
+    /// Sends an image to be placed later with the given id.
+    fn transmit_image(&mut self, image: u32, pixels: &Pixels) -> io::Result<()> {
+        let _ = (image, pixels);
+        Ok(())
+    }
+
+    /// Shows a transmitted image with its top left at the given position, above
+    /// placements with a lower `z`. Placing it again with the same placement id moves
+    /// it.
+    fn place_image(&mut self, image: u32, placement: u32, p: Point, z: i32) -> io::Result<()> {
+        let _ = (image, placement, p, z);
+        Ok(())
+    }
+
+    /// Removes a placement of an image, or the image itself and every placement of
+    /// it if None.
+    fn delete_image(&mut self, image: u32, placement: Option<u32>) -> io::Result<()> {
+        let _ = (image, placement);
+        Ok(())
+    }
+
+    /// Shows the cursor at the given position, or hides it if None is given. This is
+    /// done after everything else has been written, so nothing moves it before the
+    /// next [`flush`](Self::flush).
+    fn set_cursor(&mut self, p: Option<Point>) -> io::Result<()> {
+        let _ = p;
+        Ok(())
+    }
+
+    /// Shows the cursor where it is. Nothing is written if it is already shown.
+    fn show_cursor(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Hides the cursor. Nothing is written if it is already hidden.
+    fn hide_cursor(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Starts reporting the given mouse events, replacing any reported before.
+    fn enable_mouse(&mut self, mode: MouseMode) -> io::Result<()> {
+        let _ = mode;
+        Ok(())
+    }
+
+    /// Stops reporting mouse events.
+    fn disable_mouse(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Blanks out the entire screen.
+    fn clear(&mut self) -> io::Result<()>;
+
+    /// Makes everything written so far visible.
+    fn flush(&mut self) -> io::Result<()>;
+}
@@ -0,0 +1,166 @@
+//! Windows whose contents are kept up to date with some data.
+
+use crate::{Container, Window, WindowId, WindowTime};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Data shared between the parts of an application that change it and the windows
+/// bound to it. Each change bumps its version, which is how bound windows know to
+/// redraw. Clones share the same data, and can be changed from other threads.
+#[derive(Debug, Default)]
+pub struct Source<D> {
+    inner: Arc<Mutex<(u64, D)>>,
+}
+
+impl<D> Clone for Source<D> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<D> Source<D> {
+    /// Create a source holding the given data.
+    pub fn new(data: D) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new((0, data))),
+        }
+    }
+
+    /// Locks the data, ignoring panics of anything that held it before.
+    fn lock(&self) -> std::sync::MutexGuard<'_, (u64, D)> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Replaces the data.
+    pub fn set(&self, data: D) {
+        let mut inner = self.lock();
+        *inner = (inner.0 + 1, data);
+    }
+
+    /// Changes the data in place.
+    pub fn update(&self, f: impl FnOnce(&mut D)) {
+        let mut inner = self.lock();
+        inner.0 += 1;
+        f(&mut inner.1);
+    }
+
+    /// Returns something worked out from the data.
+    pub fn read<R>(&self, f: impl FnOnce(&D) -> R) -> R {
+        f(&self.lock().1)
+    }
+
+    /// Returns the number of times the data has been changed.
+    pub fn version(&self) -> u64 {
+        self.lock().0
+    }
+}
+
+/// Identifies a binding added with [`Container::bind`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BindingId(u64);
+
+/// Redraws a window if its source has changed, returning true if it did.
+type Redraw<T> = Arc<Mutex<dyn FnMut(&mut Window<T>) -> bool + Send>>;
+
+/// A window bound to a source.
+pub(crate) struct Binding<T: fmt::Display> {
+    id: BindingId,
+    pub(crate) win: WindowId,
+    redraw: Redraw<T>,
+}
+
+impl<T: fmt::Display> Clone for Binding<T> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id,
+            win: self.win,
+            redraw: self.redraw.clone(),
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Debug for Binding<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Binding")
+            .field("id", &self.id)
+            .field("win", &self.win)
+            .finish()
+    }
+}
+
+impl<T: fmt::Display> Container<T> {
+    /// Binds the contents of a window to a source, so that `render` redraws the
+    /// window from the data whenever it changes. The window is first drawn by the
+    /// next [`update_bindings`](Self::update_bindings), which is done before every
+    /// refresh by [`run`](Self::run). Returns an id to remove the binding with.
+    pub fn bind<D: Send + 'static>(
+        &mut self,
+        win: WindowId,
+        source: &Source<D>,
+        mut render: impl FnMut(&D, &mut Window<T>) + Send + 'static,
+    ) -> BindingId {
+        let source = source.clone();
+        let mut drawn = None;
+        let redraw = move |window: &mut Window<T>| {
+            let inner = source.lock();
+            if drawn == Some(inner.0) {
+                return false;
+            }
+            drawn = Some(inner.0);
+            render(&inner.1, window);
+            true
+        };
+
+        let id = BindingId(self.next_binding);
+        self.next_binding += 1;
+        self.bindings.push(Binding {
+            id,
+            win,
+            redraw: Arc::new(Mutex::new(redraw)),
+        });
+        id
+    }
+
+    /// Removes a binding added with [`bind`](Self::bind), leaving the window as it is.
+    /// Returns false if it was already removed. Bindings are also removed along with
+    /// their windows.
+    pub fn unbind(&mut self, id: BindingId) -> bool {
+        let len = self.bindings.len();
+        self.bindings.retain(|b| b.id != id);
+        self.bindings.len() != len
+    }
+
+    /// Redraws every bound window whose source has changed since it was last drawn,
+    /// marking what it covered before as changed. Returns true if any were redrawn.
+    pub fn update_bindings(&mut self) -> bool {
+        let start = self.profile_start();
+        // Time spent redrawing each window, if profiling.
+        let mut times = start.map(|_| vec![Duration::ZERO; self.windows.len()]);
+        let mut redrawn = false;
+        for binding in self.bindings.clone() {
+            let win_start = times.as_ref().map(|_| Instant::now());
+            let before: Vec<_> = self.windows[binding.win].footprint().collect();
+            let mut redraw = binding.redraw.lock().unwrap_or_else(|e| e.into_inner());
+            if redraw(&mut self.windows[binding.win]) {
+                self.mark_dirty(before);
+                redrawn = true;
+            }
+            if let (Some(times), Some(win_start)) = (times.as_mut(), win_start) {
+                times[binding.win] += win_start.elapsed();
+            }
+        }
+
+        self.profile_record(start, |profile, time| {
+            profile.bindings = time;
+            let times = times.unwrap_or_default();
+            profile.windows.resize(times.len(), WindowTime::default());
+            for (win, time) in profile.windows.iter_mut().zip(times) {
+                win.bindings = time;
+            }
+        });
+        redrawn
+    }
+}
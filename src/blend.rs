@@ -0,0 +1,108 @@
+//! Drawing windows of styled cells partly see-through, or mixed with what is below
+//! them.
+
+use crate::export::{DEFAULT_BG, DEFAULT_FG, colors};
+use crate::style::{Cell, Color, Style};
+use crate::{Container, Window, WindowId};
+
+/// How the colours of a window are mixed with the colours below it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum BlendMode {
+    /// The window's colours are laid over those below.
+    #[default]
+    Normal,
+    /// Colours are multiplied, which darkens.
+    Multiply,
+    /// Inverted colours are multiplied, which lightens.
+    Screen,
+}
+
+impl BlendMode {
+    /// Mixes one channel of a colour on top with one below.
+    fn apply(self, below: u8, top: u8) -> u8 {
+        let (b, t) = (below as u32, top as u32);
+        match self {
+            BlendMode::Normal => top,
+            BlendMode::Multiply => (b * t / 255) as u8,
+            BlendMode::Screen => (255 - (255 - b) * (255 - t) / 255) as u8,
+        }
+    }
+}
+
+/// How a window is blended, along with the function that blends its cells.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Blend<T> {
+    pub(crate) mode: BlendMode,
+    pub(crate) opacity: f32,
+    pub(crate) apply: fn(Option<&T>, &T, BlendMode, f32) -> T,
+}
+
+/// Returns a cell drawn over another, or over nothing, with its colours blended.
+///
+/// The character on top is shown unless it is a space, in which case the one below
+/// is shown, tinted by the background on top.
+fn blend_cells(below: Option<&Cell>, top: &Cell, mode: BlendMode, opacity: f32) -> Cell {
+    let (below_fg, below_bg) = below.map_or((DEFAULT_FG, DEFAULT_BG), |c| colors(c.style));
+    let (top_fg, top_bg) = colors(top.style);
+    let mix = |b: (u8, u8, u8), t: (u8, u8, u8)| {
+        let channel = |b: u8, t: u8| {
+            let t = mode.apply(b, t) as f32;
+            (b as f32 + (t - b as f32) * opacity).round() as u8
+        };
+        Color::Rgb(channel(b.0, t.0), channel(b.1, t.1), channel(b.2, t.2))
+    };
+
+    let (shown, fg) = match below {
+        Some(below) if top.ch == ' ' => (below, mix(below_fg, top_bg)),
+        _ => (top, mix(below_bg, top_fg)),
+    };
+    let style = Style {
+        fg: Some(fg),
+        bg: Some(mix(below_bg, top_bg)),
+        reversed: false,
+        ..shown.style
+    };
+    Cell::new(shown.ch, style)
+}
+
+impl Window<Cell> {
+    /// Returns how opaque the window is, from 0 for invisible to 1 for solid.
+    pub fn opacity(&self) -> f32 {
+        self.blend.map_or(1.0, |b| b.opacity)
+    }
+
+    /// Returns how the window's colours are mixed with those below it.
+    pub fn blend_mode(&self) -> BlendMode {
+        self.blend.map_or(BlendMode::Normal, |b| b.mode)
+    }
+
+    /// Sets how opaque the window is, from 0 for invisible to 1 for solid. Colours of
+    /// a window that isn't solid are mixed with those of the windows below it, or
+    /// the terminal's default colours if there are none.
+    pub fn set_opacity(&mut self, opacity: f32) {
+        self.set_blend(self.blend_mode(), opacity.clamp(0.0, 1.0));
+    }
+
+    /// Sets how the window's colours are mixed with those below it.
+    pub fn set_blend_mode(&mut self, mode: BlendMode) {
+        self.set_blend(mode, self.opacity());
+    }
+
+    fn set_blend(&mut self, mode: BlendMode, opacity: f32) {
+        self.blend = (mode != BlendMode::Normal || opacity < 1.0).then_some(Blend {
+            mode,
+            opacity,
+            apply: blend_cells,
+        });
+    }
+}
+
+impl Container<Cell> {
+    /// Sets the opacity of every window below the given one, such as to dim what is
+    /// behind a dialog. An opacity of 1 undoes this.
+    pub fn dim_behind(&mut self, win: WindowId, opacity: f32) {
+        for below in self.windows[..win].iter_mut() {
+            below.set_opacity(opacity);
+        }
+    }
+}
@@ -0,0 +1,197 @@
+//! Drawing at the resolution of braille dots, of which there are 2 by 4 per cell.
+
+use crate::Window;
+use crate::draw::{arc_points, clip_line, cubic_points, quad_to_cubic};
+use crate::style::{Cell, Style};
+use crate::widgets::{Widget, blank};
+
+/// Bit of a braille character for each dot, indexed by row then column.
+const DOT_BITS: [[u8; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+/// A grid of braille dots covering `wid` by `hgt` cells. Dot positions start at the
+/// top left, and positions outside the grid are ignored.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BrailleGrid {
+    wid: usize,
+    hgt: usize,
+    // Dots of each cell in rows.
+    cells: Vec<u8>,
+    style: Style,
+}
+
+impl BrailleGrid {
+    /// Create an empty grid of `wid` by `hgt` cells.
+    pub fn new(wid: usize, hgt: usize) -> Self {
+        Self {
+            wid,
+            hgt,
+            cells: vec![0; wid * hgt],
+            style: Style::new(),
+        }
+    }
+
+    /// Sets the style the grid is rendered in.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Returns the width of the grid in cells.
+    pub fn width(&self) -> usize {
+        self.wid
+    }
+
+    /// Returns the height of the grid in cells.
+    pub fn height(&self) -> usize {
+        self.hgt
+    }
+
+    /// Returns the index of the cell and the bit of the dot at a dot position.
+    fn locate(&self, x: i64, y: i64) -> Option<(usize, u8)> {
+        if x < 0 || y < 0 || x >= (self.wid * 2) as i64 || y >= (self.hgt * 4) as i64 {
+            return None;
+        }
+        let (x, y) = (x as usize, y as usize);
+        Some(((y / 4) * self.wid + x / 2, DOT_BITS[y % 4][x % 2]))
+    }
+
+    /// Sets the dot at the given dot position.
+    pub fn set_dot(&mut self, x: i64, y: i64) {
+        if let Some((idx, bit)) = self.locate(x, y) {
+            self.cells[idx] |= bit;
+        }
+    }
+
+    /// Clears the dot at the given dot position.
+    pub fn clear_dot(&mut self, x: i64, y: i64) {
+        if let Some((idx, bit)) = self.locate(x, y) {
+            self.cells[idx] &= !bit;
+        }
+    }
+
+    /// Returns true if the dot at the given dot position is set.
+    pub fn dot(&self, x: i64, y: i64) -> bool {
+        self.locate(x, y)
+            .is_some_and(|(idx, bit)| self.cells[idx] & bit != 0)
+    }
+
+    /// Clears every dot.
+    pub fn clear(&mut self) {
+        self.cells.fill(0);
+    }
+
+    /// Sets every dot on the line between two dot positions. Only the part of the line
+    /// within the grid is stepped through, so far off endpoints cost nothing extra.
+    pub fn line(&mut self, from: (i64, i64), to: (i64, i64)) {
+        if self.wid == 0 || self.hgt == 0 {
+            return;
+        }
+        let max = ((self.wid * 2 - 1) as f64, (self.hgt * 4 - 1) as f64);
+        let as_f64 = |(x, y): (i64, i64)| (x as f64, y as f64);
+        let Some((from, to)) = clip_line(as_f64(from), as_f64(to), (0.0, 0.0), max) else {
+            return;
+        };
+        // Both ends are now within the grid, so none of this can overflow.
+        let (mut x, mut y) = (from.0.round() as i64, from.1.round() as i64);
+        let to = (to.0.round() as i64, to.1.round() as i64);
+        let dx = (to.0 - x).abs();
+        let dy = -(to.1 - y).abs();
+        let sx = if x < to.0 { 1 } else { -1 };
+        let sy = if y < to.1 { 1 } else { -1 };
+        let mut err = dx + dy;
+
+        loop {
+            self.set_dot(x, y);
+            if (x, y) == to {
+                break;
+            }
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                x += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                y += sy;
+            }
+        }
+    }
+
+    /// Sets every dot on straight lines joining each dot position to the next.
+    fn path(&mut self, points: &[(i64, i64)]) {
+        for pair in points.windows(2) {
+            self.line(pair[0], pair[1]);
+        }
+    }
+
+    /// Sets every dot on a quadratic Bezier curve from `from` to `to`, bent towards
+    /// `control`.
+    pub fn quad_bezier(&mut self, from: (f64, f64), control: (f64, f64), to: (f64, f64)) {
+        self.path(&cubic_points(quad_to_cubic([from, control, to])));
+    }
+
+    /// Sets every dot on a cubic Bezier curve from `from` to `to`, bent towards the
+    /// two control points.
+    pub fn cubic_bezier(
+        &mut self,
+        from: (f64, f64),
+        c1: (f64, f64),
+        c2: (f64, f64),
+        to: (f64, f64),
+    ) {
+        self.path(&cubic_points([from, c1, c2, to]));
+    }
+
+    /// Sets every dot on part of the outline of a circle between two angles, in
+    /// radians from the right going clockwise.
+    pub fn arc(&mut self, center: (f64, f64), radius: f64, start: f64, end: f64) {
+        self.path(&arc_points(center, radius, start, end));
+    }
+
+    /// Returns the braille character for the cell at the given position, or None if
+    /// it has no dots or is outside the grid.
+    pub fn char_at(&self, x: usize, y: usize) -> Option<char> {
+        if x >= self.wid || y >= self.hgt {
+            return None;
+        }
+        match self.cells[y * self.wid + x] {
+            0 => None,
+            bits => char::from_u32(0x2800 + bits as u32),
+        }
+    }
+}
+
+impl Widget for BrailleGrid {
+    fn render(&self, win: &mut Window<Cell>, wid: usize, hgt: usize) {
+        let mut data = blank(wid, hgt, self.style);
+        for (y, row) in data.iter_mut().enumerate() {
+            for (x, cell) in row.iter_mut().enumerate() {
+                if let Some(ch) = self.char_at(x, y) {
+                    cell.ch = ch;
+                }
+            }
+        }
+        win.data = data;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_with_far_off_ends() {
+        let mut grid = BrailleGrid::new(4, 1);
+        grid.line((i64::MIN / 2, 0), (i64::MAX / 2, 0));
+        grid.line((7, i64::MIN), (7, i64::MAX));
+        assert!((0..8).all(|x| grid.dot(x, 0)));
+        assert!((0..4).all(|y| grid.dot(7, y)));
+        assert!(!grid.dot(0, 1));
+
+        let mut grid = BrailleGrid::new(4, 1);
+        grid.line((-10, 5), (5, -10));
+        assert_eq!(grid, BrailleGrid::new(4, 1));
+        grid.line((-100, -100), (100, 100));
+        assert!((0..4).all(|n| grid.dot(n, n)));
+    }
+}
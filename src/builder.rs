@@ -0,0 +1,523 @@
+//! Fluent construction of windows and containers.
+
+use crate::event::MouseMode;
+use crate::geometry::{Coord, Point, Rect};
+#[cfg(feature = "config")]
+use crate::layout::LayoutConfig;
+use crate::style::{Cell, Style, Theme};
+use crate::{Container, Window, WindowId};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::ops::Index;
+use std::time::Duration;
+
+/// Characters making up a border around a window.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BorderSet {
+    pub top_left: char,
+    pub top_right: char,
+    pub bottom_left: char,
+    pub bottom_right: char,
+    pub horizontal: char,
+    pub vertical: char,
+}
+
+impl BorderSet {
+    pub const PLAIN: Self = Self::new(['┌', '┐', '└', '┘', '─', '│']);
+    pub const ROUNDED: Self = Self::new(['╭', '╮', '╰', '╯', '─', '│']);
+    pub const DOUBLE: Self = Self::new(['╔', '╗', '╚', '╝', '═', '║']);
+    pub const THICK: Self = Self::new(['┏', '┓', '┗', '┛', '━', '┃']);
+    pub const ASCII: Self = Self::new(['+', '+', '+', '+', '-', '|']);
+
+    /// Create a border from its top left, top right, bottom left and bottom right
+    /// corners, then its horizontal and vertical lines.
+    pub const fn new([tl, tr, bl, br, h, v]: [char; 6]) -> Self {
+        Self {
+            top_left: tl,
+            top_right: tr,
+            bottom_left: bl,
+            bottom_right: br,
+            horizontal: h,
+            vertical: v,
+        }
+    }
+}
+
+impl<T: fmt::Display> Window<T> {
+    /// Draws a border over the outermost cells of the window, with a title in the
+    /// top edge if one is given. Characters are turned into cells by `cell`.
+    pub fn draw_border_with(
+        &mut self,
+        border: BorderSet,
+        title: Option<&str>,
+        cell: impl Fn(char) -> T,
+    ) {
+        let (wid, hgt) = (self.width() as Coord, self.data.len() as Coord);
+        if wid < 2 || hgt < 2 {
+            return;
+        }
+
+        for x in 1..wid - 1 {
+            self.set_cell(Point::new(x, 0), cell(border.horizontal));
+            self.set_cell(Point::new(x, hgt - 1), cell(border.horizontal));
+        }
+        for y in 1..hgt - 1 {
+            self.set_cell(Point::new(0, y), cell(border.vertical));
+            self.set_cell(Point::new(wid - 1, y), cell(border.vertical));
+        }
+        self.set_cell(Point::new(0, 0), cell(border.top_left));
+        self.set_cell(Point::new(wid - 1, 0), cell(border.top_right));
+        self.set_cell(Point::new(0, hgt - 1), cell(border.bottom_left));
+        self.set_cell(Point::new(wid - 1, hgt - 1), cell(border.bottom_right));
+
+        if let Some(title) = title {
+            for (x, ch) in (2..wid - 2).zip(title.chars()) {
+                self.set_cell(Point::new(x, 0), cell(ch));
+            }
+        }
+    }
+
+    /// Start building a window.
+    pub fn builder() -> WindowBuilder<T> {
+        WindowBuilder::default()
+    }
+}
+
+/// Builds a window, optionally with a border, title and padding around some lines
+/// of text. Created with [`Window::builder`].
+#[derive(Clone, Debug)]
+pub struct WindowBuilder<T> {
+    top_left: Point,
+    size: Option<(usize, usize)>,
+    title: Option<String>,
+    border: Option<BorderSet>,
+    padding: usize,
+    lines: Vec<Vec<T>>,
+    resizable: bool,
+    min_size: (usize, usize),
+    max_size: Option<(usize, usize)>,
+}
+
+impl<T> Default for WindowBuilder<T> {
+    fn default() -> Self {
+        Self {
+            top_left: Point::new(0, 0),
+            size: None,
+            title: None,
+            border: None,
+            padding: 0,
+            lines: Vec::new(),
+            resizable: false,
+            min_size: (1, 1),
+            max_size: None,
+        }
+    }
+}
+
+impl<T: fmt::Display + From<char> + Clone> WindowBuilder<T> {
+    /// Sets the top left of the window.
+    pub fn at(mut self, top_left: Point) -> Self {
+        self.top_left = top_left;
+        self
+    }
+
+    /// Sets the size of the window including its border and padding. If not set, it
+    /// is made just big enough for its contents.
+    pub fn size(mut self, wid: usize, hgt: usize) -> Self {
+        self.size = Some((wid, hgt));
+        self
+    }
+
+    /// Sets the top left and size of the window to those of the rectangle.
+    pub fn rect(self, rect: Rect) -> Self {
+        self.at(rect.top_left).size(rect.wid, rect.hgt)
+    }
+
+    /// Sets the title of the window, which is shown in its border if it has one.
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Gives the window a border.
+    pub fn border(mut self, border: BorderSet) -> Self {
+        self.border = Some(border);
+        self
+    }
+
+    /// Sets the number of blank cells between the border and the contents.
+    pub fn padding(mut self, padding: usize) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Sets the contents of the window to some lines of text.
+    pub fn lines<S: AsRef<str>>(mut self, lines: impl IntoIterator<Item = S>) -> Self {
+        self.lines = lines
+            .into_iter()
+            .map(|l| l.as_ref().chars().map(T::from).collect())
+            .collect();
+        self
+    }
+
+    /// Sets the contents of the window to some rows of cells.
+    pub fn rows(mut self, rows: impl IntoIterator<Item = Vec<T>>) -> Self {
+        self.lines = rows.into_iter().collect();
+        self
+    }
+
+    /// Lets the window be resized with the mouse.
+    pub fn resizable(mut self) -> Self {
+        self.resizable = true;
+        self
+    }
+
+    /// Sets the smallest size the window can be resized to with the mouse.
+    pub fn min_size(mut self, wid: usize, hgt: usize) -> Self {
+        self.min_size = (wid, hgt);
+        self
+    }
+
+    /// Sets the largest size the window can be resized to with the mouse.
+    pub fn max_size(mut self, wid: usize, hgt: usize) -> Self {
+        self.max_size = Some((wid, hgt));
+        self
+    }
+
+    /// Builds the window, with its contents cut off if they don't fit.
+    pub fn build(self) -> Window<T> {
+        let inset = self.border.is_some() as usize + self.padding;
+        let content_wid = self.lines.iter().map(|l| l.len()).max().unwrap_or(0);
+        let (wid, hgt) = self
+            .size
+            .unwrap_or((content_wid + inset * 2, self.lines.len() + inset * 2));
+
+        let mut win = Window::new(self.top_left);
+        win.data = vec![vec![T::from(' '); wid]; hgt];
+        let inner = (wid.saturating_sub(inset * 2), hgt.saturating_sub(inset * 2));
+        for (row, line) in win.data[inset.min(hgt)..]
+            .iter_mut()
+            .zip(self.lines)
+            .take(inner.1)
+        {
+            for (cell, value) in row[inset.min(wid)..].iter_mut().zip(line).take(inner.0) {
+                *cell = value;
+            }
+        }
+
+        if let Some(border) = self.border {
+            win.draw_border_with(border, self.title.as_deref(), T::from);
+        }
+        win.title = self.title;
+        win.inset = inset;
+        win.resizable = self.resizable;
+        win.min_size = self.min_size;
+        win.max_size = self.max_size;
+
+        win
+    }
+}
+
+/// Ids of the windows given names when building a container, looked up by name.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Handles {
+    ids: HashMap<String, WindowId>,
+}
+
+impl Handles {
+    /// Returns the id of the window with the given name.
+    pub fn get(&self, name: &str) -> Option<WindowId> {
+        self.ids.get(name).copied()
+    }
+
+    /// Returns the name and id of every named window.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, WindowId)> {
+        self.ids.iter().map(|(name, &id)| (name.as_str(), id))
+    }
+}
+
+impl Index<&str> for Handles {
+    type Output = WindowId;
+
+    /// Returns the id of the window with the given name, panicking if there is none.
+    fn index(&self, name: &str) -> &WindowId {
+        match self.ids.get(name) {
+            Some(id) => id,
+            None => panic!("no window named {name:?}"),
+        }
+    }
+}
+
+/// A window waiting to be added by a [`ContainerBuilder`].
+struct Entry<T: fmt::Display> {
+    name: Option<String>,
+    z: i32,
+    win: Window<T>,
+}
+
+/// Something done to every window of a [`ContainerBuilder`].
+type WindowFn<T> = Box<dyn Fn(&mut Window<T>)>;
+
+/// Describes a whole scene of windows at once. Created with [`Container::builder`].
+///
+/// Windows are stacked by their depth, set with [`z`](Self::z), with deeper windows
+/// drawn first and those of equal depth in the order they were added.
+pub struct ContainerBuilder<T: fmt::Display> {
+    entries: Vec<Entry<T>>,
+    screen: (u16, u16),
+    tick_rate: Option<Duration>,
+    max_fps: Option<Option<u32>>,
+    mouse: Option<Option<MouseMode>>,
+    scroll_lines: Option<usize>,
+    focus: Option<String>,
+    // Applied to every window when the container is built.
+    themes: Vec<WindowFn<T>>,
+}
+
+impl<T: fmt::Display> Default for ContainerBuilder<T> {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+            screen: (80, 24),
+            tick_rate: None,
+            max_fps: None,
+            mouse: None,
+            scroll_lines: None,
+            focus: None,
+            themes: Vec::new(),
+        }
+    }
+}
+
+impl<T: fmt::Display> Container<T> {
+    /// Start describing a container.
+    pub fn builder() -> ContainerBuilder<T> {
+        ContainerBuilder::default()
+    }
+}
+
+impl<T: fmt::Display> ContainerBuilder<T> {
+    /// Adds a window.
+    pub fn window(mut self, win: Window<T>) -> Self {
+        self.entries.push(Entry {
+            name: None,
+            z: 0,
+            win,
+        });
+        self
+    }
+
+    /// Adds a window whose id can be looked up by name once built.
+    pub fn named(mut self, name: impl Into<String>, win: Window<T>) -> Self {
+        self.entries.push(Entry {
+            name: Some(name.into()),
+            z: 0,
+            win,
+        });
+        self
+    }
+
+    /// Sets the depth of the window added last. Windows with a higher depth are drawn
+    /// on top.
+    pub fn z(mut self, z: i32) -> Self {
+        if let Some(entry) = self.entries.last_mut() {
+            entry.z = z;
+        }
+        self
+    }
+
+    /// Sets the size of the screen, which windows from layouts are sized for.
+    pub fn screen_size(mut self, wid: u16, hgt: u16) -> Self {
+        self.screen = (wid, hgt);
+        self
+    }
+
+    /// Sets how often [`Container::run`] produces a tick event.
+    pub fn tick_rate(mut self, tick_rate: Duration) -> Self {
+        self.tick_rate = Some(tick_rate);
+        self
+    }
+
+    /// Sets the most frames [`Container::run`] presents per second, or None for no
+    /// limit.
+    pub fn max_fps(mut self, fps: Option<u32>) -> Self {
+        self.max_fps = Some(fps);
+        self
+    }
+
+    /// Sets the mouse events the backend reports, or None to not use the mouse.
+    pub fn mouse(mut self, mode: Option<MouseMode>) -> Self {
+        self.mouse = Some(mode);
+        self
+    }
+
+    /// Sets the number of rows scrolled per tick of the mouse wheel.
+    pub fn scroll_lines(mut self, lines: usize) -> Self {
+        self.scroll_lines = Some(lines);
+        self
+    }
+
+    /// Focuses the window with the given name.
+    pub fn focus(mut self, name: impl Into<String>) -> Self {
+        self.focus = Some(name.into());
+        self
+    }
+
+    /// Calls `f` on every window when the container is built, in the order these are
+    /// given.
+    pub fn each(mut self, f: impl Fn(&mut Window<T>) + 'static) -> Self {
+        self.themes.push(Box::new(f));
+        self
+    }
+
+    /// Builds the container, returning it along with the ids of the named windows.
+    pub fn build(mut self) -> (Container<T>, Handles) {
+        let mut container = Container::new();
+        container.set_screen_size(self.screen.0, self.screen.1);
+        if let Some(tick_rate) = self.tick_rate {
+            container.tick_rate = tick_rate;
+        }
+        if let Some(fps) = self.max_fps {
+            container.max_fps = fps;
+        }
+        if let Some(mode) = self.mouse {
+            container.mouse = mode;
+        }
+        if let Some(lines) = self.scroll_lines {
+            container.scroll_lines = lines;
+        }
+
+        let mut handles = Handles::default();
+        self.entries.sort_by_key(|e| e.z);
+        for mut entry in self.entries {
+            for theme in self.themes.iter() {
+                theme(&mut entry.win);
+            }
+            let id = container.add_win(entry.win);
+            if let Some(name) = entry.name {
+                handles.ids.insert(name, id);
+            }
+        }
+
+        if let Some(name) = self.focus {
+            container.set_focus(handles.get(&name));
+        }
+        (container, handles)
+    }
+}
+
+impl ContainerBuilder<Cell> {
+    /// Applies a style under that of every cell of every window, so that anything the
+    /// cells leave unset is taken from it.
+    pub fn theme(self, style: Style) -> Self {
+        self.each(move |win| {
+            for cell in win.data.iter_mut().flatten() {
+                cell.style = style.patch(cell.style);
+            }
+        })
+    }
+
+    /// Applies the text style of a theme under that of every cell of every window,
+    /// such as the one [`Capabilities::theme`](crate::Capabilities::theme) picks for
+    /// the terminal's background.
+    pub fn with_theme(self, theme: Theme) -> Self {
+        self.theme(theme.text)
+    }
+
+    /// Adds every window of a layout, named as in the layout and sized for the
+    /// screen size given so far.
+    #[cfg(feature = "config")]
+    pub fn layout(mut self, config: &LayoutConfig) -> Self {
+        for w in config.windows.iter() {
+            let win = w.build(self.screen);
+            self = self.named(w.name.clone(), win);
+        }
+        self
+    }
+}
+
+impl<T: fmt::Display + From<char>> FromIterator<String> for Window<T> {
+    /// Creates a window at (0, 0) with a row for each line of text.
+    fn from_iter<I: IntoIterator<Item = String>>(iter: I) -> Self {
+        iter.into_iter()
+            .map(|line| line.chars().map(T::from).collect::<Vec<_>>())
+            .collect()
+    }
+}
+
+impl<T: fmt::Display> FromIterator<Vec<T>> for Window<T> {
+    /// Creates a window at (0, 0) from rows of cells.
+    fn from_iter<I: IntoIterator<Item = Vec<T>>>(iter: I) -> Self {
+        let mut win = Window::new(Point::new(0, 0));
+        win.data = iter.into_iter().collect();
+        win
+    }
+}
+
+impl<T: fmt::Display> Extend<Window<T>> for Container<T> {
+    /// Adds each window on top of the others.
+    fn extend<I: IntoIterator<Item = Window<T>>>(&mut self, iter: I) {
+        for win in iter {
+            self.add_win(win);
+        }
+    }
+}
+
+impl<T: fmt::Display> Extend<(Point, T)> for Container<T> {
+    /// Adds cells at the given positions on top of every window. Each run of cells next
+    /// to each other in a row becomes a window of its own, so scattered cells add one
+    /// window each, with ids in order of their rows and then columns. A later cell at
+    /// the same position replaces an earlier one. To add the cells as a single window,
+    /// build one with [`Window::builder`] instead.
+    fn extend<I: IntoIterator<Item = (Point, T)>>(&mut self, iter: I) {
+        let cells: BTreeMap<(Coord, Coord), T> =
+            iter.into_iter().map(|(p, v)| ((p.y, p.x), v)).collect();
+        let mut run: Option<Window<T>> = None;
+
+        for ((y, x), value) in cells {
+            if let Some(win) = &mut run {
+                if win.top_left.y == y && win.top_left.x + win.data[0].len() as Coord == x {
+                    win.data[0].push(value);
+                    continue;
+                }
+                self.add_win(run.take().unwrap());
+            }
+            let mut win = Window::new(Point::new(x, y));
+            win.data.push(vec![value]);
+            run = Some(win);
+        }
+
+        if let Some(win) = run {
+            self.add_win(win);
+        }
+    }
+}
+
+/// Builds a window from lines of text, optionally with its top left and title.
+///
+/// ```ignore
+/// let win: Window<char> = window! { at: (2, 3), ["+--+", "|hi|", "+--+"] };
+/// ```
+#[macro_export]
+macro_rules! window {
+    ($(at: ($x:expr, $y:expr),)? $(title: $title:expr,)? [$($line:expr),* $(,)?] $(,)?) => {{
+        let builder = $crate::Window::builder();
+        $(let builder = builder.at($crate::geometry::Point::new($x, $y));)?
+        $(let builder = builder.title($title);)?
+        builder.lines::<&str>([$($line),*]).build()
+    }};
+}
+
+/// Builds a container holding the given windows, the first at the bottom.
+///
+/// ```ignore
+/// let container = container![window!(["a"]), window! { at: (1, 0), ["b"] }];
+/// ```
+#[macro_export]
+macro_rules! container {
+    ($($win:expr),* $(,)?) => {{
+        let mut container = $crate::Container::new();
+        $(container.add_win($win);)*
+        container
+    }};
+}
@@ -0,0 +1,117 @@
+//! Messages published by one part of an application for others to react to.
+
+use crate::Container;
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+/// Identifies a subscription added with [`Container::subscribe`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SubscriptionId(u64);
+
+/// A subscriber to a topic, given each message and the container.
+type Subscriber<T> = Arc<Mutex<dyn FnMut(&dyn Any, &mut Container<T>) + Send>>;
+
+/// Messages waiting to be dispatched and the subscribers of each topic. Clones of a
+/// container share subscribers.
+pub(crate) struct Bus<T: fmt::Display> {
+    next: u64,
+    queue: Vec<(String, Arc<dyn Any + Send + Sync>)>,
+    topics: HashMap<String, Vec<(SubscriptionId, Subscriber<T>)>>,
+}
+
+impl<T: fmt::Display> Default for Bus<T> {
+    fn default() -> Self {
+        Self {
+            next: 0,
+            queue: Vec::new(),
+            topics: HashMap::new(),
+        }
+    }
+}
+
+impl<T: fmt::Display> Clone for Bus<T> {
+    fn clone(&self) -> Self {
+        Self {
+            next: self.next,
+            queue: self.queue.clone(),
+            topics: self.topics.clone(),
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Debug for Bus<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Bus")
+            .field("queued", &self.queue.len())
+            .field("topics", &self.topics.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl<T: fmt::Display> Container<T> {
+    /// Adds a subscriber to a topic, which is given every message of type `M`
+    /// published to it along with the container. Returns an id to remove it with.
+    pub fn subscribe<M: Any>(
+        &mut self,
+        topic: &str,
+        mut f: impl FnMut(&M, &mut Container<T>) + Send + 'static,
+    ) -> SubscriptionId {
+        let id = SubscriptionId(self.bus.next);
+        self.bus.next += 1;
+        let subscriber: Subscriber<T> = Arc::new(Mutex::new(move |msg: &dyn Any, c: &mut Self| {
+            if let Some(msg) = msg.downcast_ref::<M>() {
+                f(msg, c);
+            }
+        }));
+        self.bus
+            .topics
+            .entry(topic.to_string())
+            .or_default()
+            .push((id, subscriber));
+        id
+    }
+
+    /// Removes a subscriber added with [`subscribe`](Self::subscribe). Returns false if
+    /// it was already removed.
+    pub fn unsubscribe(&mut self, id: SubscriptionId) -> bool {
+        let mut found = false;
+        for subscribers in self.bus.topics.values_mut() {
+            let len = subscribers.len();
+            subscribers.retain(|(other, _)| *other != id);
+            found |= subscribers.len() != len;
+        }
+        self.bus
+            .topics
+            .retain(|_, subscribers| !subscribers.is_empty());
+        found
+    }
+
+    /// Publishes a message to a topic. It is given to the topic's subscribers by the
+    /// next [`dispatch_messages`](Self::dispatch_messages).
+    pub fn publish<M: Any + Send + Sync>(&mut self, topic: &str, msg: M) {
+        self.bus.queue.push((topic.to_string(), Arc::new(msg)));
+    }
+
+    /// Gives every published message to the subscribers of its topic, in the order
+    /// they were published. This is done on every tick by [`run`](Self::run).
+    /// Messages published by subscribers are left for the next dispatch. Returns how
+    /// many messages were dispatched.
+    pub fn dispatch_messages(&mut self) -> usize {
+        let queue = std::mem::take(&mut self.bus.queue);
+        let count = queue.len();
+
+        for (topic, msg) in queue {
+            let subscribers: Vec<Subscriber<T>> = match self.bus.topics.get(&topic) {
+                Some(subscribers) => subscribers.iter().map(|(_, s)| s.clone()).collect(),
+                None => continue,
+            };
+            for subscriber in subscribers {
+                let mut subscriber = subscriber.lock().unwrap_or_else(|e| e.into_inner());
+                subscriber(&*msg, self);
+            }
+        }
+        count
+    }
+}
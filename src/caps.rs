@@ -0,0 +1,315 @@
+//! Working out what the terminal the program is running in can do.
+
+use crate::BorderSet;
+use crate::style::{Color, ColorDepth, Theme};
+use std::env;
+use std::process::{Command, Stdio};
+
+/// What a terminal can do. A [`Terminal`](crate::terminal::Terminal) works this out
+/// when it is created, and uses it to decide how to write to the terminal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Capabilities {
+    /// How many colours can be shown. Colours beyond these are replaced with the
+    /// closest that can be.
+    pub color: ColorDepth,
+    /// Whether characters beyond ASCII, such as box drawing characters, can be shown.
+    pub unicode: bool,
+    /// Whether the mouse can be used.
+    pub mouse: bool,
+    /// Whether the terminal can be told to show a frame only once it has all been
+    /// written, so it never shows half of one.
+    pub synchronized_output: bool,
+    /// Whether sixel graphics can be shown.
+    pub sixel: bool,
+    /// Whether images can be shown with the kitty graphics protocol.
+    pub kitty_graphics: bool,
+    /// Whether keys can be reported with the kitty keyboard protocol.
+    pub kitty_keyboard: bool,
+    /// Colour behind the text, if known.
+    pub background: Option<(u8, u8, u8)>,
+}
+
+impl Default for Capabilities {
+    /// What most terminals in use today can do.
+    fn default() -> Self {
+        Self {
+            color: ColorDepth::Ansi256,
+            unicode: true,
+            mouse: true,
+            synchronized_output: false,
+            sixel: false,
+            kitty_graphics: false,
+            kitty_keyboard: false,
+            background: None,
+        }
+    }
+}
+
+impl Capabilities {
+    /// Guesses what the terminal can do from the environment and its terminfo entry.
+    pub fn from_env() -> Self {
+        let var = |name: &str| env::var(name).unwrap_or_default();
+        let term = var("TERM");
+        let program = var("TERM_PROGRAM");
+        // Windows consoles don't set TERM, but understand the same sequences.
+        let windows = cfg!(windows) && term.is_empty();
+        let dumb = (term.is_empty() && !windows) || term == "dumb";
+
+        let color = if dumb || env::var_os("NO_COLOR").is_some() {
+            ColorDepth::Mono
+        } else if matches!(var("COLORTERM").as_str(), "truecolor" | "24bit")
+            || term.ends_with("direct")
+            || ["iTerm.app", "WezTerm", "vscode", "ghostty"].contains(&program.as_str())
+            || windows
+            || ["kitty", "alacritty", "foot", "ghostty"]
+                .iter()
+                .any(|name| term.contains(name))
+        {
+            ColorDepth::TrueColor
+        } else if term.contains("256color") {
+            ColorDepth::Ansi256
+        } else {
+            match terminfo_colors() {
+                Some(n) if n >= 1 << 24 => ColorDepth::TrueColor,
+                Some(n) if n >= 256 => ColorDepth::Ansi256,
+                Some(n) if n >= 8 => ColorDepth::Ansi16,
+                Some(_) => ColorDepth::Mono,
+                None => ColorDepth::Ansi16,
+            }
+        };
+
+        // The first of these that is set decides the character set.
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .iter()
+            .map(|name| var(name))
+            .find(|value| !value.is_empty())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let unicode = windows || !dumb && (locale.contains("utf-8") || locale.contains("utf8"));
+
+        let sixel = ["sixel", "mlterm", "foot", "contour", "yaft"]
+            .iter()
+            .any(|name| term.contains(name));
+        let kitty_graphics = env::var_os("KITTY_WINDOW_ID").is_some()
+            || term.contains("kitty")
+            || term.contains("ghostty")
+            || program == "WezTerm";
+
+        // Some terminals give the palette indices of their colours as "fg;bg".
+        let background = var("COLORFGBG")
+            .rsplit(';')
+            .next()
+            .and_then(|bg| bg.parse().ok())
+            .map(|bg| Color::Indexed(bg).to_rgb());
+
+        Self {
+            color,
+            unicode,
+            // The Linux console doesn't report the mouse the way other terminals do.
+            mouse: !dumb && term != "linux",
+            synchronized_output: false,
+            sixel,
+            kitty_graphics,
+            // Only known once the terminal replies to a query.
+            kitty_keyboard: false,
+            background,
+        }
+    }
+
+    /// Updates what the terminal can do from its replies to
+    /// [`QUERIES`](Self::QUERIES).
+    pub(crate) fn apply_replies(&mut self, replies: &[u8]) {
+        if let Some(background) = background_reply(replies) {
+            self.background = Some(background);
+        }
+        for reply in csi_replies(replies) {
+            if let Some(attrs) = reply.strip_prefix("?").and_then(|r| r.strip_suffix('c')) {
+                // Primary device attributes, listing the features supported.
+                if attrs.split(';').skip(1).any(|attr| attr == "4") {
+                    self.sixel = true;
+                }
+            } else if reply.starts_with('?') && reply.ends_with('u') {
+                // The keyboard flags in use, which only come back if the kitty
+                // keyboard protocol is supported.
+                self.kitty_keyboard = true;
+            } else if let Some(mode) = reply.strip_prefix("?2026;") {
+                // Whether synchronized output is recognised, where 0 means it isn't.
+                self.synchronized_output = matches!(mode, "1$y" | "2$y");
+            }
+        }
+    }
+
+    /// Queries whose replies [`apply_replies`](Self::apply_replies) understands.
+    pub(crate) const QUERIES: &str = "\x1b[?2026$p\x1b[?u\x1b]11;?\x07";
+
+    /// Returns true if the background is dark, or isn't known, as it is in most
+    /// terminals.
+    pub fn is_dark_background(&self) -> bool {
+        self.background.is_none_or(|(r, g, b)| {
+            let luma = 0.299 * r as f64 + 0.587 * g as f64 + 0.114 * b as f64;
+            luma < 128.0
+        })
+    }
+
+    /// Returns a theme readable on the terminal's background.
+    pub fn theme(&self) -> Theme {
+        if self.is_dark_background() {
+            Theme::DARK
+        } else {
+            Theme::LIGHT
+        }
+    }
+
+    /// Returns the border characters the terminal can show.
+    pub fn border_set(&self) -> BorderSet {
+        if self.unicode {
+            BorderSet::PLAIN
+        } else {
+            BorderSet::ASCII
+        }
+    }
+
+    /// Returns the closest colour the terminal can show, or None if it can't show
+    /// colours.
+    pub fn color(&self, color: Color) -> Option<Color> {
+        color.downgrade(self.color)
+    }
+}
+
+/// Asks terminfo how many colours the terminal has.
+fn terminfo_colors() -> Option<u32> {
+    let out = Command::new("tput")
+        .arg("colors")
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&out.stdout).trim().parse().ok()
+}
+
+/// Returns the body and final byte of each control sequence in a terminal's replies.
+fn csi_replies(bytes: &[u8]) -> impl Iterator<Item = String> + '_ {
+    let text = String::from_utf8_lossy(bytes).into_owned();
+    let mut replies = Vec::new();
+    let mut rest = text.as_str();
+    while let Some(start) = rest.find("\x1b[") {
+        rest = &rest[start + 2..];
+        // Parameters and intermediate bytes come before the final byte.
+        match rest.find(|c: char| ('\x40'..='\x7e').contains(&c) && c != '[') {
+            Some(end) => {
+                replies.push(rest[..=end].to_string());
+                rest = &rest[end + 1..];
+            }
+            None => break,
+        }
+    }
+    replies.into_iter()
+}
+
+/// Returns the background colour from a terminal's reply to OSC 11, which looks like
+/// `ESC ] 11 ; rgb:RRRR/GGGG/BBBB` ended by BEL or ST, with one to four hex digits
+/// per component.
+fn background_reply(bytes: &[u8]) -> Option<(u8, u8, u8)> {
+    let text = String::from_utf8_lossy(bytes);
+    let start = text.find("\x1b]11;rgb:")? + "\x1b]11;rgb:".len();
+    let rest = &text[start..];
+    let end = rest.find(['\x07', '\x1b'])?;
+
+    let mut parts = rest[..end].split('/').map(|part| {
+        let value = u32::from_str_radix(part, 16).ok()?;
+        let max = (1u32 << (4 * part.len().clamp(1, 4))) - 1;
+        Some((value * 255 / max) as u8)
+    });
+    Some((parts.next()??, parts.next()??, parts.next()??))
+}
+
+/// Returns where the reply to a request for the primary device attributes ends, if
+/// it has been read. Every terminal replies to this, so it is sent after other
+/// queries to know when to stop waiting for them.
+pub(crate) fn attributes_end(bytes: &[u8]) -> Option<usize> {
+    let mut idx = 0;
+    while let Some(start) = bytes[idx..].windows(3).position(|w| w == b"\x1b[?") {
+        let body = idx + start + 3;
+        let len = bytes[body..]
+            .iter()
+            .position(|b| !(b.is_ascii_digit() || *b == b';'))?;
+        if bytes[body + len] == b'c' {
+            return Some(body + len + 1);
+        }
+        idx = body;
+    }
+    None
+}
+
+/// Rewrites the colours of the SGR sequences in `text` into ones a terminal with the
+/// given colour depth can show, removing them if it can't show any.
+pub(crate) fn downgrade(text: &str, depth: ColorDepth) -> String {
+    if depth == ColorDepth::TrueColor || !text.contains("\x1b[") {
+        return text.to_string();
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("\x1b[") {
+        out.push_str(&rest[..start]);
+        rest = &rest[start + 2..];
+        let Some(end) = rest.find(|c: char| !(c.is_ascii_digit() || c == ';')) else {
+            out.push_str("\x1b[");
+            break;
+        };
+        if rest[end..].starts_with('m') {
+            out.push_str("\x1b[");
+            out.push_str(&downgrade_params(&rest[..end], depth));
+        } else {
+            out.push_str("\x1b[");
+            out.push_str(&rest[..end]);
+        }
+        rest = &rest[end..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Rewrites the parameters of a single SGR sequence.
+fn downgrade_params(params: &str, depth: ColorDepth) -> String {
+    let params: Vec<u8> = params.split(';').map(|p| p.parse().unwrap_or(0)).collect();
+    let mut out: Vec<String> = Vec::new();
+    let mut iter = params.into_iter();
+
+    while let Some(param) = iter.next() {
+        let (color, fg) = match param {
+            38 | 48 => {
+                let color = match iter.next() {
+                    Some(2) => {
+                        let mut next = || iter.next().unwrap_or(0);
+                        Color::Rgb(next(), next(), next())
+                    }
+                    Some(5) => Color::Indexed(iter.next().unwrap_or(0)),
+                    _ => continue,
+                };
+                (color, param == 38)
+            }
+            30..=37 | 40..=47 | 90..=97 | 100..=107 if depth == ColorDepth::Mono => continue,
+            _ => {
+                out.push(param.to_string());
+                continue;
+            }
+        };
+
+        let base = if fg { 30 } else { 40 };
+        match color.downgrade(depth) {
+            Some(Color::Indexed(i)) if depth == ColorDepth::Ansi16 => {
+                let code = if i < 8 { base + i } else { base + 60 + i - 8 };
+                out.push(code.to_string());
+            }
+            Some(Color::Indexed(i)) => out.push(format!("{};5;{i}", base + 8)),
+            Some(Color::Rgb(r, g, b)) => out.push(format!("{};2;{r};{g};{b}", base + 8)),
+            _ => {}
+        }
+    }
+
+    if out.is_empty() {
+        String::from("0")
+    } else {
+        out.join(";")
+    }
+}
@@ -0,0 +1,191 @@
+//! Sparse storage of values by position, in square chunks that are only allocated
+//! where something is stored.
+
+use crate::geometry::{Coord, Point};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Width and height of a chunk.
+const CHUNK_SIZE: Coord = 32;
+/// Number of positions in a chunk.
+const CHUNK_AREA: usize = (CHUNK_SIZE * CHUNK_SIZE) as usize;
+
+/// A square of positions, with the number of them holding a value.
+#[derive(Clone)]
+struct Chunk<V> {
+    values: Box<[Option<V>]>,
+    len: usize,
+}
+
+impl<V> Chunk<V> {
+    fn new() -> Self {
+        Self {
+            values: (0..CHUNK_AREA).map(|_| None).collect(),
+            len: 0,
+        }
+    }
+}
+
+/// Returns the chunk a position is in and its index in the chunk.
+fn locate(p: Point) -> (Point, usize) {
+    let chunk = Point::new(p.x.div_euclid(CHUNK_SIZE), p.y.div_euclid(CHUNK_SIZE));
+    let x = p.x.rem_euclid(CHUNK_SIZE);
+    let y = p.y.rem_euclid(CHUNK_SIZE);
+    (chunk, (y * CHUNK_SIZE + x) as usize)
+}
+
+/// Returns the position of an index in a chunk.
+fn position(chunk: Point, idx: usize) -> Point {
+    let idx = idx as Coord;
+    Point::new(
+        chunk.x * CHUNK_SIZE + idx % CHUNK_SIZE,
+        chunk.y * CHUNK_SIZE + idx / CHUNK_SIZE,
+    )
+}
+
+/// A map from positions to values, such as the buffer of a container. Values are
+/// kept in 32 by 32 chunks allocated as they are needed, so that a large, sparse
+/// scene only costs memory where something is drawn and neighbouring positions are
+/// found without hashing each one.
+#[derive(Clone)]
+pub struct ChunkMap<V> {
+    chunks: HashMap<Point, Chunk<V>>,
+    len: usize,
+}
+
+impl<V> Default for ChunkMap<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V> ChunkMap<V> {
+    /// Create a new empty map.
+    pub fn new() -> Self {
+        Self {
+            chunks: HashMap::new(),
+            len: 0,
+        }
+    }
+
+    /// Returns the number of positions holding a value.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if no position holds a value.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of positions there is room for in the allocated chunks.
+    pub fn capacity(&self) -> usize {
+        self.chunks.len() * CHUNK_AREA
+    }
+
+    /// Returns the number of chunks allocated.
+    pub fn chunk_count(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Returns the value at the position, if any.
+    pub fn get(&self, p: &Point) -> Option<&V> {
+        let (chunk, idx) = locate(*p);
+        self.chunks.get(&chunk)?.values[idx].as_ref()
+    }
+
+    /// Returns the value at the position mutably, if any.
+    pub fn get_mut(&mut self, p: &Point) -> Option<&mut V> {
+        let (chunk, idx) = locate(*p);
+        self.chunks.get_mut(&chunk)?.values[idx].as_mut()
+    }
+
+    /// Returns true if the position holds a value.
+    pub fn contains_key(&self, p: &Point) -> bool {
+        self.get(p).is_some()
+    }
+
+    /// Stores a value at the position, returning the one it replaced, if any.
+    pub fn insert(&mut self, p: Point, value: V) -> Option<V> {
+        let (chunk, idx) = locate(p);
+        let chunk = self.chunks.entry(chunk).or_insert_with(Chunk::new);
+        let prev = chunk.values[idx].replace(value);
+        if prev.is_none() {
+            chunk.len += 1;
+            self.len += 1;
+        }
+        prev
+    }
+
+    /// Removes the value at the position, returning it if there was one. Chunks left
+    /// empty are freed.
+    pub fn remove(&mut self, p: &Point) -> Option<V> {
+        let (pos, idx) = locate(*p);
+        let chunk = self.chunks.get_mut(&pos)?;
+        let prev = chunk.values[idx].take()?;
+        chunk.len -= 1;
+        self.len -= 1;
+        if chunk.len == 0 {
+            self.chunks.remove(&pos);
+        }
+        Some(prev)
+    }
+
+    /// Removes every value, freeing the chunks.
+    pub fn clear(&mut self) {
+        self.chunks.clear();
+        self.len = 0;
+    }
+
+    /// Returns an iterator over every position holding a value, along with the value,
+    /// a chunk at a time.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &V)> + '_ {
+        self.chunks.iter().flat_map(|(&chunk, values)| {
+            values
+                .values
+                .iter()
+                .enumerate()
+                .filter_map(move |(idx, value)| Some((position(chunk, idx), value.as_ref()?)))
+        })
+    }
+
+    /// Returns an iterator over every position holding a value.
+    pub fn keys(&self) -> impl Iterator<Item = Point> + '_ {
+        self.iter().map(|(p, _)| p)
+    }
+
+    /// Returns an iterator over every value.
+    pub fn values(&self) -> impl Iterator<Item = &V> + '_ {
+        self.iter().map(|(_, value)| value)
+    }
+}
+
+impl<V: PartialEq> PartialEq for ChunkMap<V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().all(|(p, value)| other.get(&p) == Some(value))
+    }
+}
+
+impl<V: Eq> Eq for ChunkMap<V> {}
+
+impl<V: fmt::Debug> fmt::Debug for ChunkMap<V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<V> FromIterator<(Point, V)> for ChunkMap<V> {
+    fn from_iter<I: IntoIterator<Item = (Point, V)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<V> Extend<(Point, V)> for ChunkMap<V> {
+    fn extend<I: IntoIterator<Item = (Point, V)>>(&mut self, iter: I) {
+        for (p, value) in iter {
+            self.insert(p, value);
+        }
+    }
+}
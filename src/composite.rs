@@ -0,0 +1,137 @@
+//! Deciding the value of cells where windows overlap.
+
+use crate::Window;
+use crate::draw::Merge;
+use crate::style::Cell;
+use std::fmt;
+use std::sync::Arc;
+
+/// Decides what each cell of the buffer shows where a window is drawn over
+/// another, when set with [`Container::set_compositor`](crate::Container::set_compositor).
+/// By default the window on top replaces what is below.
+///
+/// Any `Fn(&T, &T) -> Merge<T>` closure is a compositor.
+pub trait Compositor<T> {
+    /// Decides what to do with the cell `top` of a window drawn over `below`.
+    fn composite(&self, below: &T, top: &T) -> Merge<T>;
+}
+
+impl<T, F: Fn(&T, &T) -> Merge<T>> Compositor<T> for F {
+    fn composite(&self, below: &T, top: &T) -> Merge<T> {
+        self(below, top)
+    }
+}
+
+/// The compositor of a container, if it has one.
+pub(crate) struct Hook<T>(pub(crate) Option<Arc<dyn Compositor<T> + Send + Sync>>);
+
+impl<T: fmt::Display> Hook<T> {
+    /// Decides what to do with the cell `top` of a window drawn over `below`, if
+    /// anything is below it. Windows that are blended are blended instead.
+    pub(crate) fn merge(&self, below: Option<&T>, top: &T, win: &Window<T>) -> Merge<T> {
+        if let Some(blend) = &win.blend {
+            return Merge::Blend((blend.apply)(below, top, blend.mode, blend.opacity));
+        }
+        match (below, &self.0) {
+            (Some(below), Some(compositor)) => compositor.composite(below, top),
+            _ => Merge::Replace,
+        }
+    }
+}
+
+impl<T> Clone for Hook<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T> Default for Hook<T> {
+    fn default() -> Self {
+        Self(None)
+    }
+}
+
+impl<T> fmt::Debug for Hook<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(_) => write!(f, "Some(Compositor)"),
+            None => write!(f, "None"),
+        }
+    }
+}
+
+/// Lines coming out of the centre of a box drawing character towards its left, right,
+/// top and bottom edges.
+pub(crate) fn box_arms(ch: char) -> Option<[bool; 4]> {
+    Some(match ch {
+        '─' | '━' | '═' => [true, true, false, false],
+        '│' | '┃' | '║' => [false, false, true, true],
+        '┌' | '┏' | '╔' | '╭' => [false, true, false, true],
+        '┐' | '┓' | '╗' | '╮' => [true, false, false, true],
+        '└' | '┗' | '╚' | '╰' => [false, true, true, false],
+        '┘' | '┛' | '╝' | '╯' => [true, false, true, false],
+        '├' | '┣' | '╠' => [false, true, true, true],
+        '┤' | '┫' | '╣' => [true, false, true, true],
+        '┬' | '┳' | '╦' => [true, true, false, true],
+        '┴' | '┻' | '╩' => [true, true, true, false],
+        '┼' | '╋' | '╬' => [true, true, true, true],
+        _ => return None,
+    })
+}
+
+/// Box drawing characters of one line weight, indexed by their arms as bits, with
+/// left as the lowest bit followed by right, top and bottom.
+const LIGHT: [char; 16] = [
+    ' ', '─', '─', '─', '│', '┘', '└', '┴', '│', '┐', '┌', '┬', '│', '┤', '├', '┼',
+];
+const HEAVY: [char; 16] = [
+    ' ', '━', '━', '━', '┃', '┛', '┗', '┻', '┃', '┓', '┏', '┳', '┃', '┫', '┣', '╋',
+];
+const DOUBLE: [char; 16] = [
+    ' ', '═', '═', '═', '║', '╝', '╚', '╩', '║', '╗', '╔', '╦', '║', '╣', '╠', '╬',
+];
+
+/// Joins box drawing characters that overlap into one with the lines of both, in the
+/// weight of the one on top, so that the borders of windows touching each other
+/// connect. Anything else on top replaces what is below.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct JoinLines;
+
+impl JoinLines {
+    /// Returns the character with the lines of both, if both are box drawing
+    /// characters.
+    fn join(below: char, top: char) -> Option<char> {
+        let (a, b) = (box_arms(below)?, box_arms(top)?);
+        let bits = (0..4)
+            .filter(|&i| a[i] || b[i])
+            .fold(0, |bits, i| bits | 1 << i);
+        let table = match top {
+            '━' | '┃' | '┏' | '┓' | '┗' | '┛' | '┣' | '┫' | '┳' | '┻' | '╋' => {
+                &HEAVY
+            }
+            '═' | '║' | '╔' | '╗' | '╚' | '╝' | '╠' | '╣' | '╦' | '╩' | '╬' => {
+                &DOUBLE
+            }
+            _ => &LIGHT,
+        };
+        Some(table[bits])
+    }
+}
+
+impl Compositor<char> for JoinLines {
+    fn composite(&self, below: &char, top: &char) -> Merge<char> {
+        match Self::join(*below, *top) {
+            Some(ch) => Merge::Blend(ch),
+            None => Merge::Replace,
+        }
+    }
+}
+
+impl Compositor<Cell> for JoinLines {
+    fn composite(&self, below: &Cell, top: &Cell) -> Merge<Cell> {
+        match Self::join(below.ch, top.ch) {
+            Some(ch) => Merge::Blend(Cell::new(ch, top.style)),
+            None => Merge::Replace,
+        }
+    }
+}
@@ -0,0 +1,434 @@
+//! Drawing shapes onto the contents of a window. Positions are relative to the top
+//! left of the window's data, and anything falling outside of it is not drawn.
+
+use crate::Window;
+use crate::geometry::{Coord, Point, Rect};
+use std::fmt;
+
+/// Which neighbours of a cell count as connected to it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Connectivity {
+    /// The cells above, below, left and right.
+    #[default]
+    Four,
+    /// The cells around it, including diagonally.
+    Eight,
+}
+
+/// What [`Window::overlay`] does with a cell of the window on top.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Merge<T> {
+    /// The top cell replaces the one below.
+    Replace,
+    /// The cell below is kept, as if the top one were transparent.
+    Skip,
+    /// Both are replaced with the given value, such as a blend of the two.
+    Blend(T),
+}
+
+impl Connectivity {
+    /// Offsets to each neighbour.
+    fn offsets(self) -> &'static [(Coord, Coord)] {
+        match self {
+            Connectivity::Four => &[(0, -1), (-1, 0), (1, 0), (0, 1)],
+            Connectivity::Eight => &[
+                (-1, -1),
+                (0, -1),
+                (1, -1),
+                (-1, 0),
+                (1, 0),
+                (-1, 1),
+                (0, 1),
+                (1, 1),
+            ],
+        }
+    }
+}
+
+/// Returns points along a cubic Bezier curve, close enough together to be joined by
+/// straight lines.
+pub(crate) fn cubic_points(p: [(f64, f64); 4]) -> Vec<(i64, i64)> {
+    // The curve is no longer than its control polygon, so use one step per unit.
+    let len: f64 = p
+        .windows(2)
+        .map(|w| (w[1].0 - w[0].0).hypot(w[1].1 - w[0].1))
+        .sum();
+    let steps = (len.ceil() as usize).max(1);
+
+    (0..=steps)
+        .map(|i| {
+            let t = i as f64 / steps as f64;
+            let u = 1.0 - t;
+            let weights = [u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t];
+            let (x, y) = p
+                .iter()
+                .zip(weights)
+                .fold((0.0, 0.0), |(x, y), (p, w)| (x + p.0 * w, y + p.1 * w));
+            (x.round() as i64, y.round() as i64)
+        })
+        .collect()
+}
+
+/// Returns the control points of the cubic Bezier curve equal to a quadratic one.
+pub(crate) fn quad_to_cubic(p: [(f64, f64); 3]) -> [(f64, f64); 4] {
+    let lerp = |a: (f64, f64), b: (f64, f64)| {
+        (a.0 + (b.0 - a.0) * 2.0 / 3.0, a.1 + (b.1 - a.1) * 2.0 / 3.0)
+    };
+    [p[0], lerp(p[0], p[1]), lerp(p[2], p[1]), p[2]]
+}
+
+/// Returns points along an arc, close enough together to be joined by straight lines.
+/// Angles are in radians from the right, going clockwise as y increases downwards.
+pub(crate) fn arc_points(center: (f64, f64), radius: f64, start: f64, end: f64) -> Vec<(i64, i64)> {
+    let steps = ((end - start).abs() * radius).ceil().max(1.0) as usize;
+
+    (0..=steps)
+        .map(|i| {
+            let angle = start + (end - start) * i as f64 / steps as f64;
+            let x = center.0 + radius * angle.cos();
+            let y = center.1 + radius * angle.sin();
+            (x.round() as i64, y.round() as i64)
+        })
+        .collect()
+}
+
+/// Cuts the line between two points down to the part within `min` to `max` in both
+/// directions, or returns None if none of it is.
+pub(crate) fn clip_line(
+    mut from: (f64, f64),
+    mut to: (f64, f64),
+    min: (f64, f64),
+    max: (f64, f64),
+) -> Option<((f64, f64), (f64, f64))> {
+    if [from.0, from.1, to.0, to.1].iter().any(|n| n.is_nan()) {
+        return None;
+    }
+    let outside = |p: (f64, f64)| p.0 < min.0 || p.0 > max.0 || p.1 < min.1 || p.1 > max.1;
+    // Move an end that is outside onto the edge the line crosses, which each end needs
+    // at most twice, until both are inside or both are beyond the same edge.
+    for _ in 0..5 {
+        if !outside(from) && !outside(to) {
+            break;
+        }
+        if (from.0 < min.0 && to.0 < min.0)
+            || (from.0 > max.0 && to.0 > max.0)
+            || (from.1 < min.1 && to.1 < min.1)
+            || (from.1 > max.1 && to.1 > max.1)
+        {
+            return None;
+        }
+        let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+        let end = if outside(from) { &mut from } else { &mut to };
+        let p = *end;
+        *end = if p.0 < min.0 {
+            (min.0, p.1 + (min.0 - p.0) * dy / dx)
+        } else if p.0 > max.0 {
+            (max.0, p.1 + (max.0 - p.0) * dy / dx)
+        } else if p.1 < min.1 {
+            (p.0 + (min.1 - p.1) * dx / dy, min.1)
+        } else {
+            (p.0 + (max.1 - p.1) * dx / dy, max.1)
+        };
+    }
+    // Rounding can leave an end a hair outside.
+    let clamp = |p: (f64, f64)| (p.0.clamp(min.0, max.0), p.1.clamp(min.1, max.1));
+    Some((clamp(from), clamp(to)))
+}
+
+fn to_f64(p: Point) -> (f64, f64) {
+    (p.x as f64, p.y as f64)
+}
+
+impl<T: fmt::Display> Window<T> {
+    /// Returns the cell at the given position, if there is one.
+    fn get_cell(&self, p: Point) -> Option<&T> {
+        let (x, y) = (usize::try_from(p.x).ok()?, usize::try_from(p.y).ok()?);
+        self.data.get(y)?.get(x)
+    }
+
+    /// Sets the cell at the given position, returning false if there isn't one.
+    pub fn set_cell(&mut self, p: Point, value: T) -> bool {
+        let cell = usize::try_from(p.y)
+            .ok()
+            .zip(usize::try_from(p.x).ok())
+            .and_then(|(y, x)| self.data.get_mut(y)?.get_mut(x));
+
+        match cell {
+            Some(cell) => {
+                *cell = value;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Draws a straight line between two points. Only the part of the line within
+    /// the window's data is stepped through, so far off ends cost nothing extra.
+    pub fn draw_line(&mut self, from: Point, to: Point, value: T)
+    where
+        T: Clone,
+    {
+        let (wid, hgt) = (self.width(), self.data.len());
+        if wid == 0 || hgt == 0 {
+            return;
+        }
+        let max = ((wid - 1) as f64, (hgt - 1) as f64);
+        let Some((from, to)) = clip_line(to_f64(from), to_f64(to), (0.0, 0.0), max) else {
+            return;
+        };
+        // Both ends are now within the data, so none of this can overflow.
+        let (mut x, mut y) = (from.0.round() as i64, from.1.round() as i64);
+        let to = (to.0.round() as i64, to.1.round() as i64);
+        let dx = (to.0 - x).abs();
+        let dy = -(to.1 - y).abs();
+        let sx = if x < to.0 { 1 } else { -1 };
+        let sy = if y < to.1 { 1 } else { -1 };
+        let mut err = dx + dy;
+
+        loop {
+            self.set_cell(Point::new(x as Coord, y as Coord), value.clone());
+            if (x, y) == to {
+                break;
+            }
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                x += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                y += sy;
+            }
+        }
+    }
+
+    /// Draws the outline of a rectangle.
+    pub fn draw_rect(&mut self, rect: Rect, value: T)
+    where
+        T: Clone,
+    {
+        if rect.wid == 0 || rect.hgt == 0 {
+            return;
+        }
+        let Point { x: left, y: top } = rect.top_left;
+        let right = left + rect.wid as Coord - 1;
+        let bottom = top + rect.hgt as Coord - 1;
+
+        self.draw_line(Point::new(left, top), Point::new(right, top), value.clone());
+        self.draw_line(
+            Point::new(left, bottom),
+            Point::new(right, bottom),
+            value.clone(),
+        );
+        self.draw_line(
+            Point::new(left, top),
+            Point::new(left, bottom),
+            value.clone(),
+        );
+        self.draw_line(Point::new(right, top), Point::new(right, bottom), value);
+    }
+
+    /// Fills a rectangle.
+    pub fn draw_filled_rect(&mut self, rect: Rect, value: T)
+    where
+        T: Clone,
+    {
+        for p in rect.points() {
+            self.set_cell(p, value.clone());
+        }
+    }
+
+    /// Draws the outline of a circle. As cells are about twice as tall as they are
+    /// wide, it will look like an ellipse.
+    pub fn draw_circle(&mut self, center: Point, radius: u32, value: T)
+    where
+        T: Clone,
+    {
+        let radius = radius as Coord;
+        let (mut x, mut y) = (radius, 0);
+        let mut err = 1 - radius;
+
+        while x >= y {
+            for (dx, dy) in [(x, y), (y, x), (-y, x), (-x, y)] {
+                self.set_cell(Point::new(center.x + dx, center.y + dy), value.clone());
+                self.set_cell(Point::new(center.x - dx, center.y - dy), value.clone());
+            }
+            y += 1;
+            if err < 0 {
+                err += 2 * y + 1;
+            } else {
+                x -= 1;
+                err += 2 * (y - x) + 1;
+            }
+        }
+    }
+
+    /// Replaces the region of cells equal to the one at `start` and connected to it
+    /// with the given value.
+    pub fn flood_fill(&mut self, start: Point, value: T, connectivity: Connectivity)
+    where
+        T: Clone + PartialEq,
+    {
+        let Some(target) = self.get_cell(start).cloned() else {
+            return;
+        };
+        if target == value {
+            return;
+        }
+
+        let mut stack = vec![start];
+        while let Some(p) = stack.pop() {
+            if self.get_cell(p) != Some(&target) {
+                continue;
+            }
+            self.set_cell(p, value.clone());
+            for &(dx, dy) in connectivity.offsets() {
+                stack.push(Point::new(p.x + dx, p.y + dy));
+            }
+        }
+    }
+
+    /// Sets each cell in a rectangle to the value returned for its position.
+    pub fn fill_with(&mut self, rect: Rect, mut f: impl FnMut(Coord, Coord) -> T) {
+        for p in rect.points() {
+            self.set_cell(p, f(p.x, p.y));
+        }
+    }
+
+    /// Fills a rectangle by repeating the contents of another window, lined up with
+    /// the top left of this one.
+    pub fn fill_pattern(&mut self, rect: Rect, pattern: &Window<T>)
+    where
+        T: Clone,
+    {
+        let rows = pattern.data.len();
+        for p in rect.points() {
+            let Some(row) = pattern
+                .data
+                .get(p.y.rem_euclid(rows.max(1) as Coord) as usize)
+            else {
+                return;
+            };
+            if let Some(value) = row.get(p.x.rem_euclid(row.len().max(1) as Coord) as usize) {
+                self.set_cell(p, value.clone());
+            }
+        }
+    }
+
+    /// Copies the contents of another window into this one with its top left at `at`,
+    /// leaving out any that don't land on a cell of this window.
+    pub fn blit(&mut self, src: &Window<T>, at: Point)
+    where
+        T: Clone,
+    {
+        for (y, row) in src.data.iter().enumerate() {
+            for (x, value) in row.iter().enumerate() {
+                self.set_cell(Point::new(x as Coord, y as Coord) + at, value.clone());
+            }
+        }
+    }
+
+    /// Returns a copy of this window with another drawn over it, lined up by their
+    /// positions and cut down to this window's cells. For each cell covered by both,
+    /// `rule` is given the cell below then the one on top and decides what to keep.
+    pub fn overlay(&self, other: &Window<T>, mut rule: impl FnMut(&T, &T) -> Merge<T>) -> Window<T>
+    where
+        T: Clone,
+    {
+        let mut win = self.clone();
+        let offset = Point::new(
+            other.top_left.x - self.top_left.x,
+            other.top_left.y - self.top_left.y,
+        );
+
+        for (y, row) in other.data.iter().enumerate() {
+            for (x, top) in row.iter().enumerate() {
+                let p = Point::new(x as Coord, y as Coord) + offset;
+                let Some(below) = self.get_cell(p) else {
+                    continue;
+                };
+                match rule(below, top) {
+                    Merge::Replace => win.set_cell(p, top.clone()),
+                    Merge::Skip => false,
+                    Merge::Blend(value) => win.set_cell(p, value),
+                };
+            }
+        }
+
+        win
+    }
+
+    /// Draws straight lines joining each point to the next.
+    fn draw_path(&mut self, points: &[(i64, i64)], value: T)
+    where
+        T: Clone,
+    {
+        let to_point = |&(x, y): &(i64, i64)| Point::new(x as Coord, y as Coord);
+        for pair in points.windows(2) {
+            self.draw_line(to_point(&pair[0]), to_point(&pair[1]), value.clone());
+        }
+    }
+
+    /// Draws a quadratic Bezier curve from `from` to `to`, bent towards `control`.
+    pub fn draw_quad_bezier(&mut self, from: Point, control: Point, to: Point, value: T)
+    where
+        T: Clone,
+    {
+        let cubic = quad_to_cubic([to_f64(from), to_f64(control), to_f64(to)]);
+        self.draw_path(&cubic_points(cubic), value);
+    }
+
+    /// Draws a cubic Bezier curve from `from` to `to`, bent towards the two control
+    /// points.
+    pub fn draw_cubic_bezier(&mut self, from: Point, c1: Point, c2: Point, to: Point, value: T)
+    where
+        T: Clone,
+    {
+        let points = cubic_points([to_f64(from), to_f64(c1), to_f64(c2), to_f64(to)]);
+        self.draw_path(&points, value);
+    }
+
+    /// Draws part of the outline of a circle between two angles, in radians from the
+    /// right going clockwise.
+    pub fn draw_arc(&mut self, center: Point, radius: u32, start: f64, end: f64, value: T)
+    where
+        T: Clone,
+    {
+        let points = arc_points(to_f64(center), radius as f64, start, end);
+        self.draw_path(&points, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(wid: usize, hgt: usize) -> Window<char> {
+        let mut win = Window::new(Point::new(0, 0));
+        win.data = vec![vec!['.'; wid]; hgt];
+        win
+    }
+
+    fn rows(win: &Window<char>) -> Vec<String> {
+        win.data.iter().map(|row| row.iter().collect()).collect()
+    }
+
+    #[test]
+    fn lines_and_rects() {
+        let mut win = grid(5, 3);
+        win.draw_line(Point::new(0, 0), Point::new(4, 2), '#');
+        assert_eq!(rows(&win), ["#....", ".##..", "...##"]);
+
+        let mut win = grid(5, 3);
+        win.draw_rect(Rect::new(Point::new(1, 0), 3, 3), '#');
+        assert_eq!(rows(&win), [".###.", ".#.#.", ".###."]);
+    }
+
+    #[test]
+    fn line_with_far_off_ends() {
+        let mut win = grid(4, 2);
+        win.draw_line(Point::new(Coord::MIN, 1), Point::new(Coord::MAX, 1), '#');
+        win.draw_line(Point::new(-10, 5), Point::new(5, -10), '#');
+        assert_eq!(rows(&win), ["....", "####"]);
+    }
+}
@@ -0,0 +1,196 @@
+//! Input events consumed by a [`Container`](crate::Container) and the events it
+//! reports back about its windows.
+
+use crate::WindowId;
+use crate::geometry::Point;
+
+/// Any event delivered by a [`Backend`](crate::backend::Backend) or produced while
+/// running a container's event loop.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Event {
+    /// A key was pressed, or is being held down.
+    Key(KeyEvent),
+    /// A key was released. This is only reported by terminals asked to with
+    /// [`KeyboardFlags::report_events`](crate::terminal::KeyboardFlags::report_events).
+    KeyRelease(KeyEvent),
+    /// The mouse was used.
+    Mouse(MouseEvent),
+    /// Text was pasted into the terminal. This is delivered as a whole rather than as
+    /// a key press per character.
+    Paste(String),
+    /// The screen was resized to the given width and height.
+    Resize(u16, u16),
+    /// The tick interval elapsed without any other event.
+    Tick,
+    /// The container did something to one of its windows.
+    Window(WindowEvent),
+}
+
+/// A key on the keyboard.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum KeyCode {
+    Char(char),
+    Enter,
+    Esc,
+    Backspace,
+    Tab,
+    BackTab,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Insert,
+    Delete,
+    /// A function key, such as F1.
+    F(u8),
+}
+
+/// Modifier keys held during a key press.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+}
+
+impl Modifiers {
+    /// No modifiers held.
+    pub const NONE: Self = Self {
+        shift: false,
+        ctrl: false,
+        alt: false,
+    };
+    /// Only shift held.
+    pub const SHIFT: Self = Self {
+        shift: true,
+        ctrl: false,
+        alt: false,
+    };
+    /// Only control held.
+    pub const CTRL: Self = Self {
+        shift: false,
+        ctrl: true,
+        alt: false,
+    };
+    /// Only alt held.
+    pub const ALT: Self = Self {
+        shift: false,
+        ctrl: false,
+        alt: true,
+    };
+}
+
+/// A key press.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct KeyEvent {
+    /// The key pressed.
+    pub code: KeyCode,
+    /// The modifiers held while it was pressed.
+    pub modifiers: Modifiers,
+}
+
+impl KeyEvent {
+    /// Create a new key event.
+    pub fn new(code: KeyCode, modifiers: Modifiers) -> Self {
+        Self { code, modifiers }
+    }
+}
+
+impl From<KeyCode> for KeyEvent {
+    fn from(code: KeyCode) -> Self {
+        Self::new(code, Modifiers::NONE)
+    }
+}
+
+/// A mouse button.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+}
+
+/// What happened in a mouse event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MouseKind {
+    /// The button was pressed.
+    Down(MouseButton),
+    /// The button was released.
+    Up(MouseButton),
+    /// The mouse moved while the button was held.
+    Drag(MouseButton),
+    /// The mouse moved with no button held.
+    Moved,
+    /// The wheel was scrolled up.
+    ScrollUp,
+    /// The wheel was scrolled down.
+    ScrollDown,
+}
+
+/// Which mouse events a terminal reports.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum MouseMode {
+    /// Presses and releases of buttons, and the wheel.
+    Click,
+    /// As well as clicks, movement while a button is held, which is needed to drag
+    /// windows.
+    #[default]
+    Drag,
+    /// As well as clicks, all movement, even with no button held.
+    Motion,
+}
+
+impl MouseMode {
+    /// Returns true if movement while a button is held is reported.
+    pub fn reports_drag(self) -> bool {
+        self != MouseMode::Click
+    }
+}
+
+/// A mouse event at a position on the screen.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct MouseEvent {
+    /// What happened.
+    pub kind: MouseKind,
+    /// Where it happened, with the origin at the top left corner of the terminal.
+    pub pos: Point,
+}
+
+impl MouseEvent {
+    /// Create a new mouse event.
+    pub fn new(kind: MouseKind, pos: Point) -> Self {
+        Self { kind, pos }
+    }
+}
+
+/// Something the container did to one of its windows in response to input.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WindowEvent {
+    /// The window was dragged from one position to another.
+    Moved {
+        win: WindowId,
+        from: Point,
+        to: Point,
+    },
+    /// The window was resized by dragging its borders, or by the [panes](crate::Panes)
+    /// it is in changing. Its new space has been filled with default values, so its
+    /// content should be redrawn to fit the new size.
+    Resized {
+        win: WindowId,
+        wid: usize,
+        hgt: usize,
+    },
+    /// The window was scrolled with the mouse wheel so that its first visible row is
+    /// now `scroll`.
+    Scrolled { win: WindowId, scroll: usize },
+    /// A dialog was dismissed by choosing the button at index `button`. The window
+    /// has already been removed.
+    DialogClosed { win: WindowId, button: usize },
+    /// A flash of the window, or of the whole screen if None, ended, and the next
+    /// frame shows it as it is.
+    FlashEnded { win: Option<WindowId> },
+}
@@ -0,0 +1,169 @@
+//! Saving what a container shows in formats other than text.
+
+use crate::Container;
+use crate::geometry::{Coord, Point};
+use crate::style::{Cell, Style};
+use std::fmt::Write;
+
+/// Colour used for text without one, like a terminal's default.
+pub(crate) const DEFAULT_FG: (u8, u8, u8) = (229, 229, 229);
+/// Colour used behind text without one, like a terminal's default.
+pub(crate) const DEFAULT_BG: (u8, u8, u8) = (0, 0, 0);
+
+/// Returns the colours a style is drawn in, with reversing applied.
+pub(crate) fn colors(style: Style) -> ((u8, u8, u8), (u8, u8, u8)) {
+    let fg = style.fg.map_or(DEFAULT_FG, |c| c.to_rgb());
+    let bg = style.bg.map_or(DEFAULT_BG, |c| c.to_rgb());
+    if style.reversed { (bg, fg) } else { (fg, bg) }
+}
+
+pub(crate) fn hex((r, g, b): (u8, u8, u8)) -> String {
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+/// Width of a cell in an SVG, in pixels.
+const SVG_CELL_WID: usize = 9;
+/// Height of a cell in an SVG, in pixels.
+const SVG_CELL_HGT: usize = 18;
+
+/// Appends a character, escaping it for HTML or XML.
+pub(crate) fn push_escaped(out: &mut String, ch: char) {
+    match ch {
+        '&' => out.push_str("&amp;"),
+        '<' => out.push_str("&lt;"),
+        '>' => out.push_str("&gt;"),
+        '"' => out.push_str("&quot;"),
+        _ => out.push(ch),
+    }
+}
+
+/// Returns the CSS for a style, leaving out anything that matches the defaults.
+fn css(style: Style) -> String {
+    let (fg, bg) = colors(style);
+    let mut out = String::new();
+    if fg != DEFAULT_FG {
+        let _ = write!(out, "color:{};", hex(fg));
+    }
+    if bg != DEFAULT_BG {
+        let _ = write!(out, "background-color:{};", hex(bg));
+    }
+    if style.bold {
+        out.push_str("font-weight:bold;");
+    }
+    if style.italic {
+        out.push_str("font-style:italic;");
+    }
+    if style.underline {
+        out.push_str("text-decoration:underline;");
+    }
+    out
+}
+
+impl Container<Cell> {
+    /// Returns the rows of cells from (0, 0) to (wid, hgt), with blanks where there is
+    /// nothing.
+    pub(crate) fn cell_rows(&self, wid: u16, hgt: u16) -> Vec<Vec<Cell>> {
+        (0..hgt as Coord)
+            .map(|y| {
+                (0..wid as Coord)
+                    .map(|x| {
+                        self.buffer
+                            .get(&Point::new(x, y))
+                            .copied()
+                            .unwrap_or_default()
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Creates an HTML snapshot of the container with positions from (0, 0) to
+    /// (wid, hgt), as a `<pre>` element with the styles of the cells given as CSS.
+    pub fn to_html(&self, wid: u16, hgt: u16) -> String {
+        let mut out = format!(
+            "<pre style=\"font-family:monospace;color:{};background-color:{};\">",
+            hex(DEFAULT_FG),
+            hex(DEFAULT_BG)
+        );
+
+        for row in self.cell_rows(wid, hgt) {
+            // Put cells next to each other with the same style in one span.
+            for run in row.chunk_by(|a, b| a.style == b.style) {
+                let style = css(run[0].style);
+                if !style.is_empty() {
+                    let _ = write!(out, "<span style=\"{style}\">");
+                }
+                for cell in run {
+                    push_escaped(&mut out, cell.ch);
+                }
+                if !style.is_empty() {
+                    out.push_str("</span>");
+                }
+            }
+            out.push('\n');
+        }
+
+        out.push_str("</pre>\n");
+        out
+    }
+
+    /// Creates an SVG image of the container with positions from (0, 0) to
+    /// (wid, hgt). Each run of text is stretched to exactly the width of its cells, so
+    /// everything lines up whatever monospace font is used.
+    pub fn to_svg(&self, wid: u16, hgt: u16) -> String {
+        let (img_wid, img_hgt) = (wid as usize * SVG_CELL_WID, hgt as usize * SVG_CELL_HGT);
+        let mut out = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{img_wid}\" height=\"{img_hgt}\" \
+             font-family=\"monospace\" font-size=\"15\" xml:space=\"preserve\">\n\
+             <rect width=\"100%\" height=\"100%\" fill=\"{}\"/>\n",
+            hex(DEFAULT_BG)
+        );
+
+        for (y, row) in self.cell_rows(wid, hgt).iter().enumerate() {
+            let top = y * SVG_CELL_HGT;
+            let mut x = 0;
+            for run in row.chunk_by(|a, b| a.style == b.style) {
+                let style = run[0].style;
+                let (fg, bg) = colors(style);
+                let (left, run_wid) = (x * SVG_CELL_WID, run.len() * SVG_CELL_WID);
+                x += run.len();
+
+                if bg != DEFAULT_BG {
+                    let _ = writeln!(
+                        out,
+                        "<rect x=\"{left}\" y=\"{top}\" width=\"{run_wid}\" height=\"{SVG_CELL_HGT}\" fill=\"{}\"/>",
+                        hex(bg)
+                    );
+                }
+                if run.iter().all(|c| c.ch == ' ') && !style.underline {
+                    continue;
+                }
+
+                // Put the baseline a little above the bottom of the cell.
+                let _ = write!(
+                    out,
+                    "<text x=\"{left}\" y=\"{}\" textLength=\"{run_wid}\" lengthAdjust=\"spacingAndGlyphs\" fill=\"{}\"",
+                    top + SVG_CELL_HGT * 3 / 4,
+                    hex(fg)
+                );
+                if style.bold {
+                    out.push_str(" font-weight=\"bold\"");
+                }
+                if style.italic {
+                    out.push_str(" font-style=\"italic\"");
+                }
+                if style.underline {
+                    out.push_str(" text-decoration=\"underline\"");
+                }
+                out.push('>');
+                for cell in run {
+                    push_escaped(&mut out, cell.ch);
+                }
+                out.push_str("</text>\n");
+            }
+        }
+
+        out.push_str("</svg>\n");
+        out
+    }
+}
@@ -0,0 +1,126 @@
+//! Briefly restyling a window, or the whole screen, to draw attention to it.
+
+use crate::event::{Event, WindowEvent};
+use crate::geometry::{Point, Rect};
+use crate::runtime::TimerId;
+use crate::style::{Cell, Style};
+use crate::{Container, WindowId};
+use std::fmt;
+use std::time::Duration;
+
+/// How long [`Container::visual_bell`] flashes the screen for.
+const BELL_DURATION: Duration = Duration::from_millis(100);
+
+/// A window, or the whole screen, being flashed.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Flash<T> {
+    // Window flashed, or None for the whole screen.
+    pub(crate) win: Option<WindowId>,
+    // Timer that ends the flash when it fires.
+    timer: TimerId,
+    style: Style,
+    apply: fn(Option<&T>, Style) -> T,
+}
+
+/// Returns a cell, or a blank one if there is none, with the style laid over its own.
+pub(crate) fn flash_cell(cell: Option<&Cell>, style: Style) -> Cell {
+    let cell = cell.copied().unwrap_or_default();
+    Cell::new(cell.ch, cell.style.patch(style))
+}
+
+impl<T: fmt::Display> Container<T> {
+    /// Returns true if the window, or the whole screen if None, is being flashed.
+    pub fn is_flashing(&self, win: Option<WindowId>) -> bool {
+        self.flashes.iter().any(|flash| flash.win == win)
+    }
+
+    /// Restyles the buffer where it is being flashed. Positions of a flashed screen
+    /// that nothing was drawn at are filled in.
+    pub(crate) fn apply_flashes(&mut self)
+    where
+        T: PartialEq,
+    {
+        let (wid, hgt) = self.screen_size();
+        for flash in &self.flashes {
+            let points: Vec<Point> = match flash.win {
+                Some(win) => self
+                    .owners
+                    .iter()
+                    .filter(|&(_, &owner)| owner == win)
+                    .map(|(p, _)| p)
+                    .collect(),
+                None => Rect::new(Point::ORIGIN, wid as usize, hgt as usize)
+                    .points()
+                    .collect(),
+            };
+            for p in points {
+                let value = (flash.apply)(self.buffer.get(&p), flash.style);
+                self.buffer.insert(p, value);
+            }
+        }
+    }
+
+    /// Stops the flashes whose timers have fired, so that the next refresh draws
+    /// what is under them as it is.
+    pub(crate) fn end_flashes(&mut self) {
+        let (wid, hgt) = self.screen_size();
+        let mut ended = Vec::new();
+        self.flashes.retain(|flash| {
+            let keep = self.timers.is_scheduled(flash.timer);
+            if !keep {
+                ended.push(flash.win);
+            }
+            keep
+        });
+
+        for win in ended {
+            let points: Vec<Point> = match win {
+                Some(win) => self.windows[win].footprint().collect(),
+                None => Rect::new(Point::ORIGIN, wid as usize, hgt as usize)
+                    .points()
+                    .collect(),
+            };
+            self.mark_dirty(points);
+        }
+    }
+
+    fn start_flash(
+        &mut self,
+        win: Option<WindowId>,
+        style: Style,
+        duration: Duration,
+        apply: fn(Option<&T>, Style) -> T,
+    ) {
+        let ended = Event::Window(WindowEvent::FlashEnded { win });
+        let timer = self.timers.after(duration, ended);
+        self.flashes.push(Flash {
+            win,
+            timer,
+            style,
+            apply,
+        });
+        self.request_refresh();
+    }
+}
+
+impl Container<Cell> {
+    /// Lays the style over every cell of the window for the given duration, such as
+    /// reversing its colours to draw attention to it. The window goes back to how it
+    /// was once [`WindowEvent::FlashEnded`] is handled, which the event loop is given
+    /// by a [timer](Self::timers).
+    pub fn flash(&mut self, win: WindowId, style: Style, duration: Duration) {
+        self.start_flash(Some(win), style, duration, flash_cell);
+    }
+
+    /// Lays the style over the whole screen for the given duration, including where
+    /// no window is shown. The screen goes back to how it was as with
+    /// [`flash`](Self::flash).
+    pub fn flash_screen(&mut self, style: Style, duration: Duration) {
+        self.start_flash(None, style, duration, flash_cell);
+    }
+
+    /// Briefly reverses the colours of the whole screen, in place of a beep.
+    pub fn visual_bell(&mut self) {
+        self.flash_screen(Style::new().reversed(), BELL_DURATION);
+    }
+}
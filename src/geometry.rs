@@ -0,0 +1,243 @@
+//! Points, sizes and rectangles of cells, with the origin at the top left and y
+//! increasing downwards.
+
+use std::fmt;
+use std::ops::{Add, AddAssign, Neg, Sub, SubAssign};
+
+/// Type of the co-ordinates of a [`Point`]. This is `i64` with the `wide-coords`
+/// feature, for canvases and world co-ordinates too large for `i32`, which it is
+/// otherwise. Positions on the screen always fit in a `u16`, and are converted where
+/// a frame is presented.
+#[cfg(not(feature = "wide-coords"))]
+pub type Coord = i32;
+/// Type of the co-ordinates of a [`Point`], widened by the `wide-coords` feature.
+#[cfg(feature = "wide-coords")]
+pub type Coord = i64;
+
+/// A position on a grid of cells.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Point {
+    pub x: Coord,
+    pub y: Coord,
+}
+
+impl Point {
+    /// The top left corner.
+    pub const ORIGIN: Self = Self { x: 0, y: 0 };
+
+    /// Create a new point.
+    pub const fn new(x: Coord, y: Coord) -> Self {
+        Self { x, y }
+    }
+
+    /// Returns the point moved by the given amounts.
+    pub const fn offset(self, dx: Coord, dy: Coord) -> Self {
+        Self::new(self.x + dx, self.y + dy)
+    }
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl From<(Coord, Coord)> for Point {
+    fn from((x, y): (Coord, Coord)) -> Self {
+        Self::new(x, y)
+    }
+}
+
+impl Add for Point {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Point {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl Neg for Point {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+/// A width and height in cells.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Size {
+    pub wid: usize,
+    pub hgt: usize,
+}
+
+impl Size {
+    /// Create a new size.
+    pub const fn new(wid: usize, hgt: usize) -> Self {
+        Self { wid, hgt }
+    }
+
+    /// Returns the number of cells covered.
+    pub const fn area(self) -> usize {
+        self.wid * self.hgt
+    }
+
+    /// Returns true if no cells are covered.
+    pub const fn is_empty(self) -> bool {
+        self.wid == 0 || self.hgt == 0
+    }
+}
+
+impl From<(usize, usize)> for Size {
+    fn from((wid, hgt): (usize, usize)) -> Self {
+        Self::new(wid, hgt)
+    }
+}
+
+/// A rectangle of cells.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Rect {
+    /// Top left co-ordinate of the rectangle.
+    pub top_left: Point,
+    pub wid: usize,
+    pub hgt: usize,
+}
+
+impl Rect {
+    /// Create a new rectangle.
+    pub const fn new(top_left: Point, wid: usize, hgt: usize) -> Self {
+        Self { top_left, wid, hgt }
+    }
+
+    /// Create a rectangle with the given top left corner and size.
+    pub const fn from_size(top_left: Point, size: Size) -> Self {
+        Self::new(top_left, size.wid, size.hgt)
+    }
+
+    /// Returns the smallest rectangle containing both corners.
+    pub fn from_corners(a: Point, b: Point) -> Self {
+        let top_left = Point::new(a.x.min(b.x), a.y.min(b.y));
+        let wid = (a.x - b.x).unsigned_abs() as usize + 1;
+        let hgt = (a.y - b.y).unsigned_abs() as usize + 1;
+        Self::new(top_left, wid, hgt)
+    }
+
+    /// Returns the width and height of the rectangle.
+    pub const fn size(&self) -> Size {
+        Size::new(self.wid, self.hgt)
+    }
+
+    /// Returns the number of cells inside the rectangle.
+    pub const fn area(&self) -> usize {
+        self.wid * self.hgt
+    }
+
+    /// Returns true if the rectangle has no cells inside it.
+    pub const fn is_empty(&self) -> bool {
+        self.wid == 0 || self.hgt == 0
+    }
+
+    /// Returns the column just past the right edge.
+    pub const fn right(&self) -> Coord {
+        self.top_left.x + self.wid as Coord
+    }
+
+    /// Returns the row just past the bottom edge.
+    pub const fn bottom(&self) -> Coord {
+        self.top_left.y + self.hgt as Coord
+    }
+
+    /// Returns the position just past the bottom right corner, so that the
+    /// rectangle spans from its top left up to but not including this.
+    pub const fn bottom_right(&self) -> Point {
+        Point::new(self.right(), self.bottom())
+    }
+
+    /// Returns true if the point is inside the rectangle.
+    pub fn contains(&self, p: Point) -> bool {
+        let (dx, dy) = (p.x - self.top_left.x, p.y - self.top_left.y);
+        dx >= 0 && dy >= 0 && (dx as usize) < self.wid && (dy as usize) < self.hgt
+    }
+
+    /// Returns true if the other rectangle is entirely inside this one.
+    pub fn contains_rect(&self, other: &Rect) -> bool {
+        other.is_empty()
+            || (self.contains(other.top_left)
+                && other.right() <= self.right()
+                && other.bottom() <= self.bottom())
+    }
+
+    /// Returns true if the rectangles have any cells in common.
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// Returns the cells the rectangles have in common, if any.
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        let left = self.top_left.x.max(other.top_left.x);
+        let top = self.top_left.y.max(other.top_left.y);
+        let right = self.right().min(other.right());
+        let bottom = self.bottom().min(other.bottom());
+
+        (left < right && top < bottom).then(|| {
+            Rect::new(
+                Point::new(left, top),
+                (right - left) as usize,
+                (bottom - top) as usize,
+            )
+        })
+    }
+
+    /// Returns the smallest rectangle containing both. An empty rectangle adds
+    /// nothing.
+    pub fn union(&self, other: &Rect) -> Rect {
+        if other.is_empty() {
+            return *self;
+        }
+        if self.is_empty() {
+            return *other;
+        }
+        let left = self.top_left.x.min(other.top_left.x);
+        let top = self.top_left.y.min(other.top_left.y);
+        let right = self.right().max(other.right());
+        let bottom = self.bottom().max(other.bottom());
+        Rect::new(
+            Point::new(left, top),
+            (right - left) as usize,
+            (bottom - top) as usize,
+        )
+    }
+
+    /// Returns the rectangle moved by the given offset.
+    pub fn translate(&self, by: Point) -> Rect {
+        Rect::new(self.top_left + by, self.wid, self.hgt)
+    }
+
+    /// Returns every point inside the rectangle, row by row.
+    pub fn points(&self) -> impl Iterator<Item = Point> + '_ {
+        (0..self.hgt as Coord).flat_map(move |y| {
+            (0..self.wid as Coord)
+                .map(move |x| Point::new(self.top_left.x + x, self.top_left.y + y))
+        })
+    }
+}
@@ -0,0 +1,237 @@
+//! Undoing and redoing changes to the windows of a container.
+
+use crate::geometry::Point;
+use crate::{Container, Window, WindowId};
+use std::collections::VecDeque;
+use std::fmt;
+
+/// A change to the windows of a container that can be undone, made with
+/// [`Container::edit`].
+#[derive(Clone, Debug)]
+pub enum Edit<T: fmt::Display> {
+    /// Add a window on top of the others.
+    Add(Window<T>),
+    /// Insert a window with the given id, like [`Container::insert_win`].
+    Insert(WindowId, Window<T>),
+    /// Remove a window.
+    Remove(WindowId),
+    /// Move a window so that its top left is at the given position.
+    Move(WindowId, Point),
+    /// Resize a window like [`Window::resize`], filling new space with the given value.
+    Resize(WindowId, usize, usize, T),
+    /// Move a window up or down so that it has the second id, like
+    /// [`Container::restack`].
+    Restack(WindowId, WindowId),
+    /// Replace a window with another.
+    Replace(WindowId, Window<T>),
+}
+
+impl<T: fmt::Display> Edit<T> {
+    /// Changes the ids the edit refers to after windows are added, removed or
+    /// reordered. Returns false if the window it changes is gone.
+    fn remap(&mut self, f: &impl Fn(WindowId) -> Option<WindowId>) -> bool {
+        match self {
+            Edit::Add(_) => true,
+            // The id of an inserted window is where it goes, which stays put if the
+            // window there is gone.
+            Edit::Insert(idx, _) => {
+                *idx = f(*idx).unwrap_or(*idx);
+                true
+            }
+            Edit::Restack(id, to) => {
+                *to = f(*to).unwrap_or(*to);
+                f(*id).map(|new| *id = new).is_some()
+            }
+            Edit::Remove(id) | Edit::Move(id, _) | Edit::Resize(id, ..) | Edit::Replace(id, _) => {
+                f(*id).map(|new| *id = new).is_some()
+            }
+        }
+    }
+}
+
+/// Edits that can be undone and redone.
+#[derive(Clone, Debug)]
+pub(crate) struct History<T: fmt::Display> {
+    // Edits that undo what was done, most recent last.
+    undo: VecDeque<Edit<T>>,
+    // Edits that redo what was undone, most recent last.
+    redo: Vec<Edit<T>>,
+    limit: usize,
+    // Edit undoing the window drag in progress, if any.
+    pub(crate) pending: Option<Edit<T>>,
+}
+
+impl<T: fmt::Display> History<T> {
+    /// Changes the ids of the recorded edits, forgetting those whose window is gone.
+    pub(crate) fn remap(&mut self, f: &impl Fn(WindowId) -> Option<WindowId>) {
+        self.undo.retain_mut(|edit| edit.remap(f));
+        self.redo.retain_mut(|edit| edit.remap(f));
+        self.pending = None;
+    }
+
+    /// Records an edit undoing what was just done, forgetting anything undone.
+    fn push(&mut self, undo: Edit<T>) {
+        self.redo.clear();
+        self.undo.push_back(undo);
+        if self.undo.len() > self.limit {
+            self.undo.pop_front();
+        }
+    }
+}
+
+impl<T: fmt::Display + Clone> Container<T> {
+    /// Starts recording edits so that they can be undone, remembering up to `limit`
+    /// of them. Only edits made with [`edit`](Self::edit) and windows moved or
+    /// resized with the mouse are recorded.
+    pub fn enable_history(&mut self, limit: usize) {
+        self.history = Some(History {
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            limit,
+            pending: None,
+        });
+    }
+
+    /// Stops recording edits and forgets those recorded.
+    pub fn disable_history(&mut self) {
+        self.history = None;
+    }
+
+    /// Returns true if there is an edit to undo.
+    pub fn can_undo(&self) -> bool {
+        self.history.as_ref().is_some_and(|h| !h.undo.is_empty())
+    }
+
+    /// Returns true if there is an undone edit to redo.
+    pub fn can_redo(&self) -> bool {
+        self.history.as_ref().is_some_and(|h| !h.redo.is_empty())
+    }
+
+    /// Makes a change to the windows, recording it if history is enabled. Returns the
+    /// id of the window changed, which for an added or restacked window is its new
+    /// id.
+    pub fn edit(&mut self, edit: Edit<T>) -> WindowId {
+        let (id, undo) = self.apply_edit(edit);
+        if let Some(history) = &mut self.history {
+            history.push(undo);
+        }
+        id
+    }
+
+    /// Undoes the last edit. Returns false if there was nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        let Some(edit) = self.history.as_mut().and_then(|h| h.undo.pop_back()) else {
+            return false;
+        };
+        let (_, redo) = self.apply_edit(edit);
+        if let Some(history) = &mut self.history {
+            history.redo.push(redo);
+        }
+        true
+    }
+
+    /// Redoes the last undone edit. Returns false if there was nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        let Some(edit) = self.history.as_mut().and_then(|h| h.redo.pop()) else {
+            return false;
+        };
+        let (_, undo) = self.apply_edit(edit);
+        if let Some(history) = &mut self.history {
+            history.undo.push_back(undo);
+        }
+        true
+    }
+
+    /// Makes a change to the windows, returning the id of the window changed and an
+    /// edit that undoes it.
+    fn apply_edit(&mut self, edit: Edit<T>) -> (WindowId, Edit<T>) {
+        match edit {
+            Edit::Add(win) => {
+                let id = self.add_win(win);
+                (id, Edit::Remove(id))
+            }
+            Edit::Insert(idx, win) => {
+                let id = self.insert_win(idx, win);
+                (id, Edit::Remove(id))
+            }
+            Edit::Remove(id) => {
+                let win = self.remove_win(id);
+                (id, Edit::Insert(id, win))
+            }
+            Edit::Move(id, to) => {
+                self.mark_dirty(self.windows[id].footprint().collect::<Vec<_>>());
+                let from = std::mem::replace(&mut self.windows[id].top_left, to);
+                (id, Edit::Move(id, from))
+            }
+            Edit::Resize(id, wid, hgt, fill) => {
+                let old = self.windows[id].clone();
+                self.mark_dirty(old.footprint().collect::<Vec<_>>());
+                self.windows[id].resize(wid, hgt, fill);
+                (id, Edit::Replace(id, old))
+            }
+            Edit::Restack(id, to) => {
+                let to = self.restack(id, to);
+                (to, Edit::Restack(to, id))
+            }
+            Edit::Replace(id, win) => {
+                self.mark_dirty(self.windows[id].footprint().collect::<Vec<_>>());
+                let old = std::mem::replace(&mut self.windows[id], win);
+                (id, Edit::Replace(id, old))
+            }
+        }
+    }
+
+    /// Remembers how to undo a drag of the given window that is starting.
+    pub(crate) fn start_drag_history(&mut self, win: WindowId, moving: bool) {
+        if let Some(history) = &mut self.history {
+            let window = &self.windows[win];
+            history.pending = Some(if moving {
+                Edit::Move(win, window.top_left)
+            } else {
+                Edit::Replace(win, window.clone())
+            });
+        }
+    }
+
+    /// Records the drag that just ended, if it changed anything.
+    pub(crate) fn end_drag_history(&mut self) {
+        let Some(history) = &mut self.history else {
+            return;
+        };
+        let changed = match &history.pending {
+            Some(Edit::Move(win, from)) => self.windows[*win].top_left != *from,
+            Some(Edit::Replace(win, old)) => {
+                let new = &self.windows[*win];
+                new.top_left != old.top_left
+                    || new.width() != old.width()
+                    || new.height() != old.height()
+            }
+            _ => false,
+        };
+        if let Some(undo) = history.pending.take()
+            && changed
+        {
+            history.push(undo);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn undo_after_removing_a_window_below() {
+        let mut container: Container<char> = Container::new();
+        let a = container.add_win(Window::new(Point::new(0, 0)));
+        container.enable_history(10);
+        container.edit(Edit::Add(Window::new(Point::new(5, 0))));
+        container.remove_win(a);
+
+        assert!(container.undo());
+        assert!(container.windows.is_empty());
+        assert!(container.redo());
+        assert_eq!(container.windows.len(), 1);
+        assert_eq!(container.windows[0].top_left, Point::new(5, 0));
+    }
+}
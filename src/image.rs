@@ -0,0 +1,124 @@
+//! Showing pictures in windows by drawing two pixels per cell with half blocks.
+
+use crate::Window;
+use crate::geometry::Point;
+use crate::style::{Cell, Color, Style};
+
+/// Pixels with less opacity than this are left out.
+const ALPHA_THRESHOLD: u8 = 128;
+
+fn color([r, g, b, a]: [u8; 4]) -> Option<Color> {
+    (a >= ALPHA_THRESHOLD).then_some(Color::Rgb(r, g, b))
+}
+
+/// RGBA pixels, stored row by row.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Pixels {
+    pub wid: usize,
+    pub hgt: usize,
+    pub data: Vec<[u8; 4]>,
+}
+
+impl Pixels {
+    /// Create pixels from their data, given row by row, `wid` pixels across. Any
+    /// pixels missing from the last row are transparent.
+    pub fn new(wid: usize, mut data: Vec<[u8; 4]>) -> Self {
+        let hgt = if wid == 0 {
+            0
+        } else {
+            data.len().div_ceil(wid)
+        };
+        data.resize(wid * hgt, [0; 4]);
+        Self { wid, hgt, data }
+    }
+
+    /// Returns the pixel at the given position, if there is one.
+    pub fn get(&self, x: usize, y: usize) -> Option<[u8; 4]> {
+        if x >= self.wid {
+            return None;
+        }
+        self.data.get(y * self.wid + x).copied()
+    }
+
+    /// Returns the pixels stretched or shrunk to a new size, picking the nearest
+    /// pixel for each.
+    pub fn scaled(&self, wid: usize, hgt: usize) -> Self {
+        let mut data = Vec::with_capacity(wid * hgt);
+        for y in 0..hgt {
+            for x in 0..wid {
+                let pixel = self.get(x * self.wid / wid, y * self.hgt / hgt);
+                data.push(pixel.unwrap_or([0; 4]));
+            }
+        }
+        Self { wid, hgt, data }
+    }
+}
+
+/// Returns a cell showing one colour above another, where None is transparent.
+fn half_block(top: Option<Color>, bottom: Option<Color>) -> Cell {
+    match (top, bottom) {
+        (Some(top), bottom) => Cell::new(
+            '▀',
+            Style {
+                fg: Some(top),
+                bg: bottom,
+                ..Style::new()
+            },
+        ),
+        (None, Some(bottom)) => Cell::new('▄', Style::new().fg(bottom)),
+        (None, None) => Cell::default(),
+    }
+}
+
+impl Window<Cell> {
+    /// Create a window at the top left of the screen showing some RGBA pixels, given
+    /// row by row, `wid` pixels across. Each cell shows two pixels above each other,
+    /// so the window is `wid` wide and half as tall as the pixels, rounding up.
+    pub fn from_pixels(pixels: &[[u8; 4]], wid: usize) -> Self {
+        let mut win = Self::new(Point::new(0, 0));
+        if wid == 0 {
+            return win;
+        }
+
+        let rows: Vec<&[[u8; 4]]> = pixels.chunks(wid).collect();
+        win.data = rows
+            .chunks(2)
+            .map(|pair| {
+                (0..wid)
+                    .map(|x| {
+                        let top = pair[0].get(x).copied().and_then(color);
+                        let bottom = pair.get(1).and_then(|r| r.get(x)).copied().and_then(color);
+                        half_block(top, bottom)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        win
+    }
+
+    /// Gives the window pixels to be shown over it at the terminal's own resolution
+    /// where the backend supports it, and sets its contents to the pixels drawn with
+    /// half blocks in `wid` by `hgt` cells for where it doesn't.
+    pub fn set_pixels(&mut self, pixels: Pixels, wid: usize, hgt: usize) {
+        let fallback = pixels.scaled(wid, hgt * 2);
+        self.data = Self::from_pixels(&fallback.data, wid).data;
+        self.pixels = Some(pixels);
+    }
+
+    /// Create a window at the top left of the screen showing an image scaled to
+    /// `wid` by `hgt` cells.
+    #[cfg(feature = "image")]
+    pub fn from_image(img: &::image::DynamicImage, wid: usize, hgt: usize) -> Self {
+        let scaled = img
+            .resize_exact(
+                wid as u32,
+                hgt as u32 * 2,
+                ::image::imageops::FilterType::Triangle,
+            )
+            .to_rgba8();
+        let pixels: Vec<[u8; 4]> = scaled.pixels().map(|p| p.0).collect();
+
+        Self::from_pixels(&pixels, wid)
+    }
+}
@@ -0,0 +1,309 @@
+//! Decoding of the bytes a terminal sends on standard input into events.
+
+use crate::event::{Event, KeyCode, KeyEvent, Modifiers, MouseButton, MouseEvent, MouseKind};
+use crate::geometry::{Coord, Point};
+
+/// Result of trying to decode a single event from the front of some input.
+enum Parsed {
+    /// An event and the number of bytes it took up.
+    Event(Event, usize),
+    /// A number of bytes that don't mean anything we understand.
+    Skip(usize),
+    /// The input ends partway through an event.
+    Incomplete,
+}
+
+/// Turns terminal input into events, holding on to sequences split across reads.
+#[derive(Debug, Default)]
+pub(crate) struct Parser {
+    buf: Vec<u8>,
+}
+
+impl Parser {
+    /// Decodes as many events as possible from the given bytes and any left over from
+    /// before.
+    pub(crate) fn feed(&mut self, bytes: &[u8]) -> Vec<Event> {
+        self.buf.extend_from_slice(bytes);
+        let mut events = Vec::new();
+        let mut idx = 0;
+
+        while idx < self.buf.len() {
+            match parse(&self.buf[idx..]) {
+                Parsed::Event(ev, len) => {
+                    events.push(ev);
+                    idx += len;
+                }
+                Parsed::Skip(len) => idx += len,
+                Parsed::Incomplete => break,
+            }
+        }
+
+        self.buf.drain(..idx);
+        events
+    }
+}
+
+fn key(code: KeyCode, modifiers: Modifiers) -> Event {
+    Event::Key(KeyEvent::new(code, modifiers))
+}
+
+fn parse(bytes: &[u8]) -> Parsed {
+    match bytes[0] {
+        0x1b => parse_escape(bytes),
+        b'\r' | b'\n' => Parsed::Event(key(KeyCode::Enter, Modifiers::NONE), 1),
+        b'\t' => Parsed::Event(key(KeyCode::Tab, Modifiers::NONE), 1),
+        0x7f | 0x08 => Parsed::Event(key(KeyCode::Backspace, Modifiers::NONE), 1),
+        0x00 => Parsed::Event(key(KeyCode::Char(' '), Modifiers::CTRL), 1),
+        b @ 0x01..=0x1a => {
+            let ch = (b'a' + b - 1) as char;
+            Parsed::Event(key(KeyCode::Char(ch), Modifiers::CTRL), 1)
+        }
+        0x1c..=0x1f => Parsed::Skip(1),
+        _ => parse_char(bytes),
+    }
+}
+
+fn parse_char(bytes: &[u8]) -> Parsed {
+    let len = match bytes[0] {
+        0x00..=0x7f => 1,
+        0xc0..=0xdf => 2,
+        0xe0..=0xef => 3,
+        0xf0..=0xf7 => 4,
+        _ => return Parsed::Skip(1),
+    };
+
+    if bytes.len() < len {
+        return Parsed::Incomplete;
+    }
+
+    match std::str::from_utf8(&bytes[..len]) {
+        Ok(s) => {
+            let ch = s.chars().next().unwrap();
+            let modifiers = if ch.is_uppercase() {
+                Modifiers::SHIFT
+            } else {
+                Modifiers::NONE
+            };
+            Parsed::Event(key(KeyCode::Char(ch), modifiers), len)
+        }
+        Err(_) => Parsed::Skip(1),
+    }
+}
+
+fn parse_escape(bytes: &[u8]) -> Parsed {
+    match bytes.get(1) {
+        // Nothing follows, so this was the escape key itself.
+        None => Parsed::Event(key(KeyCode::Esc, Modifiers::NONE), 1),
+        Some(b'[') => parse_csi(bytes),
+        Some(b'O') => {
+            let Some(&fin) = bytes.get(2) else {
+                return Parsed::Incomplete;
+            };
+            match final_key(fin) {
+                Some(code) => Parsed::Event(key(code, Modifiers::NONE), 3),
+                None => Parsed::Skip(3),
+            }
+        }
+        Some(0x1b) => Parsed::Event(key(KeyCode::Esc, Modifiers::NONE), 1),
+        // Escape followed by a key means that key was pressed with alt.
+        Some(_) => match parse(&bytes[1..]) {
+            Parsed::Event(Event::Key(mut ev), len) => {
+                ev.modifiers.alt = true;
+                Parsed::Event(Event::Key(ev), len + 1)
+            }
+            Parsed::Event(ev, len) => Parsed::Event(ev, len + 1),
+            Parsed::Skip(len) => Parsed::Skip(len + 1),
+            Parsed::Incomplete => Parsed::Incomplete,
+        },
+    }
+}
+
+/// Key given by the final byte of an SS3 or CSI sequence.
+fn final_key(fin: u8) -> Option<KeyCode> {
+    Some(match fin {
+        b'A' => KeyCode::Up,
+        b'B' => KeyCode::Down,
+        b'C' => KeyCode::Right,
+        b'D' => KeyCode::Left,
+        b'H' => KeyCode::Home,
+        b'F' => KeyCode::End,
+        b'Z' => KeyCode::BackTab,
+        b'P' => KeyCode::F(1),
+        b'Q' => KeyCode::F(2),
+        b'R' => KeyCode::F(3),
+        b'S' => KeyCode::F(4),
+        _ => return None,
+    })
+}
+
+/// Key given by the number of a CSI sequence ending in `~`.
+fn tilde_key(num: u16) -> Option<KeyCode> {
+    Some(match num {
+        1 | 7 => KeyCode::Home,
+        2 => KeyCode::Insert,
+        3 => KeyCode::Delete,
+        4 | 8 => KeyCode::End,
+        5 => KeyCode::PageUp,
+        6 => KeyCode::PageDown,
+        11..=15 => KeyCode::F((num - 10) as u8),
+        17..=21 => KeyCode::F((num - 11) as u8),
+        23 | 24 => KeyCode::F((num - 12) as u8),
+        _ => return None,
+    })
+}
+
+/// Decodes the modifier parameter used by xterm style key sequences.
+fn modifiers(param: u16) -> Modifiers {
+    let bits = param.saturating_sub(1);
+    Modifiers {
+        shift: bits & 1 != 0,
+        alt: bits & 2 != 0,
+        ctrl: bits & 4 != 0,
+    }
+}
+
+fn parse_csi(bytes: &[u8]) -> Parsed {
+    // Find the final byte of the sequence.
+    let Some(end) = bytes[2..].iter().position(|b| (0x40..=0x7e).contains(b)) else {
+        return Parsed::Incomplete;
+    };
+    let end = end + 2;
+    let len = end + 1;
+    let fin = bytes[end];
+    let body = &bytes[2..end];
+
+    if body == b"200" && fin == b'~' {
+        return parse_paste(&bytes[len..]).map_or(Parsed::Incomplete, |(text, paste_len)| {
+            Parsed::Event(Event::Paste(text), len + paste_len)
+        });
+    }
+
+    if body.first() == Some(&b'<') {
+        return match parse_sgr_mouse(&body[1..], fin) {
+            Some(ev) => Parsed::Event(Event::Mouse(ev), len),
+            None => Parsed::Skip(len),
+        };
+    }
+
+    // Replies to queries start with a private marker rather than a number.
+    if body
+        .first()
+        .is_some_and(|b| !b.is_ascii_digit() && *b != b';')
+    {
+        return Parsed::Skip(len);
+    }
+
+    // Each parameter may have sub-parameters after colons, such as the kind of key
+    // event after the modifiers in the kitty keyboard protocol.
+    let params: Vec<Vec<u32>> = body
+        .split(|&b| b == b';')
+        .map(|p| {
+            p.split(|&b| b == b':')
+                .map(|p| {
+                    std::str::from_utf8(p)
+                        .ok()
+                        .and_then(|p| p.parse().ok())
+                        .unwrap_or(0)
+                })
+                .collect()
+        })
+        .collect();
+    let param = |idx: usize, sub: usize| params.get(idx).and_then(|p| p.get(sub)).copied();
+    let mut mods = modifiers(param(1, 0).unwrap_or(1) as u16);
+    let release = param(1, 1) == Some(3);
+
+    let code = match fin {
+        b'~' => tilde_key(param(0, 0).unwrap_or(0) as u16),
+        b'u' => param(0, 0).and_then(unicode_key),
+        _ => final_key(fin),
+    };
+    let code = match code {
+        Some(KeyCode::Tab) if mods.shift => KeyCode::BackTab,
+        Some(KeyCode::BackTab) => {
+            mods = Modifiers::SHIFT;
+            KeyCode::BackTab
+        }
+        // Keys are reported without shift applied, where other input has it applied.
+        Some(KeyCode::Char(ch)) if mods.shift && ch.is_lowercase() => {
+            KeyCode::Char(ch.to_uppercase().next().unwrap_or(ch))
+        }
+        Some(code) => code,
+        None => return Parsed::Skip(len),
+    };
+
+    let ev = KeyEvent::new(code, mods);
+    if release {
+        Parsed::Event(Event::KeyRelease(ev), len)
+    } else {
+        Parsed::Event(Event::Key(ev), len)
+    }
+}
+
+/// Key given by the number of a CSI sequence ending in `u`, as sent by the kitty
+/// keyboard protocol, which is the unicode code point of the key for most.
+fn unicode_key(num: u32) -> Option<KeyCode> {
+    Some(match num {
+        9 => KeyCode::Tab,
+        13 => KeyCode::Enter,
+        27 => KeyCode::Esc,
+        127 => KeyCode::Backspace,
+        // The rest of the private use area holds keys such as modifiers on their own,
+        // which aren't reported.
+        0xe000..=0xf8ff => return None,
+        _ => KeyCode::Char(char::from_u32(num).filter(|ch| !ch.is_control())?),
+    })
+}
+
+/// Sequence sent by the terminal after pasted text in bracketed paste mode.
+const PASTE_END: &[u8] = b"\x1b[201~";
+
+/// Finds the end of some pasted text, returning the text and the number of bytes up
+/// to and including the end sequence.
+fn parse_paste(bytes: &[u8]) -> Option<(String, usize)> {
+    let end = bytes
+        .windows(PASTE_END.len())
+        .position(|w| w == PASTE_END)?;
+    // Terminals send line breaks in pasted text as carriage returns.
+    let text = String::from_utf8_lossy(&bytes[..end])
+        .replace("\r\n", "\n")
+        .replace('\r', "\n");
+
+    Some((text, end + PASTE_END.len()))
+}
+
+/// Decodes the body of an SGR mouse report, such as `0;12;5` followed by `M`.
+fn parse_sgr_mouse(body: &[u8], fin: u8) -> Option<MouseEvent> {
+    let body = std::str::from_utf8(body).ok()?;
+    let mut parts = body.split(';').map(|p| p.parse::<u16>().ok());
+    let cb = parts.next()??;
+    let x = parts.next()?? as Coord - 1;
+    let y = parts.next()?? as Coord - 1;
+
+    let button = match cb & 0b11 {
+        0 => Some(MouseButton::Left),
+        1 => Some(MouseButton::Middle),
+        2 => Some(MouseButton::Right),
+        _ => None,
+    };
+
+    let kind = if cb & 64 != 0 {
+        if cb & 1 == 0 {
+            MouseKind::ScrollUp
+        } else {
+            MouseKind::ScrollDown
+        }
+    } else if cb & 32 != 0 {
+        match button {
+            Some(button) => MouseKind::Drag(button),
+            None => MouseKind::Moved,
+        }
+    } else if fin == b'm' {
+        MouseKind::Up(button?)
+    } else if fin == b'M' {
+        MouseKind::Down(button?)
+    } else {
+        return None;
+    };
+
+    Some(MouseEvent::new(kind, Point::new(x, y)))
+}
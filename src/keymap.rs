@@ -0,0 +1,229 @@
+//! Bindings from sequences of key presses to actions.
+
+use crate::event::{Event, KeyCode, KeyEvent, Modifiers};
+use crate::{Container, TrackedId};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Outcome of feeding a key press to a [`Keymap`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum KeyResult<A> {
+    /// The key completed a bound sequence.
+    Action(A),
+    /// The key continued a bound sequence that isn't finished yet.
+    Pending,
+    /// The keys pressed don't match anything bound, and have been forgotten.
+    Unbound,
+}
+
+/// Maps sequences of key presses, such as `C-b` then `%`, to actions. Bindings can be
+/// made for a single [tracked](Container::track) window, which take priority over
+/// global bindings while that window is focused.
+#[derive(Clone, Debug)]
+pub struct Keymap<A> {
+    global: HashMap<Vec<KeyEvent>, A>,
+    windows: HashMap<TrackedId, HashMap<Vec<KeyEvent>, A>>,
+    // Keys of a sequence pressed so far.
+    pending: Vec<KeyEvent>,
+}
+
+impl<A> Default for Keymap<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A> Keymap<A> {
+    /// Create a keymap with no bindings.
+    pub fn new() -> Self {
+        Self {
+            global: HashMap::new(),
+            windows: HashMap::new(),
+            pending: Vec::new(),
+        }
+    }
+
+    /// Bind a sequence of keys to an action regardless of which window is focused.
+    pub fn bind(&mut self, keys: impl Into<Vec<KeyEvent>>, action: A) {
+        self.global.insert(keys.into(), action);
+    }
+
+    /// Bind a sequence of keys to an action while the given window is focused. The
+    /// binding follows the window as other windows are added and removed.
+    pub fn bind_for(&mut self, win: TrackedId, keys: impl Into<Vec<KeyEvent>>, action: A) {
+        self.windows
+            .entry(win)
+            .or_default()
+            .insert(keys.into(), action);
+    }
+
+    /// Bind a sequence written like `"C-b %"` to an action. See [`parse_keys`] for the
+    /// format. Returns false if the sequence could not be parsed.
+    pub fn bind_str(&mut self, keys: &str, action: A) -> bool {
+        match parse_keys(keys) {
+            Some(keys) => {
+                self.bind(keys, action);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the keys of a sequence pressed so far.
+    pub fn pending(&self) -> &[KeyEvent] {
+        &self.pending
+    }
+
+    /// Forget any keys of a sequence pressed so far.
+    pub fn reset(&mut self) {
+        self.pending.clear();
+    }
+
+    /// Feed a key press, resolving bindings for the given focused window first and
+    /// global bindings after.
+    pub fn feed(&mut self, key: KeyEvent, focused: Option<TrackedId>) -> KeyResult<A>
+    where
+        A: Clone,
+    {
+        self.pending.push(key);
+        let local = focused.and_then(|win| self.windows.get(&win));
+
+        let found = local
+            .and_then(|map| map.get(&self.pending))
+            .or_else(|| self.global.get(&self.pending));
+        if let Some(action) = found {
+            let action = action.clone();
+            self.pending.clear();
+            return KeyResult::Action(action);
+        }
+
+        let is_prefix = |map: &HashMap<Vec<KeyEvent>, A>| {
+            map.keys()
+                .any(|keys| keys.len() > self.pending.len() && keys.starts_with(&self.pending))
+        };
+        if local.is_some_and(is_prefix) || is_prefix(&self.global) {
+            return KeyResult::Pending;
+        }
+
+        self.pending.clear();
+        KeyResult::Unbound
+    }
+
+    /// Resolve an event from a container's event loop, using the container's focused
+    /// window. Returns the action if the event completed a bound sequence.
+    pub fn resolve<T: fmt::Display>(&mut self, event: &Event, container: &Container<T>) -> Option<A>
+    where
+        A: Clone,
+    {
+        match event {
+            Event::Key(key) => {
+                let focused = container.focused().and_then(|win| {
+                    self.windows
+                        .keys()
+                        .copied()
+                        .find(|&id| container.tracked(id) == Some(win))
+                });
+                match self.feed(*key, focused) {
+                    KeyResult::Action(action) => Some(action),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Parse a single key such as `a`, `C-b`, `A-Enter` or `F5`. The modifiers `C-`
+/// (control), `A-` or `M-` (alt) and `S-` (shift) can be combined, and named keys
+/// are `Enter`, `Esc`, `Backspace`, `Tab`, `BackTab`, `Space`, `Left`, `Right`,
+/// `Up`, `Down`, `Home`, `End`, `PageUp`, `PageDown`, `Insert`, `Delete` and `F1`
+/// to `F12`.
+pub fn parse_key(s: &str) -> Option<KeyEvent> {
+    let mut modifiers = Modifiers::NONE;
+    let mut rest = s;
+
+    while rest.len() > 2 && rest.as_bytes()[1] == b'-' {
+        match &rest[..1] {
+            "C" => modifiers.ctrl = true,
+            "A" | "M" => modifiers.alt = true,
+            "S" => modifiers.shift = true,
+            _ => return None,
+        }
+        rest = &rest[2..];
+    }
+
+    let code = match rest {
+        "Enter" => KeyCode::Enter,
+        "Esc" => KeyCode::Esc,
+        "Backspace" => KeyCode::Backspace,
+        "Tab" => KeyCode::Tab,
+        "BackTab" => KeyCode::BackTab,
+        "Space" => KeyCode::Char(' '),
+        "Left" => KeyCode::Left,
+        "Right" => KeyCode::Right,
+        "Up" => KeyCode::Up,
+        "Down" => KeyCode::Down,
+        "Home" => KeyCode::Home,
+        "End" => KeyCode::End,
+        "PageUp" => KeyCode::PageUp,
+        "PageDown" => KeyCode::PageDown,
+        "Insert" => KeyCode::Insert,
+        "Delete" => KeyCode::Delete,
+        _ => {
+            let mut chars = rest.chars();
+            match (chars.next(), chars.next()) {
+                (Some(ch), None) => {
+                    // Terminals report upper case letters as being typed with shift.
+                    if ch.is_uppercase() {
+                        modifiers.shift = true;
+                    }
+                    KeyCode::Char(ch)
+                }
+                (Some('F'), Some(_)) => KeyCode::F(rest[1..].parse().ok()?),
+                _ => return None,
+            }
+        }
+    };
+
+    Some(KeyEvent::new(code, modifiers))
+}
+
+/// Parse a sequence of keys separated by whitespace, such as `"C-b %"`. See
+/// [`parse_key`] for the format of each key.
+pub fn parse_keys(s: &str) -> Option<Vec<KeyEvent>> {
+    s.split_whitespace().map(parse_key).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Window;
+    use crate::geometry::Point;
+
+    #[test]
+    fn window_bindings_follow_their_window() {
+        let mut container: Container<char> = Container::new();
+        let below = container.add_win(Window::new(Point::new(0, 0)));
+        let win = container.add_win(Window::new(Point::new(0, 0)));
+        let mut keymap = Keymap::new();
+        keymap.bind_for(container.track(win), parse_keys("C-b x").unwrap(), "close");
+        keymap.bind_str("x", "global");
+
+        container.remove_win(below);
+        container.insert_win(1, Window::new(Point::new(0, 0)));
+        fn press(
+            keymap: &mut Keymap<&'static str>,
+            container: &Container<char>,
+            key: &str,
+        ) -> Option<&'static str> {
+            keymap.resolve(&Event::Key(parse_key(key).unwrap()), container)
+        }
+
+        container.set_focus(Some(1));
+        assert_eq!(press(&mut keymap, &container, "C-b"), None);
+        assert_eq!(press(&mut keymap, &container, "x"), Some("global"));
+        container.set_focus(Some(0));
+        assert_eq!(press(&mut keymap, &container, "C-b"), None);
+        assert_eq!(press(&mut keymap, &container, "x"), Some("close"));
+    }
+}
@@ -0,0 +1,95 @@
+//! The kitty graphics protocol, which shows images transmitted to the terminal once
+//! and then placed by id.
+
+use crate::geometry::Point;
+use crate::image::Pixels;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::hash::{Hash, Hasher};
+
+/// Largest amount of encoded data sent in one escape sequence.
+const CHUNK_LEN: usize = 4096;
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn base64(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// Returns the id an image is transmitted with, derived from its pixels so that the
+/// same image is only sent once.
+pub(crate) fn image_id(pixels: &Pixels) -> u32 {
+    let mut hasher = DefaultHasher::new();
+    pixels.hash(&mut hasher);
+    // Zero isn't a valid id.
+    (hasher.finish() as u32).max(1)
+}
+
+/// Returns the escape sequences transmitting an image with the given id.
+pub(crate) fn transmit(id: u32, pixels: &Pixels) -> String {
+    let bytes: Vec<u8> = pixels.data.iter().flatten().copied().collect();
+    let data = base64(&bytes);
+    let chunks: Vec<&[u8]> = data.as_bytes().chunks(CHUNK_LEN).collect();
+
+    let mut out = String::new();
+    for (n, chunk) in chunks.iter().enumerate() {
+        let more = (n + 1 < chunks.len()) as u8;
+        if n == 0 {
+            let _ = write!(
+                out,
+                "\x1b_Ga=t,q=2,f=32,i={id},s={},v={},m={more};",
+                pixels.wid, pixels.hgt
+            );
+        } else {
+            let _ = write!(out, "\x1b_Gm={more};");
+        }
+        out.push_str(std::str::from_utf8(chunk).unwrap_or_default());
+        out.push_str("\x1b\\");
+    }
+    out
+}
+
+/// Returns the escape sequence placing an image at the cursor, replacing any
+/// placement with the same id.
+pub(crate) fn place(id: u32, placement: u32, z: i32) -> String {
+    format!("\x1b_Ga=p,q=2,C=1,i={id},p={placement},z={z}\x1b\\")
+}
+
+/// Returns the escape sequence deleting one placement of an image, or the image and
+/// all its placements if None.
+pub(crate) fn delete(id: u32, placement: Option<u32>) -> String {
+    match placement {
+        Some(p) => format!("\x1b_Ga=d,q=2,d=i,i={id},p={p}\x1b\\"),
+        None => format!("\x1b_Ga=d,q=2,d=I,i={id}\x1b\\"),
+    }
+}
+
+/// Where an image is placed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct Placement {
+    pub(crate) image: u32,
+    pub(crate) pos: Point,
+    pub(crate) z: i32,
+}
+
+/// The images a container has sent to a terminal and where they are placed.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Placements {
+    pub(crate) transmitted: HashSet<u32>,
+    pub(crate) placed: HashMap<u32, Placement>,
+}
@@ -0,0 +1,451 @@
+//! Building windows from a layout described in a TOML or JSON file, so that it can be
+//! changed without recompiling.
+//!
+//! ```toml
+//! [[windows]]
+//! name = "log"
+//! x = 0
+//! y = 0
+//! width = "50%"
+//! height = "100%"
+//! title = "Log"
+//! border = "rounded"
+//! style = { fg = "green" }
+//! ```
+
+use crate::geometry::{Coord, Point, Rect};
+use crate::style::{Cell, Style};
+use crate::widgets::blank;
+use crate::{BorderSet, Container, TrackedId, Window, WindowId};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+#[cfg(feature = "notify")]
+use std::{path::PathBuf, sync::mpsc};
+
+/// A position or size, either in cells or as a share of the screen.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(try_from = "RawDimension")]
+pub enum Dimension {
+    /// A number of cells. Negative positions are counted back from the right or
+    /// bottom of the screen.
+    Cells(Coord),
+    /// A percentage of the width or height of the screen, written like `"50%"`.
+    Percent(f64),
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawDimension {
+    Cells(Coord),
+    Text(String),
+}
+
+impl TryFrom<RawDimension> for Dimension {
+    type Error = String;
+
+    fn try_from(raw: RawDimension) -> Result<Self, Self::Error> {
+        match raw {
+            RawDimension::Cells(n) => Ok(Dimension::Cells(n)),
+            RawDimension::Text(text) => text
+                .strip_suffix('%')
+                .and_then(|n| n.trim().parse().ok())
+                .map(Dimension::Percent)
+                .ok_or_else(|| format!("expected a number or percentage, found {text:?}")),
+        }
+    }
+}
+
+impl Dimension {
+    /// Returns the number of cells this is out of `total`.
+    fn cells(self, total: u16) -> Coord {
+        match self {
+            Dimension::Cells(n) if n < 0 => total as Coord + n,
+            Dimension::Cells(n) => n,
+            Dimension::Percent(p) => (total as f64 * p / 100.0).round() as Coord,
+        }
+    }
+}
+
+/// Lines drawn around the edge of a window.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Border {
+    #[default]
+    None,
+    Plain,
+    Rounded,
+    Double,
+    Thick,
+}
+
+impl Border {
+    /// Returns how far in from each edge the border is.
+    fn inset(self) -> usize {
+        match self {
+            Border::None => 0,
+            _ => 1,
+        }
+    }
+
+    /// Returns the characters the border is drawn with.
+    fn set(self) -> Option<BorderSet> {
+        Some(match self {
+            Border::None => return None,
+            Border::Plain => BorderSet::PLAIN,
+            Border::Rounded => BorderSet::ROUNDED,
+            Border::Double => BorderSet::DOUBLE,
+            Border::Thick => BorderSet::THICK,
+        })
+    }
+}
+
+/// How one window is laid out.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct WindowConfig {
+    /// Name the window is looked up by.
+    pub name: String,
+    pub x: Dimension,
+    pub y: Dimension,
+    /// Width of the window, including any border.
+    pub width: Dimension,
+    /// Height of the window, including any border.
+    pub height: Dimension,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub border: Border,
+    #[serde(default)]
+    pub style: Style,
+    #[serde(default)]
+    pub resizable: bool,
+    #[serde(default)]
+    pub min_size: Option<(usize, usize)>,
+    #[serde(default)]
+    pub max_size: Option<(usize, usize)>,
+}
+
+impl WindowConfig {
+    /// Returns the area the window takes up on a screen of the given size.
+    pub fn rect(&self, screen: (u16, u16)) -> Rect {
+        let x = self.x.cells(screen.0);
+        let y = self.y.cells(screen.1);
+        let wid = self.width.cells(screen.0).max(0) as usize;
+        let hgt = self.height.cells(screen.1).max(0) as usize;
+        Rect::new(Point::new(x, y), wid, hgt)
+    }
+
+    /// Builds the window on a screen of the given size.
+    pub fn build(&self, screen: (u16, u16)) -> Window<Cell> {
+        let rect = self.rect(screen);
+        let mut win = Window::new(rect.top_left);
+        win.data = blank(rect.wid, rect.hgt, self.style);
+        win.title = self.title.clone();
+        win.resizable = self.resizable;
+        if let Some(min_size) = self.min_size {
+            win.min_size = min_size;
+        }
+        win.max_size = self.max_size;
+        win.inset = self.border.inset();
+        if let Some(border) = self.border.set() {
+            let style = self.style;
+            win.draw_border_with(border, self.title.as_deref(), |ch| Cell::new(ch, style));
+        }
+
+        win
+    }
+}
+
+/// A layout of windows read from a config file.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+pub struct LayoutConfig {
+    #[serde(default)]
+    pub windows: Vec<WindowConfig>,
+}
+
+pub(crate) fn invalid(err: impl std::error::Error + Send + Sync + 'static) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
+
+impl LayoutConfig {
+    /// Parses a layout written in TOML.
+    pub fn from_toml(text: &str) -> io::Result<Self> {
+        toml::from_str(text).map_err(invalid)
+    }
+
+    /// Parses a layout written in JSON.
+    pub fn from_json(text: &str) -> io::Result<Self> {
+        serde_json::from_str(text).map_err(invalid)
+    }
+
+    /// Returns an error if two windows have the same name, as they couldn't be told
+    /// apart.
+    fn check_names(&self) -> io::Result<()> {
+        let mut names = HashSet::new();
+        match self.windows.iter().find(|w| !names.insert(w.name.as_str())) {
+            Some(w) => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("more than one window is named {:?}", w.name),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Reads a layout from a file, which is parsed as JSON if its name ends in
+    /// `.json` and TOML otherwise.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)?;
+        if path.extension().is_some_and(|ext| ext == "json") {
+            Self::from_json(&text)
+        } else {
+            Self::from_toml(&text)
+        }
+    }
+}
+
+/// The windows added by a layout, looked up by name. The container keeps track of
+/// them, so their ids are found again however other windows come and go.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Layout {
+    // Tracked window and border of each window.
+    windows: HashMap<String, (TrackedId, Border)>,
+}
+
+impl Layout {
+    /// Returns the id of the window with the given name, if it is still there.
+    pub fn get<T: fmt::Display>(&self, container: &Container<T>, name: &str) -> Option<WindowId> {
+        self.windows
+            .get(name)
+            .and_then(|&(id, _)| container.tracked(id))
+    }
+
+    /// Returns the name and id of every window still there.
+    pub fn iter<'a, T: fmt::Display>(
+        &'a self,
+        container: &'a Container<T>,
+    ) -> impl Iterator<Item = (&'a str, WindowId)> {
+        self.windows
+            .iter()
+            .filter_map(|(name, &(id, _))| Some((name.as_str(), container.tracked(id)?)))
+    }
+}
+
+/// Copies what is inside the border of one window's contents to inside the border of
+/// another's, as far as it fits.
+fn copy_inside(from: &[Vec<Cell>], from_inset: usize, to: &mut [Vec<Cell>], to_inset: usize) {
+    let from_rows = from.len().saturating_sub(from_inset);
+    let to_rows = to.len().saturating_sub(to_inset);
+    let rows = from.get(from_inset..from_rows).unwrap_or_default();
+
+    for (src, dst) in rows.iter().zip(to.iter_mut().take(to_rows).skip(to_inset)) {
+        let src_end = src.len().saturating_sub(from_inset);
+        let dst_end = dst.len().saturating_sub(to_inset);
+        let src = src.get(from_inset..src_end).unwrap_or_default();
+        let dst = dst.get_mut(to_inset..dst_end).unwrap_or_default();
+        for (d, s) in dst.iter_mut().zip(src) {
+            *d = *s;
+        }
+    }
+}
+
+impl Container<Cell> {
+    /// Adds every window of a layout on top of the others, sized for the current
+    /// screen. Nothing is added if two windows have the same name.
+    pub fn apply_layout(&mut self, config: &LayoutConfig) -> io::Result<Layout> {
+        config.check_names()?;
+        let screen = self.screen_size();
+        let windows = config
+            .windows
+            .iter()
+            .map(|w| {
+                let win = self.add_win(w.build(screen));
+                (w.name.clone(), (self.track(win), w.border))
+            })
+            .collect();
+
+        Ok(Layout { windows })
+    }
+
+    /// Changes the windows of a layout to match a new version of it. Windows whose
+    /// names are still there are moved and resized, keeping what is inside their
+    /// borders, new ones are added on top and the rest are removed. Nothing is changed
+    /// if two windows have the same name.
+    pub fn update_layout(&mut self, layout: &mut Layout, config: &LayoutConfig) -> io::Result<()> {
+        config.check_names()?;
+        let screen = self.screen_size();
+        let names: HashSet<&str> = config.windows.iter().map(|w| w.name.as_str()).collect();
+
+        let removed: Vec<TrackedId> = layout
+            .windows
+            .iter()
+            .filter(|(name, _)| !names.contains(name.as_str()))
+            .map(|(_, &(id, _))| id)
+            .collect();
+        layout
+            .windows
+            .retain(|name, _| names.contains(name.as_str()));
+        for id in removed {
+            if let Some(win) = self.untrack(id) {
+                self.remove_win(win);
+            }
+        }
+
+        for w in config.windows.iter() {
+            let mut win = w.build(screen);
+            // Windows removed from the container since are added again.
+            let existing = layout
+                .windows
+                .get(&w.name)
+                .and_then(|&(id, border)| Some((self.tracked(id)?, border)));
+            match existing {
+                Some((id, border)) => {
+                    let old = &self.windows[id];
+                    copy_inside(&old.data, border.inset(), &mut win.data, w.border.inset());
+                    win.hidden = old.hidden;
+                    let old = std::mem::replace(&mut self.windows[id], win);
+                    self.mark_dirty(old.footprint().collect::<Vec<_>>());
+                    if let Some((_, border)) = layout.windows.get_mut(&w.name) {
+                        *border = w.border;
+                    }
+                }
+                None => {
+                    let id = self.add_win(win);
+                    let tracked = self.track(id);
+                    layout.windows.insert(w.name.clone(), (tracked, w.border));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Watches a layout file, reading it again whenever it changes.
+#[cfg(feature = "notify")]
+pub struct LayoutWatcher {
+    path: PathBuf,
+    events: mpsc::Receiver<::notify::Result<::notify::Event>>,
+    // Kept so that watching doesn't stop.
+    _watcher: ::notify::RecommendedWatcher,
+}
+
+#[cfg(feature = "notify")]
+impl LayoutWatcher {
+    /// Start watching a layout file.
+    pub fn new(path: impl Into<PathBuf>) -> io::Result<Self> {
+        use ::notify::Watcher;
+
+        let path = path.into();
+        let (tx, events) = mpsc::channel();
+        let mut watcher = ::notify::recommended_watcher(move |ev| {
+            let _ = tx.send(ev);
+        })
+        .map_err(io::Error::other)?;
+
+        // Editors often save by replacing the file, so watch the directory it is in.
+        let dir = path
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        watcher
+            .watch(dir, ::notify::RecursiveMode::NonRecursive)
+            .map_err(io::Error::other)?;
+
+        Ok(Self {
+            path,
+            events,
+            _watcher: watcher,
+        })
+    }
+
+    /// Returns the layout read again from the file if it has changed since this was
+    /// last called, or None if it hasn't.
+    pub fn poll(&self) -> Option<io::Result<LayoutConfig>> {
+        let mut changed = false;
+        for ev in self.events.try_iter() {
+            match ev {
+                Ok(ev) => {
+                    changed |= (ev.kind.is_modify() || ev.kind.is_create())
+                        && ev
+                            .paths
+                            .iter()
+                            .any(|p| p.file_name() == self.path.file_name());
+                }
+                Err(err) => return Some(Err(io::Error::other(err))),
+            }
+        }
+
+        changed.then(|| LayoutConfig::load(&self.path))
+    }
+}
+
+#[cfg(feature = "notify")]
+impl Container<Cell> {
+    /// Updates a layout if its file has changed, returning true if it has. This is
+    /// meant to be called regularly, such as on each tick.
+    pub fn reload_layout(
+        &mut self,
+        watcher: &LayoutWatcher,
+        layout: &mut Layout,
+    ) -> io::Result<bool> {
+        match watcher.poll() {
+            Some(config) => {
+                self.update_layout(layout, &config?)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn update_after_a_window_below_is_removed() {
+        let config = LayoutConfig::from_toml(
+            r#"
+            [[windows]]
+            name = "log"
+            x = 0
+            y = 0
+            width = 10
+            height = 3
+            "#,
+        )
+        .unwrap();
+        let mut container: Container<Cell> = Container::new();
+        container.set_screen_size(40, 12);
+        let below = container.add_win(Window::new(Point::new(0, 5)));
+        let mut layout = container.apply_layout(&config).unwrap();
+        container.remove_win(below);
+        assert_eq!(layout.get(&container, "log"), Some(0));
+
+        container.update_layout(&mut layout, &config).unwrap();
+        assert_eq!(container.windows.len(), 1);
+        assert_eq!(layout.get(&container, "log"), Some(0));
+    }
+
+    #[test]
+    fn duplicate_names_are_rejected() {
+        let config = LayoutConfig::from_json(
+            r#"{"windows": [
+                {"name": "log", "x": 0, "y": 0, "width": 10, "height": 3},
+                {"name": "log", "x": 0, "y": 3, "width": 10, "height": 3}
+            ]}"#,
+        )
+        .unwrap();
+        let mut container: Container<Cell> = Container::new();
+        let err = container.apply_layout(&config).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(container.windows.is_empty());
+
+        let mut layout = container.apply_layout(&LayoutConfig::default()).unwrap();
+        assert!(container.update_layout(&mut layout, &config).is_err());
+        assert!(container.windows.is_empty());
+    }
+}
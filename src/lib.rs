@@ -1,6 +1,93 @@
-use point::Point;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+pub mod backend;
+mod bind;
+mod blend;
+pub mod braille;
+mod builder;
+mod bus;
+mod caps;
+mod chunks;
+mod composite;
+mod draw;
+pub mod event;
+mod export;
+mod flash;
+pub mod geometry;
+mod history;
+mod image;
+mod input;
+pub mod keymap;
+mod kitty;
+#[cfg(feature = "config")]
+pub mod layout;
+mod modal;
+pub mod notify;
+mod observe;
+mod panes;
+mod profile;
+pub mod record;
+mod rle;
+pub mod runtime;
+mod scene;
+#[cfg(feature = "image")]
+mod screenshot;
+#[cfg(feature = "config")]
+mod session;
+mod shared;
+mod sixel;
+mod stats;
+#[cfg(feature = "tokio")]
+pub mod stream;
+pub mod style;
+#[cfg(any(unix, windows))]
+mod sys;
+#[cfg(any(unix, windows))]
+pub mod terminal;
+pub mod testing;
+mod track;
+mod transaction;
+mod transform;
+mod updates;
+mod view;
+pub mod widgets;
+mod zoom;
+
+pub use bind::{BindingId, Source};
+pub use blend::BlendMode;
+pub use builder::{BorderSet, ContainerBuilder, Handles, WindowBuilder};
+pub use bus::SubscriptionId;
+pub use caps::Capabilities;
+pub use chunks::ChunkMap;
+pub use composite::{Compositor, JoinLines};
+pub use draw::{Connectivity, Merge};
+pub use geometry::{Coord, Point, Rect, Size};
+pub use history::Edit;
+pub use image::Pixels;
+pub use observe::ObserverId;
+pub use panes::{Direction, PaneAction, PaneId, Panes};
+pub use profile::{Profile, WindowTime};
+pub use rle::{RleFrame, Run};
+pub use scene::{SceneId, SceneManager, Transition};
+#[cfg(feature = "config")]
+pub use session::{Session, SessionWindow};
+pub use shared::{RenderThread, SharedContainer};
+pub use stats::{MemoryStats, WindowMemory};
+pub use track::TrackedId;
+pub use transaction::Transaction;
+pub use transform::{Mirror, Transform};
+pub use updates::Updates;
+pub use view::{WindowView, WindowViewMut};
+pub use zoom::Sampling;
+
+use event::{Event, MouseButton, MouseEvent, MouseKind, MouseMode, WindowEvent};
+
+/// Identifies a window by its index in [`Container::windows`].
+pub type WindowId = usize;
 
 /// A window to be displayed. Do note that the origin is the top left
 /// corner of the terminal.
@@ -10,6 +97,39 @@ pub struct Window<T: fmt::Display> {
     pub top_left: Point,
     /// Contains all the characters of the window in rows.
     pub data: Vec<Vec<T>>,
+    /// Whether the window can be resized by dragging its borders with the mouse.
+    pub resizable: bool,
+    /// Smallest width and height the window can be resized to with the mouse.
+    pub min_size: (usize, usize),
+    /// Largest width and height the window can be resized to with the mouse, if any.
+    pub max_size: Option<(usize, usize)>,
+    /// Number of rows shown at once. If this is set, the window is scrollable and only
+    /// shows rows starting from `scroll`.
+    pub view_hgt: Option<usize>,
+    /// Index of the first row shown by a scrollable window.
+    pub scroll: usize,
+    /// Name of the window, shown by widgets that list windows.
+    pub title: Option<String>,
+    /// Whether the window is hidden, such as when it has been minimised. Hidden
+    /// windows aren't drawn and don't receive mouse events.
+    pub hidden: bool,
+    /// Pixels drawn over the window, starting at its top left, by backends that can
+    /// show images.
+    pub pixels: Option<Pixels>,
+    /// Area of the terminal the window is cut down to, if any. Only the part of the
+    /// window inside it is drawn or receives mouse events.
+    pub clip: Option<Rect>,
+    /// Column and row of `data` the terminal cursor is placed at while the window is
+    /// focused, such as where text is typed into an input. The cursor is hidden if no
+    /// focused window sets one.
+    pub cursor: Option<Point>,
+    /// Number of cells on each side of `data` taken up by a border and padding,
+    /// which [`to_local`](Self::to_local) and [`to_screen`](Self::to_screen) measure
+    /// from inside of.
+    pub inset: usize,
+    // How the window is blended with those below it, if it isn't simply drawn over
+    // them.
+    pub(crate) blend: Option<blend::Blend<T>>,
 }
 
 #[allow(unused_must_use)]
@@ -19,6 +139,18 @@ impl<T: fmt::Display> Window<T> {
         Self {
             top_left,
             data: Vec::new(),
+            resizable: false,
+            min_size: (1, 1),
+            max_size: None,
+            view_hgt: None,
+            scroll: 0,
+            title: None,
+            hidden: false,
+            pixels: None,
+            clip: None,
+            cursor: None,
+            inset: 0,
+            blend: None,
         }
     }
 
@@ -42,6 +174,211 @@ impl<T: fmt::Display> Window<T> {
         self.data.insert(0, vec![ch.clone(); first_len]);
         self.data.push(vec![ch; last_len]);
     }
+
+    /// Length of the longest row of the window.
+    pub fn width(&self) -> usize {
+        self.data.iter().map(|row| row.len()).max().unwrap_or(0)
+    }
+
+    /// Number of rows shown by the window.
+    pub fn height(&self) -> usize {
+        self.visible_rows().len()
+    }
+
+    /// Returns the rows currently shown by the window. This is every row unless the
+    /// window is scrollable.
+    pub fn visible_rows(&self) -> &[Vec<T>] {
+        let start = self.scroll.min(self.data.len());
+        let end = match self.view_hgt {
+            Some(hgt) => (start + hgt).min(self.data.len()),
+            None => self.data.len(),
+        };
+
+        &self.data[start..end]
+    }
+
+    /// Returns true if the window is scrollable.
+    pub fn is_scrollable(&self) -> bool {
+        self.view_hgt.is_some()
+    }
+
+    /// Largest value `scroll` can take while still filling the view.
+    pub fn max_scroll(&self) -> usize {
+        match self.view_hgt {
+            Some(hgt) => self.data.len().saturating_sub(hgt),
+            None => 0,
+        }
+    }
+
+    /// Scrolls the window by the given number of rows, where negative values scroll
+    /// up. Returns true if the scroll position changed.
+    pub fn scroll_by(&mut self, rows: isize) -> bool {
+        let prev = self.scroll;
+        self.scroll = self
+            .scroll
+            .saturating_add_signed(rows)
+            .min(self.max_scroll());
+
+        prev != self.scroll
+    }
+
+    /// Makes the window exactly `wid` by `hgt`, cutting off characters that no longer
+    /// fit and filling new space with the given character. For scrollable windows,
+    /// `hgt` is the number of rows shown and no rows are removed.
+    pub fn resize(&mut self, wid: usize, hgt: usize, fill: T)
+    where
+        T: Clone,
+    {
+        if self.is_scrollable() {
+            self.view_hgt = Some(hgt);
+            if self.data.len() < hgt {
+                self.data.resize(hgt, Vec::new());
+            }
+            self.scroll = self.scroll.min(self.max_scroll());
+        } else {
+            self.data.resize(hgt, Vec::new());
+        }
+
+        for row in self.data.iter_mut() {
+            row.resize(wid, fill.clone());
+        }
+    }
+
+    /// Returns the area of the terminal the window takes up, from its top left to
+    /// the end of its longest shown row.
+    pub fn rect(&self) -> Rect {
+        Rect::new(self.top_left, self.width(), self.height())
+    }
+
+    /// Moves the window to the top left of the rectangle and resizes it to fit
+    /// exactly, like [`resize`](Self::resize).
+    pub fn set_rect(&mut self, rect: Rect, fill: T)
+    where
+        T: Clone,
+    {
+        self.top_left = rect.top_left;
+        self.resize(rect.wid, rect.hgt, fill);
+    }
+
+    /// Clamps the given size to the window's minimum and maximum size.
+    pub fn clamp_size(&self, wid: usize, hgt: usize) -> (usize, usize) {
+        let (mut wid, mut hgt) = (wid.max(self.min_size.0), hgt.max(self.min_size.1));
+        if let Some((max_wid, max_hgt)) = self.max_size {
+            wid = wid.min(max_wid);
+            hgt = hgt.min(max_hgt);
+        }
+        (wid, hgt)
+    }
+
+    /// Returns true if the window has a character at the given position, which is
+    /// relative to the top left corner of the terminal.
+    pub fn contains(&self, p: Point) -> bool {
+        let x = p.x - self.top_left.x;
+        let y = p.y - self.top_left.y;
+
+        if x < 0 || y < 0 {
+            return false;
+        }
+
+        self.clip.is_none_or(|clip| clip.contains(p))
+            && self
+                .visible_rows()
+                .get(y as usize)
+                .is_some_and(|row| (x as usize) < row.len())
+    }
+
+    /// Returns an iterator over every position the window covers, relative to the top
+    /// left corner of the terminal.
+    pub fn footprint(&self) -> impl Iterator<Item = Point> + '_ {
+        self.cells().map(|(p, _)| p)
+    }
+
+    /// Returns an iterator over every shown cell of the window along with its position
+    /// relative to the top left corner of the terminal.
+    pub fn cells(&self) -> impl Iterator<Item = (Point, &T)> + '_ {
+        self.visible_rows()
+            .iter()
+            .enumerate()
+            .flat_map(move |(y, row)| {
+                row.iter()
+                    .enumerate()
+                    .map(move |(x, ch)| (Point::new(x as Coord, y as Coord) + self.top_left, ch))
+            })
+            .filter(|(p, _)| self.clip.is_none_or(|clip| clip.contains(*p)))
+    }
+
+    /// Returns true if the position of `data` is inside the window's border and
+    /// padding.
+    fn is_inner(&self, p: Point) -> bool {
+        let inset = self.inset as Coord;
+        let hgt = self.data.len() as Coord;
+        p.x >= inset
+            && p.y >= inset
+            && p.y < hgt - inset
+            && (p.x as usize) + self.inset < self.data[p.y as usize].len()
+    }
+
+    /// Converts a position relative to the top left corner of the terminal to one
+    /// relative to the window's contents, inside any border and padding and
+    /// counting rows scrolled past. Returns None if the window doesn't show its
+    /// contents there, such as when it is clipped or on its border.
+    pub fn to_local(&self, p: Point) -> Option<Point> {
+        if !self.contains(p) {
+            return None;
+        }
+        let inner = p - self.top_left + Point::new(0, self.scroll as Coord);
+        self.is_inner(inner)
+            .then(|| inner - Point::new(self.inset as Coord, self.inset as Coord))
+    }
+
+    /// Converts a position relative to the window's contents, as returned by
+    /// [`to_local`](Self::to_local), to where it is shown on the terminal. Returns
+    /// None if it isn't shown, such as when it is scrolled out of view.
+    pub fn to_screen(&self, p: Point) -> Option<Point> {
+        let inner = p + Point::new(self.inset as Coord, self.inset as Coord);
+        if p.x < 0 || p.y < 0 || !self.is_inner(inner) {
+            return None;
+        }
+        let screen = inner + self.top_left - Point::new(0, self.scroll as Coord);
+        self.contains(screen).then_some(screen)
+    }
+
+    /// Creates a string representation of the window like its [`Display`](fmt::Display)
+    /// implementation, but with short rows padded with `fill` so that every line is
+    /// as wide as the window.
+    pub fn to_string_padded(&self, fill: T) -> String {
+        let wid = self.width();
+        let mut out = String::new();
+
+        for row in self.data.iter() {
+            for ch in row.iter() {
+                out.push_str(&ch.to_string());
+            }
+            for _ in row.len()..wid {
+                out.push_str(&fill.to_string());
+            }
+            out.push('\n');
+        }
+
+        out
+    }
+}
+
+/// Windows are equal when they are at the same position with the same contents,
+/// regardless of their other settings.
+impl<T: fmt::Display + PartialEq> PartialEq for Window<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.top_left == other.top_left && self.data == other.data
+    }
+}
+
+impl<T: fmt::Display + Eq> Eq for Window<T> {}
+
+impl<T: fmt::Display + Hash> Hash for Window<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.top_left.hash(state);
+        self.data.hash(state);
+    }
 }
 
 impl<T: fmt::Display> fmt::Display for Window<T> {
@@ -58,16 +395,14 @@ impl<T: fmt::Display> fmt::Display for Window<T> {
 
 impl fmt::Write for Window<char> {
     fn write_str(&mut self, s: &str) -> Result<(), fmt::Error> {
-        let mut len = self.data.len();
-        if len == 0 {
+        if self.data.is_empty() {
             self.data.push(Vec::new());
-            len += 1;
         }
-        let mut cur_idx = len - 1;
 
+        // Each line goes on the end of the last row, then starts a new one.
         for line in s.lines() {
-            self.data[cur_idx].extend(line.chars());
-            cur_idx += 1;
+            let last = self.data.len() - 1;
+            self.data[last].extend(line.chars());
             self.data.push(Vec::new());
         }
 
@@ -76,13 +411,148 @@ impl fmt::Write for Window<char> {
 }
 
 /// Contains various windows and displays them according to their position.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug)]
 pub struct Container<T: fmt::Display> {
     /// Each window stored in the container.
     pub windows: Vec<Window<T>>,
-    buffer: HashMap<Point, T>,
+    /// Number of rows a scrollable window scrolls by per tick of the mouse wheel.
+    pub scroll_lines: usize,
+    /// How often [`run`](Self::run) produces a tick event.
+    pub tick_rate: Duration,
+    /// Most frames [`run`](Self::run) presents per second, or None for no limit.
+    pub max_fps: Option<u32>,
+    /// Mouse events the backend reports, or None to not use the mouse. Windows can
+    /// only be dragged if movement while a button is held is reported. Changes are
+    /// applied to the backend by [`run`](Self::run) with the next frame.
+    pub mouse: Option<MouseMode>,
+    /// Events scheduled to be given to [`run`](Self::run)'s callback later.
+    pub timers: runtime::Timers,
+    refresh_request: runtime::RefreshRequest,
+    updates: updates::Queue<T>,
+    profile: Option<profile::Profile>,
+    buffer: ChunkMap<T>,
+    // Window each position of the buffer was taken from.
+    owners: ChunkMap<WindowId>,
+    compositor: composite::Hook<T>,
+    observers: observe::Observers,
+    history: Option<history::History<T>>,
+    bus: bus::Bus<T>,
+    bindings: Vec<bind::Binding<T>>,
+    next_binding: u64,
     // Contains all positions that have been changed since the last refresh.
     changed: Vec<Point>,
+    // Whether the last refresh left the buffer as it was.
+    unchanged: bool,
+    // Positions that must be reported as changed by the next refresh.
+    dirty: HashSet<Point>,
+    drag: Option<Drag>,
+    focus: Option<WindowId>,
+    // Window that all input is restricted to, if any.
+    modal: Option<WindowId>,
+    // Windows pushed as modal, bottom first.
+    modals: Vec<modal::Modal<T>>,
+    next_modal: u64,
+    // Windows kept track of with track, by their current ids.
+    tracked: Vec<(TrackedId, WindowId)>,
+    next_tracked: u64,
+    // Events waiting to be given to the event loop's callback.
+    posted: VecDeque<Event>,
+    // Last known size of the screen.
+    screen: (u16, u16),
+    toasts: Vec<notify::Toast>,
+    toast_corner: notify::Corner,
+    // Images shown with the kitty graphics protocol.
+    images: kitty::Placements,
+    // Where the cursor was last placed on the backend, if it was shown.
+    placed_cursor: Option<Point>,
+    // Mouse events the backend was last told to report, if it has been told.
+    applied_mouse: Option<Option<MouseMode>>,
+    flashes: Vec<flash::Flash<T>>,
+}
+
+// Windows and containers can be sent to and shared with other threads whenever
+// their contents can.
+const _: () = {
+    fn assert_send_sync<S: Send + Sync>() {}
+    #[allow(dead_code)]
+    fn check<T: fmt::Display + Send + Sync>() {
+        assert_send_sync::<Window<T>>();
+        assert_send_sync::<Container<T>>();
+        assert_send_sync::<Updates<T>>();
+    }
+};
+
+/// Shows the windows as they are now, over the smallest area holding all of them,
+/// with gaps between windows left blank.
+impl<T: fmt::Display> fmt::Display for Container<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Values blended by the compositor are owned, and the rest borrowed.
+        let mut cells: HashMap<Point, Result<&T, T>> = HashMap::new();
+        for win in self.windows.iter().filter(|win| !win.hidden) {
+            for (p, ch) in win.cells() {
+                let below = cells.get(&p).map(|below| match below {
+                    Ok(below) => *below,
+                    Err(below) => below,
+                });
+                let merge = self.compositor.merge(below, ch, win);
+                match merge {
+                    Merge::Replace => cells.insert(p, Ok(ch)),
+                    Merge::Skip => None,
+                    Merge::Blend(value) => cells.insert(p, Err(value)),
+                };
+            }
+        }
+
+        let Some(min_x) = cells.keys().map(|p| p.x).min() else {
+            return Ok(());
+        };
+        let min_y = cells.keys().map(|p| p.y).min().unwrap_or(0);
+        let max_x = cells.keys().map(|p| p.x).max().unwrap_or(0);
+        let max_y = cells.keys().map(|p| p.y).max().unwrap_or(0);
+
+        for y in min_y..=max_y {
+            // Gaps at the end of the line are left out.
+            let end = (min_x..=max_x)
+                .rev()
+                .find(|&x| cells.contains_key(&Point::new(x, y)))
+                .unwrap_or(min_x - 1);
+            for x in min_x..=end {
+                match cells.get(&Point::new(x, y)) {
+                    Some(Ok(ch)) => write!(f, "{ch}")?,
+                    Some(Err(ch)) => write!(f, "{ch}")?,
+                    None => write!(f, " ")?,
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// A window being dragged with the mouse.
+#[derive(Clone, Copy, Debug)]
+enum Drag {
+    /// Moving the window by its title bar.
+    Move {
+        win: WindowId,
+        // Position of the cursor relative to the top left of the window.
+        grab: Point,
+    },
+    /// Resizing the window by its borders.
+    Resize {
+        win: WindowId,
+        left: bool,
+        right: bool,
+        bottom: bool,
+        // Column of the right border when the drag started.
+        right_x: Coord,
+    },
+}
+
+impl<T: fmt::Display> Default for Container<T> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<T: fmt::Display> Container<T> {
@@ -90,14 +560,156 @@ impl<T: fmt::Display> Container<T> {
     pub fn new() -> Self {
         Self {
             windows: Vec::new(),
-            buffer: HashMap::new(),
+            scroll_lines: 3,
+            tick_rate: Duration::from_millis(250),
+            max_fps: Some(60),
+            mouse: None,
+            timers: runtime::Timers::default(),
+            refresh_request: runtime::RefreshRequest::default(),
+            updates: updates::Queue::default(),
+            profile: None,
+            buffer: ChunkMap::new(),
+            owners: ChunkMap::new(),
+            compositor: composite::Hook::default(),
+            observers: observe::Observers::default(),
+            history: None,
+            bus: bus::Bus::default(),
+            bindings: Vec::new(),
+            next_binding: 0,
             changed: Vec::new(),
+            unchanged: false,
+            dirty: HashSet::new(),
+            drag: None,
+            focus: None,
+            modal: None,
+            modals: Vec::new(),
+            next_modal: 0,
+            tracked: Vec::new(),
+            next_tracked: 0,
+            posted: VecDeque::new(),
+            screen: (80, 24),
+            toasts: Vec::new(),
+            toast_corner: notify::Corner::default(),
+            images: kitty::Placements::default(),
+            placed_cursor: None,
+            applied_mouse: None,
+            flashes: Vec::new(),
+        }
+    }
+
+    /// Add the given window to the container on top of the others, returning its id.
+    /// Notifications stay above every other window, so that the ids of other windows
+    /// don't change as they come and go.
+    pub fn add_win(&mut self, win: Window<T>) -> WindowId {
+        if self.toasts.is_empty() {
+            self.windows.push(win);
+            self.windows.len() - 1
+        } else {
+            self.insert_win(self.windows.len(), win)
         }
     }
 
-    /// Add the given window to the container.
-    pub fn add_win(&mut self, win: Window<T>) {
-        self.windows.push(win);
+    /// Returns the number of windows below the notifications.
+    fn below_toasts(&self) -> usize {
+        self.windows.len() - self.toasts.len()
+    }
+
+    /// Remove the window with the given id from the container. The ids of windows
+    /// above it go down by one.
+    pub fn remove_win(&mut self, win: WindowId) -> Window<T> {
+        let removed = self.windows.remove(win);
+        self.mark_dirty(removed.footprint().collect::<Vec<_>>());
+
+        self.remap_ids(|id| match id {
+            _ if id == win => None,
+            _ if id > win => Some(id - 1),
+            _ => Some(id),
+        });
+
+        removed
+    }
+
+    /// Insert the given window into the container with the given id, so that it is
+    /// drawn below the window that had that id. The ids of windows above it go up by
+    /// one. Windows can't be inserted above the notifications.
+    pub fn insert_win(&mut self, idx: WindowId, win: Window<T>) -> WindowId {
+        let idx = idx.min(self.below_toasts());
+        self.windows.insert(idx, win);
+        self.remap_ids(|id| Some(if id >= idx { id + 1 } else { id }));
+        idx
+    }
+
+    /// Moves the window with the given id up or down so that its id becomes `to`,
+    /// shifting the ids of the windows in between. Returns its new id. Windows other
+    /// than notifications can't be moved above the notifications.
+    pub fn restack(&mut self, win: WindowId, to: WindowId) -> WindowId {
+        let top = if self.toasts.iter().any(|toast| toast.win == win) {
+            self.windows.len()
+        } else {
+            self.below_toasts()
+        };
+        let to = to.min(top - 1);
+        let moved = self.windows.remove(win);
+        self.mark_dirty(moved.footprint().collect::<Vec<_>>());
+        self.windows.insert(to, moved);
+
+        self.remap_ids(|id| {
+            if id == win {
+                return Some(to);
+            }
+            let id = if id > win { id - 1 } else { id };
+            Some(if id >= to { id + 1 } else { id })
+        });
+        to
+    }
+
+    /// Changes the ids of windows kept by the container after windows are added,
+    /// removed or reordered, forgetting any mapped to None.
+    fn remap_ids(&mut self, f: impl Fn(WindowId) -> Option<WindowId>) {
+        self.focus = self.focus.and_then(&f);
+        self.modal = self.modal.and_then(&f);
+        self.modals.retain_mut(|modal| match f(modal.win) {
+            Some(id) => {
+                modal.win = id;
+                modal.prev_focus = modal.prev_focus.and_then(&f);
+                modal.prev_modal = modal.prev_modal.and_then(&f);
+                true
+            }
+            None => false,
+        });
+        self.tracked.retain_mut(|(_, win)| match f(*win) {
+            Some(id) => {
+                *win = id;
+                true
+            }
+            None => false,
+        });
+        self.toasts.retain_mut(|toast| match f(toast.win) {
+            Some(id) => {
+                toast.win = id;
+                true
+            }
+            None => false,
+        });
+        self.flashes.retain_mut(|flash| match flash.win.map(&f) {
+            Some(Some(id)) => {
+                flash.win = Some(id);
+                true
+            }
+            Some(None) => false,
+            None => true,
+        });
+        self.bindings.retain_mut(|binding| match f(binding.win) {
+            Some(id) => {
+                binding.win = id;
+                true
+            }
+            None => false,
+        });
+        self.drag = None;
+        if let Some(history) = &mut self.history {
+            history.remap(&f);
+        }
     }
 
     /// Return a slice of all positions in the buffer that have been changed since the last call to refresh.
@@ -106,33 +718,400 @@ impl<T: fmt::Display> Container<T> {
     }
 
     /// Return a reference to the internal buffer.
-    pub fn get_buffer(&self) -> &HashMap<Point, T> {
+    pub fn get_buffer(&self) -> &ChunkMap<T> {
         &self.buffer
     }
 
-    /// Redraws all the windows into the buffer.
-    pub fn refresh(&mut self)
+    /// Returns what was at the given position as of the last refresh, if anything.
+    pub fn cell_at(&self, p: Point) -> Option<&T> {
+        self.buffer.get(&p)
+    }
+
+    /// Returns the window the cell at the given position was taken from as of the last
+    /// refresh, if any. Unlike [`window_at`](Self::window_at), this doesn't change
+    /// until the next refresh.
+    pub fn owner_at(&self, p: Point) -> Option<WindowId> {
+        self.owners.get(&p).copied()
+    }
+
+    /// Mark the given positions as changed, so that they are reported by
+    /// [`changed`](Self::changed) after the next refresh even if no window covers them
+    /// any more.
+    pub fn mark_dirty(&mut self, points: impl IntoIterator<Item = Point>) {
+        self.dirty.extend(points);
+    }
+
+    /// Converts a position on the screen to one relative to the contents of the
+    /// window, as with [`Window::to_local`]. Returns None if the window is hidden.
+    pub fn to_local(&self, win: WindowId, p: Point) -> Option<Point> {
+        let win = self.windows.get(win)?;
+        if win.hidden {
+            return None;
+        }
+        win.to_local(p)
+    }
+
+    /// Converts a position relative to the contents of the window to where it is on
+    /// the screen, as with [`Window::to_screen`]. Returns None if the window is
+    /// hidden.
+    pub fn to_screen(&self, win: WindowId, p: Point) -> Option<Point> {
+        let win = self.windows.get(win)?;
+        if win.hidden {
+            return None;
+        }
+        win.to_screen(p)
+    }
+
+    /// Returns the topmost shown window with a character at the given position,
+    /// along with the position relative to its contents. This is None if there's no
+    /// window there or the position is on its border or padding.
+    pub fn local_at(&self, p: Point) -> Option<(WindowId, Point)> {
+        let win = self.window_at(p)?;
+        Some((win, self.windows[win].to_local(p)?))
+    }
+
+    /// Returns the topmost shown window with a character at the given position, if
+    /// any.
+    pub fn window_at(&self, p: Point) -> Option<WindowId> {
+        self.windows
+            .iter()
+            .rposition(|win| !win.hidden && win.contains(p))
+    }
+
+    /// Hides or shows the window with the given id. A window that is hidden loses
+    /// focus.
+    pub fn set_hidden(&mut self, win: WindowId, hidden: bool) {
+        if self.windows[win].hidden == hidden {
+            return;
+        }
+        self.windows[win].hidden = hidden;
+        self.mark_dirty(self.windows[win].footprint().collect::<Vec<_>>());
+
+        if hidden && self.focus == Some(win) {
+            self.focus = None;
+        }
+    }
+
+    /// Cuts the window with the given id down to an area of the terminal, or shows all
+    /// of it again if None.
+    pub fn set_clip(&mut self, win: WindowId, clip: Option<Rect>) {
+        self.mark_dirty(self.windows[win].footprint().collect::<Vec<_>>());
+        self.windows[win].clip = clip;
+    }
+
+    /// Moves and resizes the window with the given id to fill the rectangle, like
+    /// [`Window::set_rect`].
+    pub fn set_rect(&mut self, win: WindowId, rect: Rect, fill: T)
     where
-        T: Clone + PartialEq,
+        T: Clone,
     {
-        self.buffer.clear();
-        self.changed = Vec::new();
-
-        for win in self.windows.iter() {
-            for (y, row) in win.data.iter().enumerate() {
-                for (x, ch) in row.iter().enumerate() {
-                    let p = Point::new(x as i32, y as i32) + win.top_left;
-
-                    let prev = self.buffer.get(&p);
-                    if prev.is_none() || prev.unwrap() != ch {
-                        self.buffer.insert(p, ch.clone());
-                        self.changed.push(p);
+        self.mark_dirty(self.windows[win].footprint().collect::<Vec<_>>());
+        self.windows[win].set_rect(rect, fill);
+    }
+
+    /// Sets what decides the value of cells where windows overlap, instead of the
+    /// window on top replacing what is below. Takes effect on the next refresh.
+    pub fn set_compositor(&mut self, compositor: impl Compositor<T> + Send + Sync + 'static) {
+        self.compositor = composite::Hook(Some(std::sync::Arc::new(compositor)));
+    }
+
+    /// Goes back to windows on top replacing what is below them.
+    pub fn clear_compositor(&mut self) {
+        self.compositor = composite::Hook::default();
+    }
+
+    /// Returns the window that currently receives keyboard input, if any.
+    pub fn focused(&self) -> Option<WindowId> {
+        self.focus
+    }
+
+    /// Sets the window that receives keyboard input. While a window is modal, focus
+    /// stays on it.
+    pub fn set_focus(&mut self, win: Option<WindowId>) {
+        if self.modal.is_none() {
+            self.focus = win;
+        }
+    }
+
+    /// Returns where on the screen the focused window wants the cursor, as of the
+    /// last refresh. This is None unless the window sets a
+    /// [cursor](Window::cursor) at a position that is shown and not covered by
+    /// another window.
+    pub fn cursor_position(&self) -> Option<Point> {
+        let id = self.focus?;
+        let win = self.windows.get(id)?;
+        let cursor = win.cursor?;
+        let row = cursor.y - win.scroll as Coord;
+        if win.hidden || row < 0 {
+            return None;
+        }
+        let p = Point::new(win.top_left.x + cursor.x, win.top_left.y + row);
+        (self.owner_at(p) == Some(id)).then_some(p)
+    }
+
+    /// Returns the window all input is restricted to, if any.
+    pub fn modal(&self) -> Option<WindowId> {
+        self.modal
+    }
+
+    /// Restricts all input to the given window, focusing it, or lifts the restriction
+    /// if None is given. While a window is modal, mouse events over other windows are
+    /// ignored. This replaces the window input is restricted to without touching the
+    /// [modal stack](Self::push_modal).
+    pub fn set_modal(&mut self, win: Option<WindowId>) {
+        self.modal = win;
+        if win.is_some() {
+            self.focus = win;
+        }
+    }
+
+    /// Queues an event to be given to the callback of [`run`](Self::run) after the
+    /// event currently being handled.
+    pub fn post(&mut self, event: Event) {
+        self.posted.push_back(event);
+    }
+
+    /// Returns the last known width and height of the screen.
+    pub fn screen_size(&self) -> (u16, u16) {
+        self.screen
+    }
+
+    /// Sets the size of the screen, which is done automatically by [`run`](Self::run).
+    pub fn set_screen_size(&mut self, wid: u16, hgt: u16) {
+        self.screen = (wid, hgt);
+        self.layout_toasts();
+    }
+
+    /// Returns true if a window is currently being dragged.
+    pub fn is_dragging(&self) -> bool {
+        self.drag.is_some()
+    }
+
+    /// Handle a mouse event. Pressing the left button on a window focuses it.
+    /// Pressing it on the top row of a window and dragging moves the window along with
+    /// the cursor. Doing the same on the left, right or bottom border of a resizable
+    /// window resizes it, filling any new space with the default value of T.
+    /// Scrolling the wheel over a scrollable window scrolls it by `scroll_lines` rows.
+    pub fn handle_mouse(&mut self, ev: MouseEvent) -> Option<WindowEvent>
+    where
+        T: Clone + Default,
+    {
+        let starts_input = matches!(
+            ev.kind,
+            MouseKind::Down(_) | MouseKind::ScrollUp | MouseKind::ScrollDown
+        );
+        if starts_input && self.modal.is_some() && self.window_at(ev.pos) != self.modal {
+            return None;
+        }
+
+        match ev.kind {
+            MouseKind::Down(MouseButton::Left) => {
+                let win = self.window_at(ev.pos)?;
+                self.focus = Some(win);
+                let window = &self.windows[win];
+                let top_left = window.top_left;
+                let x = ev.pos.x - top_left.x;
+                let y = ev.pos.y - top_left.y;
+                let right_x = top_left.x + window.width() as Coord - 1;
+
+                if y == 0 {
+                    let grab = Point::new(x, y);
+                    self.drag = Some(Drag::Move { win, grab });
+                    self.start_drag_history(win, true);
+                } else if window.resizable {
+                    let left = x == 0;
+                    let right = ev.pos.x == right_x;
+                    let bottom = y == window.height() as Coord - 1;
+
+                    if left || right || bottom {
+                        self.drag = Some(Drag::Resize {
+                            win,
+                            left,
+                            right,
+                            bottom,
+                            right_x,
+                        });
+                        self.start_drag_history(win, false);
                     }
                 }
+                None
+            }
+            MouseKind::Drag(MouseButton::Left) => match self.drag? {
+                Drag::Move { win, grab } => {
+                    let from = self.windows.get(win)?.top_left;
+                    let to = Point::new(ev.pos.x - grab.x, ev.pos.y - grab.y);
+
+                    if from == to {
+                        return None;
+                    }
+
+                    self.mark_dirty(self.windows[win].footprint().collect::<Vec<_>>());
+                    self.windows[win].top_left = to;
+                    self.mark_dirty(self.windows[win].footprint().collect::<Vec<_>>());
+
+                    Some(WindowEvent::Moved { win, from, to })
+                }
+                Drag::Resize {
+                    win,
+                    left,
+                    right,
+                    bottom,
+                    right_x,
+                } => {
+                    let window = self.windows.get(win)?;
+                    let top_left = window.top_left;
+                    let mut wid = window.width() as Coord;
+                    let mut hgt = window.height() as Coord;
+
+                    if left {
+                        wid = right_x - ev.pos.x + 1;
+                    } else if right {
+                        wid = ev.pos.x - top_left.x + 1;
+                    }
+                    if bottom {
+                        hgt = ev.pos.y - top_left.y + 1;
+                    }
+
+                    let (wid, hgt) = window.clamp_size(wid.max(0) as usize, hgt.max(0) as usize);
+                    if wid == window.width() && hgt == window.height() {
+                        return None;
+                    }
+
+                    self.mark_dirty(self.windows[win].footprint().collect::<Vec<_>>());
+                    let window = &mut self.windows[win];
+                    if left {
+                        window.top_left.x = right_x - wid as Coord + 1;
+                    }
+                    window.resize(wid, hgt, T::default());
+                    self.mark_dirty(self.windows[win].footprint().collect::<Vec<_>>());
+
+                    Some(WindowEvent::Resized { win, wid, hgt })
+                }
+            },
+            MouseKind::Up(MouseButton::Left) => {
+                self.drag = None;
+                self.end_drag_history();
+                None
+            }
+            MouseKind::ScrollUp | MouseKind::ScrollDown => {
+                let win = self.window_at(ev.pos)?;
+                let lines = self.scroll_lines as isize;
+                let rows = if ev.kind == MouseKind::ScrollUp {
+                    -lines
+                } else {
+                    lines
+                };
+
+                if !self.windows[win].scroll_by(rows) {
+                    return None;
+                }
+
+                self.mark_dirty(self.windows[win].footprint().collect::<Vec<_>>());
+                Some(WindowEvent::Scrolled {
+                    win,
+                    scroll: self.windows[win].scroll,
+                })
             }
+            _ => None,
         }
     }
 
+    /// Applies any [queued](Self::updates) changes, then redraws all the windows into
+    /// the buffer.
+    pub fn refresh(&mut self)
+    where
+        T: Clone + PartialEq,
+    {
+        self.take_refresh_request();
+        let start = self.profile_start();
+        self.apply_updates();
+        self.profile_record(start, |profile, time| profile.updates = time);
+
+        let start = self.profile_start();
+        let before = std::mem::take(&mut self.buffer);
+        self.owners.clear();
+        let dirty = std::mem::take(&mut self.dirty);
+        // Time spent drawing each window, if profiling.
+        let mut times = start.map(|_| vec![Duration::ZERO; self.windows.len()]);
+
+        for (id, win) in self
+            .windows
+            .iter()
+            .enumerate()
+            .filter(|(_, win)| !win.hidden)
+        {
+            let win_start = times.as_ref().map(|_| Instant::now());
+            for (p, ch) in win.cells() {
+                let prev = self.buffer.get(&p);
+                let value = match self.compositor.merge(prev, ch, win) {
+                    Merge::Replace => ch.clone(),
+                    Merge::Skip => continue,
+                    Merge::Blend(value) => value,
+                };
+
+                self.owners.insert(p, id);
+                self.buffer.insert(p, value);
+            }
+            if let (Some(times), Some(win_start)) = (times.as_mut(), win_start) {
+                times[id] = win_start.elapsed();
+            }
+        }
+
+        self.apply_dim();
+        self.apply_flashes();
+
+        // What differs from the last refresh, including what nothing is drawn at any
+        // more, and anything marked dirty besides.
+        self.changed = self
+            .buffer
+            .iter()
+            .filter(|&(p, value)| before.get(&p) != Some(value))
+            .map(|(p, _)| p)
+            .collect();
+        self.unchanged = self.changed.is_empty() && before.len() == self.buffer.len();
+        self.changed
+            .extend(before.keys().filter(|p| !self.buffer.contains_key(p)));
+        self.changed.extend(
+            dirty
+                .into_iter()
+                .filter(|p| self.buffer.get(p) == before.get(p)),
+        );
+
+        let len = self.windows.len();
+        self.profile_record(start, |profile, time| {
+            profile.frames += 1;
+            profile.composite = time;
+            profile.windows.resize(len, WindowTime::default());
+            for (win, time) in profile.windows.iter_mut().zip(times.unwrap_or_default()) {
+                win.composite = time;
+            }
+        });
+
+        if !self.changed.is_empty() {
+            self.observers.notify(&self.changed);
+        }
+    }
+
+    /// Returns true if the last refresh left the buffer exactly as the one before it
+    /// did, so there is nothing new to present.
+    pub fn is_unchanged(&self) -> bool {
+        self.unchanged
+    }
+
+    /// Returns true if both containers showed the same thing within `wid` by `hgt` as
+    /// of their last refresh.
+    pub fn frame_eq(&self, other: &Self, wid: u16, hgt: u16) -> bool
+    where
+        T: PartialEq,
+    {
+        let in_frame = |p: &Point| p.x >= 0 && p.y >= 0 && p.x < wid as Coord && p.y < hgt as Coord;
+        self.buffer
+            .keys()
+            .chain(other.buffer.keys())
+            .filter(in_frame)
+            .all(|p| self.buffer.get(&p) == other.buffer.get(&p))
+    }
+
     /// Draws the buffer to the screen. Uses the default value of T when there is no stored
     /// value in the buffer.
     pub fn draw(&self, wid: u16, hgt: u16)
@@ -143,44 +1122,70 @@ impl<T: fmt::Display> Container<T> {
     }
 
     /// Draws the buffer to the screen, using the provided default where there is no stored
-    /// value in the buffer.
+    /// value in the buffer. The whole frame is written at once, and on Windows the
+    /// console is first told to interpret any escape sequences the values contain.
     pub fn draw_with_default(&self, wid: u16, hgt: u16, default: T)
     where
         T: Clone,
     {
-        let wid = wid as i32;
-        let hgt = hgt as i32;
+        #[cfg(any(unix, windows))]
+        let _ = sys::enable_ansi();
 
-        for y in 0..hgt {
-            for x in 0..wid {
-                let p = Point::new(x, y);
+        let frame = self.to_string_with_default(wid, hgt, default);
+        let mut out = io::stdout().lock();
+        let _ = out.write_all(frame.as_bytes()).and_then(|()| out.flush());
+    }
 
-                print!(
-                    "{}",
-                    if let Some(c) = self.buffer.get(&p) {
-                        c.clone()
-                    } else {
-                        default.clone()
-                    }
-                );
-            }
-            println!();
-        }
+    /// Draws exactly the area holding every shown window to the screen, like
+    /// [`to_string_auto`](Self::to_string_auto).
+    pub fn draw_auto(&self)
+    where
+        T: Default + Clone,
+    {
+        #[cfg(any(unix, windows))]
+        let _ = sys::enable_ansi();
+
+        let frame = self.to_string_auto();
+        let mut out = io::stdout().lock();
+        let _ = out.write_all(frame.as_bytes()).and_then(|()| out.flush());
+    }
+
+    /// Returns the smallest rectangle holding every shown window, leaving out what
+    /// they are clipped to, or None if there are none.
+    pub fn bounds(&self) -> Option<Rect> {
+        self.windows
+            .iter()
+            .filter(|win| !win.hidden)
+            .filter_map(|win| match win.clip {
+                Some(clip) => win.rect().intersection(&clip),
+                None => Some(win.rect()).filter(|rect| !rect.is_empty()),
+            })
+            .reduce(|bounds, rect| bounds.union(&rect))
     }
 
     /// Creates a string representation of the container with positions from (0, 0) to
     /// (wid, hgt), using the provided default when there is no stored value in the
     /// buffer.
     pub fn to_string_with_default(&self, wid: u16, hgt: u16, default: T) -> String
+    where
+        T: Clone,
+    {
+        self.area_to_string(
+            Rect::new(Point::ORIGIN, wid as usize, hgt as usize),
+            default,
+        )
+    }
+
+    /// Creates a string representation of the given area of the container, using the
+    /// provided default when there is no stored value in the buffer.
+    fn area_to_string(&self, area: Rect, default: T) -> String
     where
         T: Clone,
     {
         let mut out = String::new();
-        let wid = wid as i32;
-        let hgt = hgt as i32;
 
-        for y in 0..hgt {
-            for x in 0..wid {
+        for y in area.top_left.y..area.bottom() {
+            for x in area.top_left.x..area.right() {
                 let p = Point::new(x, y);
 
                 let ch = if let Some(c) = self.buffer.get(&p) {
@@ -197,6 +1202,20 @@ impl<T: fmt::Display> Container<T> {
         out
     }
 
+    /// Creates a string representation of exactly the area holding every shown
+    /// window, as given by [`bounds`](Self::bounds), using the default value of T
+    /// where there is no stored value in the buffer. Unlike
+    /// [`to_string`](Self::to_string), this doesn't need the size of the screen, so
+    /// it suits quick tools and tests.
+    pub fn to_string_auto(&self) -> String
+    where
+        T: Clone + Default,
+    {
+        self.bounds()
+            .map(|bounds| self.area_to_string(bounds, T::default()))
+            .unwrap_or_default()
+    }
+
     /// Creates a string representation of the container with positions from (0, 0) to
     /// (wid, hgt), using the default value of T when there is no stored value in the
     /// buffer.
@@ -206,4 +1225,69 @@ impl<T: fmt::Display> Container<T> {
     {
         self.to_string_with_default(wid, hgt, T::default())
     }
+
+    /// Creates a string representation of only the part of the container with
+    /// something in it, using the default value of T for gaps, with gaps and
+    /// whitespace removed from the end of each line.
+    pub fn to_string_trimmed(&self) -> String
+    where
+        T: Clone + Default,
+    {
+        let Some(min_x) = self.buffer.keys().map(|p| p.x).min() else {
+            return String::new();
+        };
+        let min_y = self.buffer.keys().map(|p| p.y).min().unwrap_or(0);
+        let max_x = self.buffer.keys().map(|p| p.x).max().unwrap_or(0);
+        let max_y = self.buffer.keys().map(|p| p.y).max().unwrap_or(0);
+        let default = T::default();
+        let mut out = String::new();
+
+        for y in min_y..=max_y {
+            // Gaps at the end of the line are left out along with whitespace.
+            let end = (min_x..=max_x)
+                .rev()
+                .find(|&x| self.buffer.contains_key(&Point::new(x, y)))
+                .unwrap_or(min_x - 1);
+            let mut line = String::new();
+            for x in min_x..=end {
+                let ch = self.buffer.get(&Point::new(x, y)).unwrap_or(&default);
+                line.push_str(&ch.to_string());
+            }
+            out.push_str(line.trim_end());
+            out.push('\n');
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refresh_reports_only_what_changed() {
+        let mut container: Container<char> = Container::new();
+        let mut win = Window::new(Point::new(0, 0));
+        win.data = vec![vec!['a', 'b']];
+        container.add_win(win);
+        container.refresh();
+        assert_eq!(container.changed().len(), 2);
+
+        container.refresh();
+        assert!(container.changed().is_empty());
+        assert!(container.is_unchanged());
+
+        container.windows[0].data[0][1] = 'c';
+        container.refresh();
+        assert_eq!(container.changed(), &[Point::new(1, 0)]);
+
+        // Moving the window by hand leaves its old cells behind to be blanked out.
+        container.windows[0].top_left = Point::new(0, 1);
+        container.refresh();
+        let mut changed = container.changed().to_vec();
+        changed.sort_by_key(|p| (p.y, p.x));
+        let expected = [(0, 0), (1, 0), (0, 1), (1, 1)].map(|(x, y)| Point::new(x, y));
+        assert_eq!(changed, expected);
+    }
 }
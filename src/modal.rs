@@ -0,0 +1,129 @@
+//! Stacking modal windows, such as a dialog opened from another dialog.
+
+use crate::flash::flash_cell;
+use crate::geometry::{Point, Rect};
+use crate::style::{Cell, Style};
+use crate::{Container, WindowId};
+use std::fmt;
+
+/// A style laid over a cell, and how it is laid over one.
+type Dim<T> = (Style, fn(Option<&T>, Style) -> T);
+
+/// A window pushed onto the modal stack, with what to go back to when it is popped.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Modal<T> {
+    // Identifies the entry however the windows are renumbered.
+    key: u64,
+    pub(crate) win: WindowId,
+    pub(crate) prev_focus: Option<WindowId>,
+    pub(crate) prev_modal: Option<WindowId>,
+    // What is laid over everything below the window, if anything.
+    dim: Option<Dim<T>>,
+}
+
+impl<T: fmt::Display> Container<T> {
+    /// Returns the windows on the modal stack, bottom first. The last is the one
+    /// input is restricted to.
+    pub fn modal_stack(&self) -> impl Iterator<Item = WindowId> + '_ {
+        self.modals.iter().map(|modal| modal.win)
+    }
+
+    /// Restricts all input to the given window, focusing it, until it is popped with
+    /// [`pop_modal`](Self::pop_modal). Modals pushed while another is shown stack on
+    /// top of it, such as a confirmation opened from a settings dialog.
+    pub fn push_modal(&mut self, win: WindowId) {
+        self.start_modal(win, None);
+    }
+
+    /// Removes the window on top of the modal stack from it, returning it. Input goes
+    /// back to the modal below it, if any, and focus to the window focused when it was
+    /// pushed. The window itself is left in the container.
+    pub fn pop_modal(&mut self) -> Option<WindowId> {
+        let key = self.modals.last()?.key;
+        self.end_modal(key)
+    }
+
+    /// Returns the window of an entry of the modal stack, if it is still there.
+    pub(crate) fn modal_window(&self, key: u64) -> Option<WindowId> {
+        self.modals
+            .iter()
+            .find(|modal| modal.key == key)
+            .map(|modal| modal.win)
+    }
+
+    /// Removes an entry from the modal stack, wherever it is, returning its window.
+    /// Entries above it go back to what it went back to.
+    pub(crate) fn end_modal(&mut self, key: u64) -> Option<WindowId> {
+        let idx = self.modals.iter().position(|modal| modal.key == key)?;
+        let modal = self.modals.remove(idx);
+        match self.modals.get_mut(idx) {
+            Some(above) => {
+                above.prev_modal = modal.prev_modal;
+                if above.prev_focus == Some(modal.win) {
+                    above.prev_focus = modal.prev_focus;
+                }
+            }
+            None => {
+                self.modal = modal.prev_modal;
+                self.focus = modal.prev_focus;
+            }
+        }
+        if modal.dim.is_some() {
+            let (wid, hgt) = self.screen_size();
+            self.mark_dirty(Rect::new(Point::ORIGIN, wid as usize, hgt as usize).points());
+            self.request_refresh();
+        }
+        Some(modal.win)
+    }
+
+    /// Pushes a window onto the modal stack, returning the key of its entry.
+    pub(crate) fn start_modal(&mut self, win: WindowId, dim: Option<Dim<T>>) -> u64 {
+        let key = self.next_modal;
+        self.next_modal += 1;
+        self.modals.push(Modal {
+            key,
+            win,
+            prev_focus: self.focus,
+            prev_modal: self.modal,
+            dim,
+        });
+        self.set_modal(Some(win));
+        if dim.is_some() {
+            self.request_refresh();
+        }
+        key
+    }
+
+    /// Restyles the buffer below the topmost modal that dims, including positions
+    /// of the screen that nothing was drawn at.
+    pub(crate) fn apply_dim(&mut self)
+    where
+        T: PartialEq,
+    {
+        let Some((win, (style, apply))) = self
+            .modals
+            .iter()
+            .rev()
+            .find_map(|modal| modal.dim.map(|dim| (modal.win, dim)))
+        else {
+            return;
+        };
+        let (wid, hgt) = self.screen_size();
+        for p in Rect::new(Point::ORIGIN, wid as usize, hgt as usize).points() {
+            if self.owners.get(&p).is_some_and(|&owner| owner >= win) {
+                continue;
+            }
+            let value = apply(self.buffer.get(&p), style);
+            self.buffer.insert(p, value);
+        }
+    }
+}
+
+impl Container<Cell> {
+    /// Pushes a modal window like [`push_modal`](Self::push_modal), laying the style
+    /// over everything below it until it is popped, such as a dark foreground colour to
+    /// dim the rest of the screen.
+    pub fn push_modal_dimmed(&mut self, win: WindowId, style: Style) {
+        self.start_modal(win, Some((style, flash_cell)));
+    }
+}
@@ -0,0 +1,178 @@
+//! Short lived notifications shown in a corner of the screen.
+
+use crate::geometry::{Coord, Point};
+use crate::style::{Cell, Color, Style};
+use crate::widgets::Paragraph;
+use crate::{Container, Window, WindowId};
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// Widest the text of a notification is allowed to be before it wraps.
+const MAX_TEXT_WID: usize = 40;
+
+/// How important a notification is, which decides its colour.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Level {
+    #[default]
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl Level {
+    /// Colour of notifications of this level.
+    pub fn color(self) -> Color {
+        match self {
+            Level::Info => Color::Blue,
+            Level::Success => Color::Green,
+            Level::Warning => Color::Yellow,
+            Level::Error => Color::Red,
+        }
+    }
+}
+
+/// A corner of the screen.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Corner {
+    TopLeft,
+    #[default]
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+/// A notification being shown.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Toast {
+    pub(crate) win: WindowId,
+    expires: Instant,
+}
+
+impl<T: fmt::Display> Container<T> {
+    /// Sets the corner notifications are stacked in.
+    pub fn set_toast_corner(&mut self, corner: Corner) {
+        self.toast_corner = corner;
+        self.layout_toasts();
+    }
+
+    /// Returns the ids of the windows of the notifications being shown, oldest first.
+    pub fn toasts(&self) -> Vec<WindowId> {
+        self.toasts.iter().map(|t| t.win).collect()
+    }
+
+    /// Removes every notification whose time is up, moving the rest up to fill the
+    /// gaps. As notifications are above every other window, this doesn't change the
+    /// ids of other windows. This is done by [`run`](Self::run) as soon as each one's
+    /// time is up. Returns how many were removed.
+    pub fn expire_toasts(&mut self) -> usize {
+        let now = Instant::now();
+        let mut removed = 0;
+
+        while let Some(idx) = self.toasts.iter().position(|t| t.expires <= now) {
+            let win = self.toasts[idx].win;
+            self.remove_win(win);
+            removed += 1;
+        }
+
+        if removed > 0 {
+            self.layout_toasts();
+        }
+        removed
+    }
+
+    /// Returns when the next notification expires, if any are shown.
+    pub(crate) fn next_toast_expiry(&self) -> Option<Instant> {
+        self.toasts.iter().map(|t| t.expires).min()
+    }
+
+    /// Stacks the notifications in their corner, newest closest to the corner.
+    pub(crate) fn layout_toasts(&mut self) {
+        let (screen_wid, screen_hgt) = self.screen_size();
+        let mut offset = 0;
+
+        for toast in self.toasts.iter().rev() {
+            let win = &self.windows[toast.win];
+            let (wid, hgt) = (win.width() as Coord, win.height() as Coord);
+            let x = match self.toast_corner {
+                Corner::TopLeft | Corner::BottomLeft => 0,
+                Corner::TopRight | Corner::BottomRight => screen_wid as Coord - wid,
+            };
+            let y = match self.toast_corner {
+                Corner::TopLeft | Corner::TopRight => offset,
+                Corner::BottomLeft | Corner::BottomRight => screen_hgt as Coord - offset - hgt,
+            };
+            offset += hgt;
+
+            let to = Point::new(x, y);
+            if win.top_left != to {
+                let old = win.footprint().collect::<Vec<_>>();
+                self.dirty.extend(old);
+                self.windows[toast.win].top_left = to;
+            }
+        }
+    }
+}
+
+impl Container<Cell> {
+    /// Shows a notification with the given text for the given duration, stacked with
+    /// any others in the notification corner. Returns the id of its window.
+    pub fn notify(&mut self, text: &str, level: Level, duration: Duration) -> WindowId {
+        let style = Style::new().fg(level.color());
+        let text_wid = text
+            .lines()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0)
+            .clamp(1, MAX_TEXT_WID);
+        let lines = Paragraph::new([text]).wrap(true).lines(text_wid);
+
+        let mut win = Window::new(Point::new(0, 0));
+        win.data.push(Vec::new());
+        win.data[0].push(Cell::new('╭', style));
+        win.data[0].extend(std::iter::repeat_n(Cell::new('─', style), text_wid + 2));
+        win.data[0].push(Cell::new('╮', style));
+
+        for line in lines {
+            let mut row = vec![Cell::new('│', style), Cell::default()];
+            row.extend(line.iter().copied());
+            row.resize(text_wid + 3, Cell::default());
+            row.push(Cell::new('│', style));
+            win.data.push(row);
+        }
+
+        let mut bottom = vec![Cell::new('╰', style)];
+        bottom.extend(std::iter::repeat_n(Cell::new('─', style), text_wid + 2));
+        bottom.push(Cell::new('╯', style));
+        win.data.push(bottom);
+
+        // Notifications go above everything else, so that their coming and going
+        // leaves the ids of other windows alone.
+        self.windows.push(win);
+        let id = self.windows.len() - 1;
+        self.toasts.push(Toast {
+            win: id,
+            expires: Instant::now() + duration,
+        });
+        self.layout_toasts();
+        id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expiring_leaves_other_ids_alone() {
+        let mut container: Container<Cell> = Container::new();
+        container.set_screen_size(40, 12);
+        container.notify("Saved", Level::Success, Duration::ZERO);
+        let win = container.add_win(Window::new(Point::new(0, 0)));
+        assert_eq!(win, 0);
+
+        assert_eq!(container.expire_toasts(), 1);
+        assert_eq!(container.windows.len(), 1);
+        assert_eq!(container.windows[win].top_left, Point::new(0, 0));
+    }
+}
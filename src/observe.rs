@@ -0,0 +1,58 @@
+//! Callbacks told about what changed after each refresh.
+
+use crate::Container;
+use crate::geometry::Point;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+/// Identifies an observer added with [`Container::on_change`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ObserverId(u64);
+
+/// A callback given the positions changed by a refresh.
+type Callback = Arc<Mutex<dyn FnMut(&[Point]) + Send>>;
+
+/// The observers of a container. Clones of a container share them.
+#[derive(Clone, Default)]
+pub(crate) struct Observers {
+    next: u64,
+    callbacks: Vec<(ObserverId, Callback)>,
+}
+
+impl Observers {
+    /// Calls every observer with the changed positions.
+    pub(crate) fn notify(&self, changed: &[Point]) {
+        for (_, callback) in self.callbacks.iter() {
+            // An observer that panicked before is still called.
+            let mut callback = callback.lock().unwrap_or_else(|e| e.into_inner());
+            callback(changed);
+        }
+    }
+}
+
+impl fmt::Debug for Observers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.callbacks.iter().map(|(id, _)| id))
+            .finish()
+    }
+}
+
+impl<T: fmt::Display> Container<T> {
+    /// Adds a callback that is given every position changed by a refresh, after each
+    /// refresh that changes anything. Returns an id to remove it with.
+    pub fn on_change(&mut self, f: impl FnMut(&[Point]) + Send + 'static) -> ObserverId {
+        let id = ObserverId(self.observers.next);
+        self.observers.next += 1;
+        self.observers.callbacks.push((id, Arc::new(Mutex::new(f))));
+        id
+    }
+
+    /// Removes a callback added with [`on_change`](Self::on_change). Returns false if
+    /// it was already removed.
+    pub fn remove_observer(&mut self, id: ObserverId) -> bool {
+        let len = self.observers.callbacks.len();
+        self.observers.callbacks.retain(|(other, _)| *other != id);
+        self.observers.callbacks.len() != len
+    }
+}
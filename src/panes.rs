@@ -0,0 +1,680 @@
+//! Tiling an area of the screen with panes, each showing a window, by splitting
+//! them in two like a terminal multiplexer.
+
+use crate::event::{Event, WindowEvent};
+use crate::geometry::{Coord, Rect};
+use crate::{Container, TrackedId, Window, WindowId};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Identifies a pane of [`Panes`]. Ids aren't reused once a pane is closed.
+pub type PaneId = usize;
+
+/// A direction on screen, such as to look for a neighbouring pane in or for a
+/// [`Transition`](crate::Transition) to move in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// Something done to the focused pane by [`Panes::perform`], which can be bound to
+/// keys.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PaneAction {
+    /// Split it with a new pane on the right.
+    SplitHorizontal,
+    /// Split it with a new pane below.
+    SplitVertical,
+    /// Close it.
+    Close,
+    /// Focus the pane next to it in a direction.
+    Focus(Direction),
+    /// Move its edge facing a direction that way by a number of cells.
+    Grow(Direction, usize),
+    /// Zoom it to fill the whole area, or put the panes back if one is zoomed.
+    ToggleZoom,
+}
+
+/// How a split divides its area.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Axis {
+    /// Side by side, first on the left.
+    Horizontal,
+    /// One above the other, first on top.
+    Vertical,
+}
+
+/// A pane, or an area split between two others.
+#[derive(Clone, Debug, PartialEq)]
+enum Node {
+    Pane(PaneId),
+    Split {
+        axis: Axis,
+        // Share of the area given to the first.
+        ratio: f64,
+        first: Box<Node>,
+        second: Box<Node>,
+    },
+}
+
+/// Returns how much of `len` cells the first side of a split gets, leaving at least
+/// one for each side if there is room.
+fn first_len(len: usize, ratio: f64) -> usize {
+    if len < 2 {
+        return len;
+    }
+    ((len as f64 * ratio).round() as usize).clamp(1, len - 1)
+}
+
+/// Returns the areas of the two sides of a split.
+fn split_rect(area: Rect, axis: Axis, ratio: f64) -> (Rect, Rect) {
+    match axis {
+        Axis::Horizontal => {
+            let wid = first_len(area.wid, ratio);
+            (
+                Rect::new(area.top_left, wid, area.hgt),
+                Rect::new(
+                    area.top_left.offset(wid as Coord, 0),
+                    area.wid - wid,
+                    area.hgt,
+                ),
+            )
+        }
+        Axis::Vertical => {
+            let hgt = first_len(area.hgt, ratio);
+            (
+                Rect::new(area.top_left, area.wid, hgt),
+                Rect::new(
+                    area.top_left.offset(0, hgt as Coord),
+                    area.wid,
+                    area.hgt - hgt,
+                ),
+            )
+        }
+    }
+}
+
+impl Node {
+    /// Adds the area of each pane under this node to `out`, in order.
+    fn rects(&self, area: Rect, out: &mut Vec<(PaneId, Rect)>) {
+        match self {
+            Node::Pane(pane) => out.push((*pane, area)),
+            Node::Split {
+                axis,
+                ratio,
+                first,
+                second,
+            } => {
+                let (a, b) = split_rect(area, *axis, *ratio);
+                first.rects(a, out);
+                second.rects(b, out);
+            }
+        }
+    }
+
+    /// Returns the node of the pane, if it is under this one.
+    fn find_mut(&mut self, pane: PaneId) -> Option<&mut Node> {
+        match self {
+            Node::Pane(p) if *p == pane => Some(self),
+            Node::Pane(_) => None,
+            Node::Split { first, second, .. } => {
+                first.find_mut(pane).or_else(|| second.find_mut(pane))
+            }
+        }
+    }
+
+    /// Returns the node on the other side of the split the pane is in, if it is
+    /// under this one and split from anything.
+    fn sibling(&self, pane: PaneId) -> Option<&Node> {
+        let Node::Split { first, second, .. } = self else {
+            return None;
+        };
+        if **first == Node::Pane(pane) {
+            Some(second)
+        } else if **second == Node::Pane(pane) {
+            Some(first)
+        } else {
+            first.sibling(pane).or_else(|| second.sibling(pane))
+        }
+    }
+
+    /// Returns the first pane under this node.
+    fn first_pane(&self) -> PaneId {
+        match self {
+            Node::Pane(pane) => *pane,
+            Node::Split { first, .. } => first.first_pane(),
+        }
+    }
+
+    /// Moves the edge of the pane facing `dir` by `amount` cells that way, by changing
+    /// the share of the nearest split that has the pane on that side. Returns None
+    /// if the pane isn't under this node, or whether anything changed.
+    fn grow(&mut self, area: Rect, pane: PaneId, dir: Direction, amount: usize) -> Option<bool> {
+        let Node::Split {
+            axis,
+            ratio,
+            first,
+            second,
+        } = self
+        else {
+            return (*self == Node::Pane(pane)).then_some(false);
+        };
+        let (a, b) = split_rect(area, *axis, *ratio);
+        let (in_first, grown) = match first.grow(a, pane, dir, amount) {
+            Some(grown) => (true, grown),
+            None => (false, second.grow(b, pane, dir, amount)?),
+        };
+        if grown {
+            return Some(true);
+        }
+
+        let facing = match dir {
+            Direction::Left => *axis == Axis::Horizontal && !in_first,
+            Direction::Right => *axis == Axis::Horizontal && in_first,
+            Direction::Up => *axis == Axis::Vertical && !in_first,
+            Direction::Down => *axis == Axis::Vertical && in_first,
+        };
+        let total = match axis {
+            Axis::Horizontal => area.wid,
+            Axis::Vertical => area.hgt,
+        };
+        if !facing || total < 2 {
+            return Some(false);
+        }
+
+        let len = first_len(total, *ratio);
+        let new = if in_first {
+            len.saturating_add(amount)
+        } else {
+            len.saturating_sub(amount)
+        }
+        .clamp(1, total - 1);
+        *ratio = new as f64 / total as f64;
+        Some(new != len)
+    }
+
+    /// Removes the pane from under this node, giving its space to the other side of
+    /// the split it was in. Returns false if it isn't under this one.
+    fn remove(&mut self, pane: PaneId) -> bool {
+        let Node::Split { first, second, .. } = self else {
+            return false;
+        };
+        let sibling = if **first == Node::Pane(pane) {
+            std::mem::replace(&mut **second, Node::Pane(pane))
+        } else if **second == Node::Pane(pane) {
+            std::mem::replace(&mut **first, Node::Pane(pane))
+        } else {
+            return first.remove(pane) || second.remove(pane);
+        };
+        *self = sibling;
+        true
+    }
+}
+
+/// An area of the screen tiled with panes, each showing a window of a container.
+/// Panes are split in two to make new ones, and the windows are moved and resized to
+/// fill their panes whenever the panes change, with a
+/// [`WindowEvent::Resized`] posted for each window whose size changed so that its
+/// content can be redrawn to fit.
+///
+/// A pane can be zoomed to fill the whole area on its own for a while, with the
+/// others hidden until it is put back.
+///
+/// The panes [track](Container::track) their windows, so other windows can be added
+/// to and removed from the container in between.
+#[derive(Clone, Debug)]
+pub struct Panes {
+    area: Rect,
+    root: Option<Node>,
+    windows: HashMap<PaneId, TrackedId>,
+    next: PaneId,
+    zoom: Option<Zoom>,
+}
+
+/// A pane filling the whole area, with what to put back when it stops.
+#[derive(Clone, Debug)]
+struct Zoom {
+    pane: PaneId,
+    // Scroll position of the pane's window before it was zoomed.
+    scroll: usize,
+    // Whether the window of each other pane was hidden before.
+    hidden: HashMap<PaneId, bool>,
+}
+
+impl Panes {
+    /// Create a single pane filling the area, with a new window added to the
+    /// container. The pane is focused.
+    pub fn new<T>(container: &mut Container<T>, area: Rect) -> Self
+    where
+        T: fmt::Display + Clone + Default,
+    {
+        let mut panes = Self {
+            area,
+            root: Some(Node::Pane(0)),
+            windows: HashMap::new(),
+            next: 1,
+            zoom: None,
+        };
+        let win = container.add_win(Window::new(area.top_left));
+        panes.windows.insert(0, container.track(win));
+        container.set_focus(Some(win));
+        panes.layout(container);
+        panes
+    }
+
+    /// Returns the area the panes fill.
+    pub fn area(&self) -> Rect {
+        self.area
+    }
+
+    /// Changes the area the panes fill, such as when the screen is resized, keeping
+    /// the share each side of every split gets.
+    pub fn set_area<T>(&mut self, container: &mut Container<T>, area: Rect)
+    where
+        T: fmt::Display + Clone + Default,
+    {
+        self.area = area;
+        self.layout(container);
+    }
+
+    /// Returns every pane along with its area, from left to right and top to bottom
+    /// within each split. While a pane is zoomed, these are the areas the panes get
+    /// back when it stops.
+    pub fn rects(&self) -> Vec<(PaneId, Rect)> {
+        let mut out = Vec::new();
+        if let Some(root) = &self.root {
+            root.rects(self.area, &mut out);
+        }
+        out
+    }
+
+    /// Returns every pane, in the order of [`rects`](Self::rects).
+    pub fn panes(&self) -> Vec<PaneId> {
+        self.rects().into_iter().map(|(pane, _)| pane).collect()
+    }
+
+    /// Returns the area of the pane, if it exists.
+    pub fn rect(&self, pane: PaneId) -> Option<Rect> {
+        self.rects()
+            .into_iter()
+            .find_map(|(p, rect)| (p == pane).then_some(rect))
+    }
+
+    /// Returns the id of the window shown in the pane, if the pane exists and its
+    /// window is still in the container.
+    pub fn window<T: fmt::Display>(
+        &self,
+        container: &Container<T>,
+        pane: PaneId,
+    ) -> Option<WindowId> {
+        container.tracked(*self.windows.get(&pane)?)
+    }
+
+    /// Returns the pane showing the window, if any.
+    pub fn pane_of<T: fmt::Display>(
+        &self,
+        container: &Container<T>,
+        win: WindowId,
+    ) -> Option<PaneId> {
+        self.windows
+            .iter()
+            .find_map(|(&pane, &id)| (container.tracked(id) == Some(win)).then_some(pane))
+    }
+
+    /// Returns the pane whose window is focused, if any.
+    pub fn focused<T: fmt::Display>(&self, container: &Container<T>) -> Option<PaneId> {
+        self.pane_of(container, container.focused()?)
+    }
+
+    /// Focuses the window of the pane.
+    pub fn focus<T: fmt::Display>(&self, container: &mut Container<T>, pane: PaneId) {
+        if let Some(win) = self.window(container, pane) {
+            container.set_focus(Some(win));
+        }
+    }
+
+    /// Splits the pane in two side by side, with a new pane on the right, like tmux's
+    /// horizontal split. The new pane is focused and returned, or None if the pane
+    /// doesn't exist.
+    pub fn split_horizontal<T>(
+        &mut self,
+        container: &mut Container<T>,
+        pane: PaneId,
+    ) -> Option<PaneId>
+    where
+        T: fmt::Display + Clone + Default,
+    {
+        self.split(container, pane, Axis::Horizontal)
+    }
+
+    /// Splits the pane in two one above the other, with a new pane below. The new
+    /// pane is focused and returned, or None if the pane doesn't exist.
+    pub fn split_vertical<T>(
+        &mut self,
+        container: &mut Container<T>,
+        pane: PaneId,
+    ) -> Option<PaneId>
+    where
+        T: fmt::Display + Clone + Default,
+    {
+        self.split(container, pane, Axis::Vertical)
+    }
+
+    fn split<T>(&mut self, container: &mut Container<T>, pane: PaneId, axis: Axis) -> Option<PaneId>
+    where
+        T: fmt::Display + Clone + Default,
+    {
+        self.unzoom(container);
+        let node = self.root.as_mut()?.find_mut(pane)?;
+        let new = self.next;
+        self.next += 1;
+        *node = Node::Split {
+            axis,
+            ratio: 0.5,
+            first: Box::new(Node::Pane(pane)),
+            second: Box::new(Node::Pane(new)),
+        };
+
+        let win = container.add_win(Window::new(self.area.top_left));
+        self.windows.insert(new, container.track(win));
+        container.set_focus(Some(win));
+        self.layout(container);
+        Some(new)
+    }
+
+    /// Closes the pane, removing its window from the container and giving its space
+    /// to the pane or panes it was split from. If it was focused, the pane taking
+    /// its place is focused. Returns false if the pane doesn't exist.
+    pub fn close<T>(&mut self, container: &mut Container<T>, pane: PaneId) -> bool
+    where
+        T: fmt::Display + Clone + Default,
+    {
+        if !self.windows.contains_key(&pane) {
+            return false;
+        }
+        self.unzoom(container);
+        let win = self
+            .windows
+            .remove(&pane)
+            .and_then(|id| container.untrack(id));
+        let was_focused = win.is_some() && container.focused() == win;
+        let successor = self
+            .root
+            .as_ref()
+            .and_then(|root| root.sibling(pane))
+            .map(Node::first_pane);
+
+        match &mut self.root {
+            Some(Node::Pane(_)) => self.root = None,
+            Some(root) => {
+                root.remove(pane);
+            }
+            None => {}
+        }
+
+        if let Some(win) = win {
+            container.remove_win(win);
+        }
+        if let Some(successor) = successor.filter(|_| was_focused) {
+            self.focus(container, successor);
+        }
+        self.layout(container);
+        true
+    }
+
+    /// Returns the pane next to the pane in the given direction, if any. Of the
+    /// panes along that edge, the one level with the pane's top left is picked.
+    pub fn neighbour(&self, pane: PaneId, dir: Direction) -> Option<PaneId> {
+        let rects = self.rects();
+        let rect = rects.iter().find(|(p, _)| *p == pane)?.1;
+        let (edge, level) = match dir {
+            Direction::Left => (rect.top_left.x, rect.top_left.y),
+            Direction::Right => (rect.right(), rect.top_left.y),
+            Direction::Up => (rect.top_left.y, rect.top_left.x),
+            Direction::Down => (rect.bottom(), rect.top_left.x),
+        };
+
+        rects
+            .iter()
+            .filter(|(p, r)| {
+                *p != pane
+                    && match dir {
+                        Direction::Left => r.right() == edge,
+                        Direction::Right => r.top_left.x == edge,
+                        Direction::Up => r.bottom() == edge,
+                        Direction::Down => r.top_left.y == edge,
+                    }
+            })
+            .filter(|(_, r)| match dir {
+                Direction::Left | Direction::Right => {
+                    r.top_left.y < rect.bottom() && r.bottom() > rect.top_left.y
+                }
+                Direction::Up | Direction::Down => {
+                    r.top_left.x < rect.right() && r.right() > rect.top_left.x
+                }
+            })
+            .min_by_key(|(_, r)| {
+                let start = match dir {
+                    Direction::Left | Direction::Right => r.top_left.y,
+                    Direction::Up | Direction::Down => r.top_left.x,
+                };
+                // The pane covering the level starts at or before it.
+                (start > level, (level - start).abs())
+            })
+            .map(|(p, _)| *p)
+    }
+
+    /// Focuses the pane next to the focused one in the given direction, returning it
+    /// if there is one. If a pane is zoomed, the panes are put back first.
+    pub fn focus_towards<T>(
+        &mut self,
+        container: &mut Container<T>,
+        dir: Direction,
+    ) -> Option<PaneId>
+    where
+        T: fmt::Display + Clone + Default,
+    {
+        self.unzoom(container);
+        let pane = self.neighbour(self.focused(container)?, dir)?;
+        self.focus(container, pane);
+        Some(pane)
+    }
+
+    /// Grows the pane by moving its edge facing `dir` that way by `amount` cells,
+    /// shrinking the panes on the other side of it. Nothing happens if the pane has
+    /// no edge that way that can move, such as at the edge of the area. Returns true
+    /// if any pane changed size.
+    pub fn grow<T>(
+        &mut self,
+        container: &mut Container<T>,
+        pane: PaneId,
+        dir: Direction,
+        amount: usize,
+    ) -> bool
+    where
+        T: fmt::Display + Clone + Default,
+    {
+        self.unzoom(container);
+        let area = self.area;
+        let grown = self
+            .root
+            .as_mut()
+            .and_then(|root| root.grow(area, pane, dir, amount))
+            .unwrap_or(false);
+        if grown {
+            self.layout(container);
+        }
+        grown
+    }
+
+    /// Does something to the focused pane, or to the only pane if there is no
+    /// focus, such as in response to keys bound to actions with a
+    /// [`Keymap`](crate::keymap::Keymap). Returns false if there was no pane to do
+    /// it to or nothing changed.
+    pub fn perform<T>(&mut self, container: &mut Container<T>, action: PaneAction) -> bool
+    where
+        T: fmt::Display + Clone + Default,
+    {
+        let panes = self.panes();
+        let only = (panes.len() == 1).then(|| panes[0]);
+        let Some(pane) = self.focused(container).or(only) else {
+            return false;
+        };
+
+        match action {
+            PaneAction::SplitHorizontal => self.split_horizontal(container, pane).is_some(),
+            PaneAction::SplitVertical => self.split_vertical(container, pane).is_some(),
+            PaneAction::Close => self.close(container, pane),
+            PaneAction::Focus(dir) => self.focus_towards(container, dir).is_some(),
+            PaneAction::Grow(dir, amount) => self.grow(container, pane, dir, amount),
+            PaneAction::ToggleZoom => self.toggle_zoom(container, pane),
+        }
+    }
+
+    /// Returns the pane zoomed to fill the whole area, if any.
+    pub fn zoomed(&self) -> Option<PaneId> {
+        self.zoom.as_ref().map(|zoom| zoom.pane)
+    }
+
+    /// Zooms the pane to fill the whole area, hiding the others and focusing it, or
+    /// if a pane is already zoomed, puts every pane back as it was. The panes left
+    /// hidden keep their contents, and the zoomed pane is scrolled back to where it
+    /// was. Splitting, closing, growing or moving focus between panes puts them back
+    /// first. Returns false if the pane doesn't exist.
+    pub fn toggle_zoom<T>(&mut self, container: &mut Container<T>, pane: PaneId) -> bool
+    where
+        T: fmt::Display + Clone + Default,
+    {
+        if self.zoom.is_some() {
+            self.unzoom(container);
+            return true;
+        }
+        let Some(win) = self.window(container, pane) else {
+            return false;
+        };
+
+        let mut hidden = HashMap::new();
+        for (&other, &id) in self.windows.iter().filter(|&(&p, _)| p != pane) {
+            let Some(other_win) = container.tracked(id) else {
+                continue;
+            };
+            hidden.insert(other, container.windows[other_win].hidden);
+            container.set_hidden(other_win, true);
+        }
+        self.zoom = Some(Zoom {
+            pane,
+            scroll: container.windows[win].scroll,
+            hidden,
+        });
+        container.set_focus(Some(win));
+        self.layout(container);
+        true
+    }
+
+    /// Puts the panes back as they were before one was zoomed, if one is.
+    fn unzoom<T>(&mut self, container: &mut Container<T>)
+    where
+        T: fmt::Display + Clone + Default,
+    {
+        let Some(zoom) = self.zoom.take() else {
+            return;
+        };
+        for (other, hidden) in zoom.hidden {
+            if let Some(win) = self.window(container, other) {
+                container.set_hidden(win, hidden);
+            }
+        }
+        self.layout(container);
+        if let Some(win) = self.window(container, zoom.pane) {
+            let window = &mut container.windows[win];
+            window.scroll = zoom.scroll.min(window.max_scroll());
+        }
+    }
+
+    /// Moves and resizes every pane's window to fill the pane.
+    fn layout<T>(&self, container: &mut Container<T>)
+    where
+        T: fmt::Display + Clone + Default,
+    {
+        let rects = match &self.zoom {
+            Some(zoom) => vec![(zoom.pane, self.area)],
+            None => self.rects(),
+        };
+        for (pane, rect) in rects {
+            let Some(win) = self.window(container, pane) else {
+                continue;
+            };
+            let old = container.windows[win].rect();
+            if old == rect {
+                continue;
+            }
+            container.set_rect(win, rect, T::default());
+            if old.size() != rect.size() {
+                container.post(Event::Window(WindowEvent::Resized {
+                    win,
+                    wid: rect.wid,
+                    hgt: rect.hgt,
+                }));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::Point;
+
+    #[test]
+    fn splitting_growing_and_closing() {
+        let mut container: Container<char> = Container::new();
+        let mut panes = Panes::new(&mut container, Rect::new(Point::new(0, 0), 20, 10));
+        let right = panes.split_horizontal(&mut container, 0).unwrap();
+        let below = panes.split_vertical(&mut container, right).unwrap();
+        assert_eq!(
+            panes.rects(),
+            [
+                (0, Rect::new(Point::new(0, 0), 10, 10)),
+                (right, Rect::new(Point::new(10, 0), 10, 5)),
+                (below, Rect::new(Point::new(10, 5), 10, 5)),
+            ]
+        );
+        assert_eq!(panes.neighbour(0, Direction::Right), Some(right));
+        assert_eq!(panes.neighbour(below, Direction::Up), Some(right));
+        assert_eq!(panes.neighbour(below, Direction::Left), Some(0));
+        assert_eq!(panes.focused(&container), Some(below));
+
+        assert!(panes.grow(&mut container, 0, Direction::Right, 2));
+        assert_eq!(panes.rect(right), Some(Rect::new(Point::new(12, 0), 8, 5)));
+        assert!(!panes.grow(&mut container, 0, Direction::Left, 2));
+
+        assert!(panes.close(&mut container, below));
+        assert_eq!(panes.rect(right), Some(Rect::new(Point::new(12, 0), 8, 10)));
+        assert_eq!(panes.focused(&container), Some(right));
+        assert_eq!(container.windows.len(), 2);
+    }
+
+    #[test]
+    fn windows_added_and_removed_outside() {
+        let mut container: Container<char> = Container::new();
+        let below = container.add_win(Window::new(Point::new(0, 0)));
+        let mut panes = Panes::new(&mut container, Rect::new(Point::new(0, 0), 20, 10));
+        let right = panes.split_horizontal(&mut container, 0).unwrap();
+        container.insert_win(0, Window::new(Point::new(0, 0)));
+        container.remove_win(below + 1);
+
+        assert!(panes.toggle_zoom(&mut container, right));
+        let win = panes.window(&container, right).unwrap();
+        assert_eq!(container.windows[win].rect(), panes.area());
+        assert!(container.windows[panes.window(&container, 0).unwrap()].hidden);
+
+        assert!(panes.close(&mut container, right));
+        assert_eq!(container.windows.len(), 2);
+        let win = panes.window(&container, 0).unwrap();
+        assert_eq!(container.windows[win].rect(), panes.area());
+        assert_eq!(panes.focused(&container), Some(0));
+    }
+}
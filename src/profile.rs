@@ -0,0 +1,89 @@
+//! Timing how long each part of a frame takes.
+
+use crate::{Container, WindowId};
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// Time spent on one window during a frame.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct WindowTime {
+    /// Time spent redrawing the window from its bound sources.
+    pub bindings: Duration,
+    /// Time spent drawing the window into the buffer.
+    pub composite: Duration,
+}
+
+impl WindowTime {
+    /// Returns the time spent on the window altogether.
+    pub fn total(&self) -> Duration {
+        self.bindings + self.composite
+    }
+}
+
+/// How long each part of the latest frame took, recorded once profiling is enabled
+/// with [`Container::enable_profiling`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Profile {
+    /// Number of refreshes recorded.
+    pub frames: u64,
+    /// Time spent redrawing bound windows.
+    pub bindings: Duration,
+    /// Time spent applying queued [`Updates`](crate::Updates).
+    pub updates: Duration,
+    /// Time spent drawing the windows into the buffer.
+    pub composite: Duration,
+    /// Time spent writing the changes to a backend.
+    pub present: Duration,
+    /// Time spent on each window, by id.
+    pub windows: Vec<WindowTime>,
+}
+
+impl Profile {
+    /// Returns the time spent on the frame altogether.
+    pub fn total(&self) -> Duration {
+        self.bindings + self.updates + self.composite + self.present
+    }
+
+    /// Returns the ids of the `n` windows that took longest, along with their times,
+    /// slowest first.
+    pub fn slowest(&self, n: usize) -> Vec<(WindowId, WindowTime)> {
+        let mut windows: Vec<_> = self.windows.iter().copied().enumerate().collect();
+        windows.sort_by_key(|(_, time)| std::cmp::Reverse(time.total()));
+        windows.truncate(n);
+        windows
+    }
+}
+
+impl<T: fmt::Display> Container<T> {
+    /// Starts recording how long refreshing and presenting the container takes, as
+    /// well as how long each window takes.
+    pub fn enable_profiling(&mut self) {
+        self.profile.get_or_insert_with(Profile::default);
+    }
+
+    /// Stops recording, discarding what was recorded.
+    pub fn disable_profiling(&mut self) {
+        self.profile = None;
+    }
+
+    /// Returns the times recorded for the latest frame, if profiling is enabled.
+    pub fn profile(&self) -> Option<&Profile> {
+        self.profile.as_ref()
+    }
+
+    /// Returns the time now if profiling is enabled, to time something from.
+    pub(crate) fn profile_start(&self) -> Option<Instant> {
+        self.profile.as_ref().map(|_| Instant::now())
+    }
+
+    /// Records the time since `start` if profiling is enabled.
+    pub(crate) fn profile_record(
+        &mut self,
+        start: Option<Instant>,
+        record: impl FnOnce(&mut Profile, Duration),
+    ) {
+        if let (Some(profile), Some(start)) = (self.profile.as_mut(), start) {
+            record(profile, start.elapsed());
+        }
+    }
+}
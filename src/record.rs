@@ -0,0 +1,533 @@
+//! Recording what is presented to a backend.
+
+use crate::Container;
+use crate::backend::Backend;
+use crate::event::{Event, MouseMode};
+use crate::geometry::{Coord, Point};
+use crate::image::Pixels;
+use std::collections::HashMap;
+use std::fmt::{self, Write as _};
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Returns a string as a JSON string literal.
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for ch in s.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 || c == '\u{7f}' => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// A backend that passes everything on to another while recording the output as an
+/// asciinema v2 cast, which can be played back with `asciinema play`. Everything
+/// written between flushes becomes one event, timed from when recording started.
+pub struct CastRecorder<B: Backend, W: Write> {
+    inner: B,
+    out: W,
+    start: Instant,
+    // Output written since the last flush.
+    pending: String,
+}
+
+impl<B: Backend> CastRecorder<B, BufWriter<File>> {
+    /// Start recording to a `.cast` file.
+    pub fn create(inner: B, path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::new(inner, BufWriter::new(File::create(path)?))
+    }
+}
+
+impl<B: Backend, W: Write> CastRecorder<B, W> {
+    /// Start recording, writing the cast's header straight away.
+    pub fn new(mut inner: B, mut out: W) -> io::Result<Self> {
+        let (wid, hgt) = inner.size()?;
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        writeln!(
+            out,
+            "{{\"version\": 2, \"width\": {wid}, \"height\": {hgt}, \"timestamp\": {timestamp}}}"
+        )?;
+
+        Ok(Self {
+            inner,
+            out,
+            start: Instant::now(),
+            pending: String::new(),
+        })
+    }
+
+    /// Writes an event of the given kind, such as `o` for output.
+    fn write_event(&mut self, kind: &str, data: &str) -> io::Result<()> {
+        let time = self.start.elapsed().as_secs_f64();
+        writeln!(self.out, "[{time:.6}, \"{kind}\", {}]", json_string(data))
+    }
+
+    /// Stops recording, returning the backend and where the cast was written.
+    pub fn finish(mut self) -> io::Result<(B, W)> {
+        if !self.pending.is_empty() {
+            let pending = std::mem::take(&mut self.pending);
+            self.write_event("o", &pending)?;
+        }
+        self.out.flush()?;
+        Ok((self.inner, self.out))
+    }
+}
+
+impl<B: Backend, W: Write> Backend for CastRecorder<B, W> {
+    fn size(&mut self) -> io::Result<(u16, u16)> {
+        self.inner.size()
+    }
+
+    fn read_event(&mut self, timeout: Duration) -> io::Result<Option<Event>> {
+        let ev = self.inner.read_event(timeout)?;
+        if let Some(Event::Resize(wid, hgt)) = ev {
+            self.write_event("r", &format!("{wid}x{hgt}"))?;
+        }
+        Ok(ev)
+    }
+
+    fn write_cell(&mut self, p: Point, cell: Option<&dyn fmt::Display>) -> io::Result<()> {
+        let _ = write!(self.pending, "\x1b[{};{}H", p.y + 1, p.x + 1);
+        let _ = match cell {
+            Some(cell) => write!(self.pending, "{cell}"),
+            None => write!(self.pending, " "),
+        };
+        self.inner.write_cell(p, cell)
+    }
+
+    fn supports_sixel(&self) -> bool {
+        self.inner.supports_sixel()
+    }
+
+    fn write_sixel(&mut self, p: Point, sixel: &str) -> io::Result<()> {
+        let _ = write!(self.pending, "\x1b[{};{}H{sixel}", p.y + 1, p.x + 1);
+        self.inner.write_sixel(p, sixel)
+    }
+
+    fn supports_kitty_graphics(&self) -> bool {
+        self.inner.supports_kitty_graphics()
+    }
+
+    fn transmit_image(&mut self, image: u32, pixels: &Pixels) -> io::Result<()> {
+        self.inner.transmit_image(image, pixels)
+    }
+
+    fn place_image(&mut self, image: u32, placement: u32, p: Point, z: i32) -> io::Result<()> {
+        self.inner.place_image(image, placement, p, z)
+    }
+
+    fn delete_image(&mut self, image: u32, placement: Option<u32>) -> io::Result<()> {
+        self.inner.delete_image(image, placement)
+    }
+
+    fn set_cursor(&mut self, p: Option<Point>) -> io::Result<()> {
+        match p {
+            Some(p) => {
+                let _ = write!(self.pending, "\x1b[{};{}H\x1b[?25h", p.y + 1, p.x + 1);
+            }
+            None => self.pending.push_str("\x1b[?25l"),
+        }
+        self.inner.set_cursor(p)
+    }
+
+    fn show_cursor(&mut self) -> io::Result<()> {
+        self.pending.push_str("\x1b[?25h");
+        self.inner.show_cursor()
+    }
+
+    fn hide_cursor(&mut self) -> io::Result<()> {
+        self.pending.push_str("\x1b[?25l");
+        self.inner.hide_cursor()
+    }
+
+    fn enable_mouse(&mut self, mode: MouseMode) -> io::Result<()> {
+        self.inner.enable_mouse(mode)
+    }
+
+    fn disable_mouse(&mut self) -> io::Result<()> {
+        self.inner.disable_mouse()
+    }
+
+    fn clear(&mut self) -> io::Result<()> {
+        self.pending.push_str("\x1b[2J");
+        self.inner.clear()
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if !self.pending.is_empty() {
+            let pending = std::mem::take(&mut self.pending);
+            self.write_event("o", &pending)?;
+        }
+        self.inner.flush()
+    }
+}
+
+/// What a container showed at some point while recording.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Frame<T> {
+    /// Time since recording started.
+    pub time: Duration,
+    /// The container's buffer.
+    pub cells: HashMap<Point, T>,
+}
+
+/// Records the buffer of a container each time it is given one, so that it can be
+/// played back later with a [`Player`].
+#[derive(Clone, Debug)]
+pub struct Recorder<T> {
+    start: Instant,
+    frames: Vec<Frame<T>>,
+}
+
+impl<T> Default for Recorder<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Recorder<T> {
+    /// Start recording.
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            frames: Vec::new(),
+        }
+    }
+
+    /// Records a frame of what the container currently shows. This should be called
+    /// after each refresh.
+    pub fn record(&mut self, container: &Container<T>)
+    where
+        T: fmt::Display + Clone,
+    {
+        self.frames.push(Frame {
+            time: self.start.elapsed(),
+            cells: container
+                .get_buffer()
+                .iter()
+                .map(|(p, cell)| (p, cell.clone()))
+                .collect(),
+        });
+    }
+
+    /// Returns the frames recorded so far.
+    pub fn frames(&self) -> &[Frame<T>] {
+        &self.frames
+    }
+
+    /// Stops recording, returning the frames.
+    pub fn into_frames(self) -> Vec<Frame<T>> {
+        self.frames
+    }
+
+    /// Saves the frames recorded so far to a file, with each cell written as it is
+    /// displayed. The file can be played back with [`Player::load`].
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()>
+    where
+        T: fmt::Display,
+    {
+        let mut out = BufWriter::new(File::create(path)?);
+        for frame in self.frames.iter() {
+            writeln!(out, "@{} {}", frame.time.as_micros(), frame.cells.len())?;
+            for (p, cell) in frame.cells.iter() {
+                let text = cell.to_string().replace('\\', "\\\\").replace('\n', "\\n");
+                writeln!(out, "{} {} {text}", p.x, p.y)?;
+            }
+        }
+        out.flush()
+    }
+}
+
+/// Plays back recorded frames through a backend.
+#[derive(Clone, Debug)]
+pub struct Player<T> {
+    frames: Vec<Frame<T>>,
+    // Index of the next frame to show.
+    next: usize,
+}
+
+impl Player<String> {
+    /// Reads frames saved by [`Recorder::save`], with each cell as it was displayed.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Reads frames from the text of a recording.
+    fn parse(text: &str) -> io::Result<Self> {
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, "invalid recording");
+        let mut lines = text.lines();
+        let mut frames = Vec::new();
+
+        while let Some(header) = lines.next() {
+            let (time, count) = header
+                .strip_prefix('@')
+                .and_then(|h| h.split_once(' '))
+                .ok_or_else(invalid)?;
+            let time = Duration::from_micros(time.parse().map_err(|_| invalid())?);
+            let count: usize = count.parse().map_err(|_| invalid())?;
+
+            // The count comes from the file, so the map grows as cells are read
+            // rather than being sized for it up front.
+            let mut cells = HashMap::new();
+            for _ in 0..count {
+                let mut parts = lines.next().ok_or_else(invalid)?.splitn(3, ' ');
+                let mut num = || {
+                    parts
+                        .next()
+                        .and_then(|n| n.parse().ok())
+                        .ok_or_else(invalid)
+                };
+                let p = Point::new(num()?, num()?);
+                let text = parts.next().unwrap_or_default();
+                if cells.insert(p, unescape(text)).is_some() {
+                    return Err(invalid());
+                }
+            }
+            frames.push(Frame { time, cells });
+        }
+
+        Ok(Self::new(frames))
+    }
+}
+
+/// Undoes the escaping of backslashes and line breaks done when saving.
+pub(crate) fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(ch) = chars.next() {
+        match (ch, chars.clone().next()) {
+            ('\\', Some('n')) => {
+                chars.next();
+                out.push('\n');
+            }
+            ('\\', Some('\\')) => {
+                chars.next();
+                out.push('\\');
+            }
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
+impl<T: fmt::Display> Player<T> {
+    /// Create a player starting at the first of the given frames.
+    pub fn new(frames: Vec<Frame<T>>) -> Self {
+        Self { frames, next: 0 }
+    }
+
+    /// Returns every frame.
+    pub fn frames(&self) -> &[Frame<T>] {
+        &self.frames
+    }
+
+    /// Returns the index of the next frame to be shown.
+    pub fn position(&self) -> usize {
+        self.next
+    }
+
+    /// Sets the next frame to be shown.
+    pub fn seek(&mut self, idx: usize) {
+        self.next = idx.min(self.frames.len());
+    }
+
+    /// Clears the backend and shows the frame with the given index within `wid` by
+    /// `hgt`, making the one after it next.
+    pub fn show(
+        &mut self,
+        idx: usize,
+        backend: &mut dyn Backend,
+        wid: u16,
+        hgt: u16,
+    ) -> io::Result<()> {
+        let Some(frame) = self.frames.get(idx) else {
+            return Ok(());
+        };
+        backend.clear()?;
+        for (&p, cell) in frame.cells.iter() {
+            if in_bounds(p, wid, hgt) {
+                backend.write_cell(p, Some(cell))?;
+            }
+        }
+        self.next = idx + 1;
+        backend.flush()
+    }
+
+    /// Shows the next frame by writing only what changed since the one before it,
+    /// exactly as presenting the container did. Returns false if there are no frames
+    /// left.
+    pub fn step(&mut self, backend: &mut dyn Backend, wid: u16, hgt: u16) -> io::Result<bool>
+    where
+        T: PartialEq,
+    {
+        let Some(frame) = self.frames.get(self.next) else {
+            return Ok(false);
+        };
+        let Some(prev) = self.next.checked_sub(1).and_then(|i| self.frames.get(i)) else {
+            self.show(self.next, backend, wid, hgt)?;
+            return Ok(true);
+        };
+
+        for (&p, cell) in frame.cells.iter() {
+            if in_bounds(p, wid, hgt) && prev.cells.get(&p) != Some(cell) {
+                backend.write_cell(p, Some(cell))?;
+            }
+        }
+        for &p in prev.cells.keys() {
+            if in_bounds(p, wid, hgt) && !frame.cells.contains_key(&p) {
+                backend.write_cell(p, None)?;
+            }
+        }
+
+        self.next += 1;
+        backend.flush()?;
+        Ok(true)
+    }
+
+    /// Shows every frame left, waiting between them as long as was waited while
+    /// recording.
+    pub fn play(&mut self, backend: &mut dyn Backend, wid: u16, hgt: u16) -> io::Result<()>
+    where
+        T: PartialEq,
+    {
+        let mut last = None;
+        while let Some(frame) = self.frames.get(self.next) {
+            if let Some(last) = last {
+                thread::sleep(frame.time.saturating_sub(last));
+            }
+            last = Some(frame.time);
+            self.step(backend, wid, hgt)?;
+        }
+        Ok(())
+    }
+}
+
+fn in_bounds(p: Point, wid: u16, hgt: u16) -> bool {
+    p.x >= 0 && p.y >= 0 && p.x < wid as Coord && p.y < hgt as Coord
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A screen that remembers what was written to it.
+    #[derive(Default)]
+    struct Screen {
+        cells: HashMap<Point, String>,
+        events: Vec<Event>,
+    }
+
+    impl Backend for Screen {
+        fn size(&mut self) -> io::Result<(u16, u16)> {
+            Ok((4, 2))
+        }
+
+        fn read_event(&mut self, _timeout: Duration) -> io::Result<Option<Event>> {
+            Ok(self.events.pop())
+        }
+
+        fn write_cell(&mut self, p: Point, cell: Option<&dyn fmt::Display>) -> io::Result<()> {
+            match cell {
+                Some(cell) => self.cells.insert(p, cell.to_string()),
+                None => self.cells.remove(&p),
+            };
+            Ok(())
+        }
+
+        fn clear(&mut self) -> io::Result<()> {
+            self.cells.clear();
+            Ok(())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn cast_of_output_and_resizes() {
+        let screen = Screen {
+            events: vec![Event::Resize(8, 3)],
+            ..Default::default()
+        };
+        let mut cast = CastRecorder::new(screen, Vec::new()).unwrap();
+        cast.write_cell(Point::new(1, 0), Some(&"\"")).unwrap();
+        cast.flush().unwrap();
+        cast.flush().unwrap();
+        assert_eq!(
+            cast.read_event(Duration::ZERO).unwrap(),
+            Some(Event::Resize(8, 3))
+        );
+        cast.hide_cursor().unwrap();
+        let (screen, out) = cast.finish().unwrap();
+
+        assert_eq!(screen.cells[&Point::new(1, 0)], "\"");
+        let lines: Vec<String> = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| match line.split_once(", ") {
+                // Leave out the time, which differs between runs.
+                Some((_, rest)) if line.starts_with('[') => rest.to_string(),
+                _ => line.to_string(),
+            })
+            .collect();
+        assert!(lines[0].starts_with("{\"version\": 2, \"width\": 4, \"height\": 2, "));
+        assert_eq!(
+            lines[1..],
+            [
+                "\"o\", \"\\u001b[1;2H\\\"\"]",
+                "\"r\", \"8x3\"]",
+                "\"o\", \"\\u001b[?25l\"]",
+            ]
+        );
+    }
+
+    #[test]
+    fn save_then_load() {
+        let mut recorder = Recorder::<String>::new();
+        for (micros, cells) in [(0, vec![]), (1500, vec![((0, 0), "a"), ((3, 1), "\\\n ")])] {
+            recorder.frames.push(Frame {
+                time: Duration::from_micros(micros),
+                cells: cells
+                    .into_iter()
+                    .map(|((x, y), text)| (Point::new(x, y), text.to_string()))
+                    .collect(),
+            });
+        }
+        let path = std::env::temp_dir().join(format!("windowed-record-{}", std::process::id()));
+        recorder.save(&path).unwrap();
+        let loaded = Player::load(&path);
+        let _ = fs::remove_file(&path);
+        assert_eq!(loaded.unwrap().frames(), recorder.frames());
+    }
+
+    #[test]
+    fn corrupt_recordings_are_invalid() {
+        for text in [
+            "0 0",
+            "@0 18446744073709551615\n",
+            "@0 2\n0 0 a\n",
+            "@0 2\n0 0 a\n0 0 b\n",
+            "@0 1\nx 0 a\n",
+        ] {
+            let err = Player::parse(text).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData, "{text:?}");
+        }
+    }
+}
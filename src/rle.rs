@@ -0,0 +1,217 @@
+//! Frames stored as runs of identical cells, for keeping or sending them cheaply.
+
+use crate::Container;
+use crate::backend::Backend;
+use crate::geometry::{Coord, Point};
+use crate::record::unescape;
+use std::fmt::{self, Write as _};
+use std::io;
+
+/// A number of cells in a row holding the same value, or nothing.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Run<T> {
+    pub value: Option<T>,
+    pub len: usize,
+}
+
+/// What a container showed over an area starting at (0, 0), as runs of identical
+/// cells read row by row. Runs carry on from the end of one row to the start of the
+/// next. Created with [`Container::to_rle_frame`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RleFrame<T> {
+    wid: u16,
+    hgt: u16,
+    runs: Vec<Run<T>>,
+}
+
+impl<T> RleFrame<T> {
+    /// Returns the width of the frame.
+    pub fn width(&self) -> u16 {
+        self.wid
+    }
+
+    /// Returns the height of the frame.
+    pub fn height(&self) -> u16 {
+        self.hgt
+    }
+
+    /// Returns the runs making up the frame.
+    pub fn runs(&self) -> &[Run<T>] {
+        &self.runs
+    }
+
+    /// Returns an iterator over every position of the frame, row by row, along with
+    /// its value if it has one.
+    pub fn cells(&self) -> impl Iterator<Item = (Point, Option<&T>)> + '_ {
+        let wid = (self.wid as Coord).max(1);
+        self.runs
+            .iter()
+            .flat_map(|run| std::iter::repeat_n(run.value.as_ref(), run.len))
+            .enumerate()
+            .map(move |(idx, value)| {
+                let idx = idx as Coord;
+                (Point::new(idx % wid, idx / wid), value)
+            })
+    }
+
+    /// Returns the value at the given position, if there is one. This goes through
+    /// the runs before it, so use [`cells`](Self::cells) to read the whole frame.
+    pub fn get(&self, p: Point) -> Option<&T> {
+        if p.x < 0 || p.y < 0 || p.x >= self.wid as Coord || p.y >= self.hgt as Coord {
+            return None;
+        }
+        let mut idx = (p.y * self.wid as Coord + p.x) as usize;
+        for run in self.runs.iter() {
+            if idx < run.len {
+                return run.value.as_ref();
+            }
+            idx -= run.len;
+        }
+        None
+    }
+
+    /// Clears the backend and shows the frame on it.
+    pub fn present(&self, backend: &mut dyn Backend) -> io::Result<()>
+    where
+        T: fmt::Display,
+    {
+        backend.clear()?;
+        for (p, value) in self.cells() {
+            if let Some(value) = value {
+                backend.write_cell(p, Some(value))?;
+            }
+        }
+        backend.flush()
+    }
+
+    /// Writes the frame as text, with each cell written as it is displayed. The text
+    /// can be read back with [`decode`](RleFrame::decode).
+    pub fn encode(&self) -> String
+    where
+        T: fmt::Display,
+    {
+        let mut out = format!("{} {} {}\n", self.wid, self.hgt, self.runs.len());
+        for run in self.runs.iter() {
+            let _ = match &run.value {
+                Some(value) => {
+                    let text = value.to_string().replace('\\', "\\\\").replace('\n', "\\n");
+                    writeln!(out, "{} {text}", run.len)
+                }
+                None => writeln!(out, "{}", run.len),
+            };
+        }
+        out
+    }
+}
+
+impl RleFrame<String> {
+    /// Reads a frame written by [`encode`](RleFrame::encode), with each cell as it
+    /// was displayed.
+    pub fn decode(text: &str) -> io::Result<Self> {
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, "invalid frame");
+        let mut lines = text.lines();
+        let mut header = lines
+            .next()
+            .ok_or_else(invalid)?
+            .split(' ')
+            .map(|n| n.parse::<usize>().map_err(|_| invalid()));
+        let mut num = || header.next().ok_or_else(invalid)?;
+        let wid = u16::try_from(num()?).map_err(|_| invalid())?;
+        let hgt = u16::try_from(num()?).map_err(|_| invalid())?;
+        let count = num()?;
+
+        // The count is only trusted as far as the runs it claims could fit.
+        let area = wid as usize * hgt as usize;
+        let mut runs = Vec::with_capacity(count.min(area));
+        let mut total = 0usize;
+        for _ in 0..count {
+            let line = lines.next().ok_or_else(invalid)?;
+            let (len, value) = match line.split_once(' ') {
+                Some((len, text)) => (len, Some(unescape(text))),
+                None => (line, None),
+            };
+            let len = len.parse().map_err(|_| invalid())?;
+            total = total
+                .checked_add(len)
+                .filter(|&total| total <= area)
+                .ok_or_else(invalid)?;
+            runs.push(Run { value, len });
+        }
+
+        if total != area {
+            return Err(invalid());
+        }
+        Ok(Self { wid, hgt, runs })
+    }
+}
+
+impl<T: fmt::Display + Clone + PartialEq> Container<T> {
+    /// Returns what the container shows from (0, 0) to (wid, hgt) as of the last
+    /// refresh, run-length encoded. Frames compare cheaply with each other and take
+    /// little space when most of the screen is blank or the same.
+    pub fn to_rle_frame(&self, wid: u16, hgt: u16) -> RleFrame<T> {
+        let mut runs: Vec<Run<T>> = Vec::new();
+        for y in 0..hgt as Coord {
+            for x in 0..wid as Coord {
+                let value = self.cell_at(Point::new(x, y));
+                match runs.last_mut() {
+                    Some(run) if run.value.as_ref() == value => run.len += 1,
+                    _ => runs.push(Run {
+                        value: value.cloned(),
+                        len: 1,
+                    }),
+                }
+            }
+        }
+        RleFrame { wid, hgt, runs }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_then_decode() {
+        let frame = RleFrame {
+            wid: 3,
+            hgt: 2,
+            runs: vec![
+                Run {
+                    value: Some(String::from("a")),
+                    len: 2,
+                },
+                Run {
+                    value: None,
+                    len: 3,
+                },
+                Run {
+                    value: Some(String::from("\\\n")),
+                    len: 1,
+                },
+            ],
+        };
+        let decoded = RleFrame::decode(&frame.encode()).unwrap();
+        assert_eq!(decoded, frame);
+        assert_eq!(decoded.get(Point::new(1, 1)), None);
+        assert_eq!(
+            decoded.get(Point::new(2, 1)).map(String::as_str),
+            Some("\\\n")
+        );
+    }
+
+    #[test]
+    fn corrupt_headers_are_invalid() {
+        for text in [
+            "",
+            "1 1",
+            "1 1 1152921504606846975\n",
+            "2 1 2\n18446744073709551615\n1\n",
+            "2 1 1\n3 a\n",
+            "2 1 2\n1 a\n",
+        ] {
+            let err = RleFrame::decode(text).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData, "{text:?}");
+        }
+    }
+}
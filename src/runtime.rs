@@ -0,0 +1,649 @@
+//! The event loop that drives a container.
+
+use crate::backend::Backend;
+use crate::event::{Event, MouseMode, WindowEvent};
+use crate::geometry::Coord;
+use crate::kitty::{self, Placement};
+use crate::{Container, Window, sixel};
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::fmt;
+use std::io;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// What the event loop should do after handling an event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Control {
+    /// Keep running.
+    Continue,
+    /// Stop running and return.
+    Quit,
+}
+
+/// Decides when an event loop presents a frame and produces a [`Event::Tick`].
+///
+/// Presentation is capped at `max_fps` frames per second, so a burst of events is
+/// shown as one frame rather than one per event. Frames are spaced from when each
+/// one started, so the time spent rendering comes out of the frame budget and only
+/// the remainder is slept. This can also pace a loop that refreshes and draws a
+/// container itself, using [`wait`](Self::wait) and [`presented`](Self::presented).
+#[derive(Clone, Debug)]
+pub struct FramePacer {
+    /// Most frames presented per second, or None to present after every event.
+    pub max_fps: Option<u32>,
+    /// How often a tick is produced.
+    pub tick_rate: Duration,
+    // When the latest frame started, and when the one being rendered started.
+    last_frame: Option<Instant>,
+    rendering: Option<Instant>,
+    render_time: Duration,
+    last_tick: Instant,
+}
+
+impl FramePacer {
+    /// Create a pacer that has yet to present a frame.
+    pub fn new(max_fps: Option<u32>, tick_rate: Duration) -> Self {
+        Self {
+            max_fps,
+            tick_rate,
+            last_frame: None,
+            rendering: None,
+            render_time: Duration::ZERO,
+            last_tick: Instant::now(),
+        }
+    }
+
+    /// Returns the shortest time allowed between frames.
+    pub fn frame_time(&self) -> Duration {
+        match self.max_fps {
+            Some(fps) if fps > 0 => Duration::from_secs(1) / fps,
+            _ => Duration::ZERO,
+        }
+    }
+
+    /// Returns how long until the next frame may be started.
+    pub fn until_frame(&self) -> Duration {
+        self.last_frame.map_or(Duration::ZERO, |last| {
+            self.frame_time().saturating_sub(last.elapsed())
+        })
+    }
+
+    /// Returns how long until the next tick is due.
+    pub fn until_tick(&self) -> Duration {
+        self.tick_rate.saturating_sub(self.last_tick.elapsed())
+    }
+
+    /// Returns true if a frame may be started now.
+    pub fn frame_due(&self) -> bool {
+        self.until_frame().is_zero()
+    }
+
+    /// Records that rendering a frame is starting.
+    pub fn begin_frame(&mut self) {
+        let now = Instant::now();
+        self.last_frame = Some(now);
+        self.rendering = Some(now);
+    }
+
+    /// Records that a frame was just presented, measuring how long it took if it was
+    /// started with [`begin_frame`](Self::begin_frame).
+    pub fn presented(&mut self) {
+        match self.rendering.take() {
+            Some(start) => {
+                let took = start.elapsed();
+                // Smooth out the odd slow frame.
+                self.render_time = if self.render_time.is_zero() {
+                    took
+                } else {
+                    (self.render_time * 7 + took) / 8
+                };
+            }
+            None => self.last_frame = Some(Instant::now()),
+        }
+    }
+
+    /// Returns the average time recent frames took to render and present.
+    pub fn render_time(&self) -> Duration {
+        self.render_time
+    }
+
+    /// Returns true if frames are taking longer to render than the frame budget.
+    pub fn over_budget(&self) -> bool {
+        self.render_time > self.frame_time()
+    }
+
+    /// Returns true if a tick is due, starting the wait for the next one if so.
+    pub fn tick(&mut self) -> bool {
+        if self.until_tick().is_zero() {
+            self.last_tick = Instant::now();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns how long an event loop may wait for input before it has something to
+    /// do, which is the next tick or, if a frame is waiting to be presented, the next
+    /// frame.
+    pub fn timeout(&self, pending: bool) -> Duration {
+        if pending {
+            self.until_tick().min(self.until_frame())
+        } else {
+            self.until_tick()
+        }
+    }
+
+    /// Sleeps for what is left of the current frame's budget, then starts the next
+    /// frame.
+    pub fn wait(&mut self) {
+        thread::sleep(self.until_frame());
+        self.begin_frame();
+    }
+}
+
+/// How long [`Container::run`] waits between checks for refreshes requested by
+/// other threads when presentation isn't capped.
+const REQUEST_POLL: Duration = Duration::from_millis(16);
+
+/// Requests a refresh of a container from anywhere, including other threads.
+///
+/// Any number of requests made before the container's event loop gets to them are
+/// handled with a single refresh.
+#[derive(Clone, Debug)]
+pub struct RefreshHandle(Arc<AtomicBool>);
+
+impl RefreshHandle {
+    /// Asks for the container to be refreshed and presented with the next frame.
+    pub fn request(&self) {
+        self.0.store(true, Ordering::Release);
+    }
+}
+
+/// Whether a container has been asked to refresh. Each copy of a container has its
+/// own.
+#[derive(Debug, Default)]
+pub(crate) struct RefreshRequest(Arc<AtomicBool>);
+
+impl Clone for RefreshRequest {
+    fn clone(&self) -> Self {
+        Self(Arc::new(AtomicBool::new(self.0.load(Ordering::Acquire))))
+    }
+}
+
+impl RefreshRequest {
+    /// Returns true if handles to the request have been given out.
+    fn shared(&self) -> bool {
+        Arc::strong_count(&self.0) > 1
+    }
+}
+
+/// Identifies a timer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TimerId(u64);
+
+#[derive(Clone, Debug)]
+struct Timer {
+    id: TimerId,
+    due: Instant,
+    // Time between firings, if the timer repeats.
+    period: Option<Duration>,
+    event: Event,
+}
+
+/// Events scheduled to be given to the event loop of a container later, either once
+/// or repeatedly.
+///
+/// Repeating timers are scheduled from when they were due rather than when they
+/// fired, so they don't drift however long handling each event takes. Firings
+/// missed because the loop was busy are not made up for.
+#[derive(Clone, Debug, Default)]
+pub struct Timers {
+    timers: Vec<Timer>,
+    next_id: u64,
+}
+
+impl Timers {
+    /// Gives `event` to the event loop once `delay` has passed.
+    pub fn after(&mut self, delay: Duration, event: Event) -> TimerId {
+        self.add(delay, None, event)
+    }
+
+    /// Gives `event` to the event loop every time `period` passes, starting one
+    /// period from now.
+    pub fn every(&mut self, period: Duration, event: Event) -> TimerId {
+        // A zero period would fire forever without letting the loop do anything else.
+        let period = period.max(Duration::from_millis(1));
+        self.add(period, Some(period), event)
+    }
+
+    fn add(&mut self, delay: Duration, period: Option<Duration>, event: Event) -> TimerId {
+        let id = TimerId(self.next_id);
+        self.next_id += 1;
+        self.timers.push(Timer {
+            id,
+            due: Instant::now() + delay,
+            period,
+            event,
+        });
+        id
+    }
+
+    /// Stops a timer. Returns false if there was no such timer, such as one that
+    /// already fired and doesn't repeat.
+    pub fn cancel(&mut self, id: TimerId) -> bool {
+        let len = self.timers.len();
+        self.timers.retain(|t| t.id != id);
+        self.timers.len() != len
+    }
+
+    /// Returns true if the timer with the given id is still waiting to fire.
+    pub fn is_scheduled(&self, id: TimerId) -> bool {
+        self.timers.iter().any(|t| t.id == id)
+    }
+
+    /// Stops every timer.
+    pub fn clear(&mut self) {
+        self.timers.clear();
+    }
+
+    /// Returns the number of timers waiting to fire.
+    pub fn len(&self) -> usize {
+        self.timers.len()
+    }
+
+    /// Returns true if no timers are waiting to fire.
+    pub fn is_empty(&self) -> bool {
+        self.timers.is_empty()
+    }
+
+    /// Returns when the next timer is due, if there are any.
+    pub fn next_due(&self) -> Option<Instant> {
+        self.timers.iter().map(|t| t.due).min()
+    }
+
+    /// Returns the events of every timer due by `now`, in the order they were due.
+    /// Timers that don't repeat are removed, and the rest scheduled again.
+    pub fn fire(&mut self, now: Instant) -> Vec<Event> {
+        let mut due: Vec<(Instant, TimerId, Event)> = Vec::new();
+        self.timers.retain_mut(|t| {
+            if t.due > now {
+                return true;
+            }
+            due.push((t.due, t.id, t.event.clone()));
+            match t.period {
+                Some(period) => {
+                    while t.due <= now {
+                        t.due += period;
+                    }
+                    true
+                }
+                None => false,
+            }
+        });
+        due.sort_by_key(|&(at, id, _)| (at, id.0));
+        due.into_iter().map(|(_, _, event)| event).collect()
+    }
+}
+
+impl<T: fmt::Display> Container<T> {
+    /// Asks for the container to be refreshed and presented with the next frame of
+    /// [`run`](Self::run). Requests made before then are coalesced into one refresh.
+    pub fn request_refresh(&self) {
+        self.refresh_request.0.store(true, Ordering::Release);
+    }
+
+    /// Returns a handle that other threads can use to request a refresh. While any
+    /// handles exist, [`run`](Self::run) checks for requests at least once a frame.
+    pub fn refresh_handle(&self) -> RefreshHandle {
+        RefreshHandle(Arc::clone(&self.refresh_request.0))
+    }
+
+    /// Returns true if a refresh has been requested since the last one.
+    pub fn refresh_requested(&self) -> bool {
+        self.refresh_request.0.load(Ordering::Acquire)
+    }
+
+    /// Clears any requested refresh, returning true if there was one.
+    pub(crate) fn take_refresh_request(&self) -> bool {
+        self.refresh_request.0.swap(false, Ordering::AcqRel)
+    }
+
+    /// Refreshes the container if a refresh has been requested, for loops that don't
+    /// use [`run`](Self::run). Returns true if it was refreshed.
+    pub fn refresh_if_requested(&mut self) -> bool
+    where
+        T: Clone + PartialEq,
+    {
+        let requested = self.take_refresh_request();
+        if requested {
+            self.update_bindings();
+            self.refresh();
+        }
+        requested
+    }
+
+    /// Writes every position changed by the last refresh that lies within `wid` by
+    /// `hgt` to the backend.
+    pub fn present(&mut self, backend: &mut dyn Backend, wid: u16, hgt: u16) -> io::Result<()> {
+        let start = self.profile_start();
+        self.write_changes(backend, wid, hgt)?;
+        self.profile_record(start, |profile, time| profile.present = time);
+        Ok(())
+    }
+
+    fn write_changes(&mut self, backend: &mut dyn Backend, wid: u16, hgt: u16) -> io::Result<()> {
+        for &p in self.changed() {
+            if p.x < 0 || p.y < 0 || p.x >= wid as Coord || p.y >= hgt as Coord {
+                continue;
+            }
+            backend.write_cell(p, self.get_buffer().get(&p).map(|c| c as &dyn fmt::Display))?;
+        }
+        // Cells drawn over an image erase it, so draw it again.
+        self.present_pixels(backend, |win| {
+            self.changed().iter().any(|&p| win.contains(p))
+        })?;
+        self.place_images(backend)?;
+        self.place_cursor(backend)?;
+        backend.flush()
+    }
+
+    /// Clears the backend and writes the whole buffer within `wid` by `hgt` to it.
+    pub fn present_all(&mut self, backend: &mut dyn Backend, wid: u16, hgt: u16) -> io::Result<()> {
+        let start = self.profile_start();
+        self.write_all(backend, wid, hgt)?;
+        self.profile_record(start, |profile, time| profile.present = time);
+        Ok(())
+    }
+
+    fn write_all(&mut self, backend: &mut dyn Backend, wid: u16, hgt: u16) -> io::Result<()> {
+        // Start over with images too, in case clearing removed them.
+        for image in self.images.transmitted.drain() {
+            backend.delete_image(image, None)?;
+        }
+        self.images.placed.clear();
+        backend.clear()?;
+        for (p, c) in self.get_buffer().iter() {
+            if p.x < 0 || p.y < 0 || p.x >= wid as Coord || p.y >= hgt as Coord {
+                continue;
+            }
+            backend.write_cell(p, Some(c))?;
+        }
+        self.present_pixels(backend, |_| true)?;
+        self.place_images(backend)?;
+        self.place_cursor(backend)?;
+        backend.flush()
+    }
+
+    /// Shows the cursor where the focused window wants it, or hides it.
+    fn place_cursor(&mut self, backend: &mut dyn Backend) -> io::Result<()> {
+        self.placed_cursor = self.cursor_position();
+        backend.set_cursor(self.placed_cursor)
+    }
+
+    /// Writes the pixels of each shown window picked by `redraw` as sixel graphics, if
+    /// the backend supports them.
+    fn present_pixels(
+        &self,
+        backend: &mut dyn Backend,
+        redraw: impl Fn(&Window<T>) -> bool,
+    ) -> io::Result<()> {
+        if !backend.supports_sixel() || backend.supports_kitty_graphics() {
+            return Ok(());
+        }
+        for win in self.windows.iter().filter(|w| !w.hidden) {
+            if let Some(pixels) = &win.pixels
+                && redraw(win)
+            {
+                backend.write_sixel(win.top_left, &sixel::encode(pixels))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Brings the images placed with the kitty graphics protocol in line with the
+    /// pixels of each shown window, if the backend supports it. Images are only sent
+    /// once, and are moved, replaced or deleted along with their windows.
+    fn place_images(&mut self, backend: &mut dyn Backend) -> io::Result<()> {
+        if !backend.supports_kitty_graphics() {
+            return Ok(());
+        }
+
+        // Each window's image is placed with an id and depth given by its index.
+        let mut wanted = HashMap::new();
+        for (idx, win) in self.windows.iter().enumerate() {
+            if let Some(pixels) = &win.pixels
+                && !win.hidden
+            {
+                let placement = Placement {
+                    image: kitty::image_id(pixels),
+                    pos: win.top_left,
+                    z: idx as i32,
+                };
+                if self.images.transmitted.insert(placement.image) {
+                    backend.transmit_image(placement.image, pixels)?;
+                }
+                wanted.insert(idx as u32 + 1, placement);
+            }
+        }
+
+        for (&id, old) in self.images.placed.iter() {
+            if wanted.get(&id).is_none_or(|new| new.image != old.image) {
+                backend.delete_image(old.image, Some(id))?;
+            }
+        }
+        for (&id, new) in wanted.iter() {
+            if self.images.placed.get(&id) != Some(new) {
+                backend.place_image(new.image, id, new.pos, new.z)?;
+            }
+        }
+
+        // Free images no longer shown anywhere.
+        let shown: Vec<u32> = wanted.values().map(|p| p.image).collect();
+        for &image in self.images.transmitted.iter() {
+            if !shown.contains(&image) {
+                backend.delete_image(image, None)?;
+            }
+        }
+        self.images
+            .transmitted
+            .retain(|image| shown.contains(image));
+        self.images.placed = wanted;
+
+        Ok(())
+    }
+
+    /// Runs the container until the callback returns [`Control::Quit`].
+    ///
+    /// Each event read from the backend is first handled by the container (so mouse
+    /// events drag, resize and scroll windows), then given to the callback. Anything
+    /// the container did in response is given to the callback as an
+    /// [`Event::Window`], followed by any events [`post`](Self::post)ed while handling
+    /// it. A [`Event::Tick`] is given whenever `tick_rate` passes without one, and
+    /// published messages are dispatched before it is handled. The events of
+    /// [`timers`](Self::timers) are given as they become due, and notifications are
+    /// removed as soon as their time is up. After each event bound windows are
+    /// updated, then the container is refreshed and the changes presented to the
+    /// backend, at most `max_fps` times a second. Events arriving faster than that are
+    /// handled together in one frame, as are any refreshes
+    /// [requested](Self::request_refresh) meanwhile.
+    pub fn run<B, F>(&mut self, backend: &mut B, mut f: F) -> io::Result<()>
+    where
+        B: Backend,
+        F: FnMut(Event, &mut Self) -> Control,
+        T: Clone + PartialEq + Default,
+    {
+        let mut size = backend.size()?;
+        self.start(backend, size)?;
+
+        let mut pacer = FramePacer::new(self.max_fps, self.tick_rate);
+        pacer.presented();
+        // Whether events have been handled since the last frame, and whether the
+        // next frame must redraw everything.
+        let mut pending = false;
+        let mut full = false;
+
+        loop {
+            pacer.max_fps = self.max_fps;
+            pacer.tick_rate = self.tick_rate;
+            let (mut due, expired) = self.due_events();
+            pending |= expired || self.take_refresh_request();
+            if pacer.tick() {
+                due.insert(0, Event::Tick);
+            }
+            if due.is_empty() {
+                let mut timeout = self.until_due(pacer.timeout(pending));
+                if self.refresh_request.shared() {
+                    timeout = timeout.min(pacer.frame_time().max(REQUEST_POLL));
+                }
+                due.extend(backend.read_event(timeout)?);
+            }
+
+            for event in due {
+                let (events, resized) = self.handle_event(event, &mut size);
+                if self.deliver(events, &mut f) == Control::Quit {
+                    return Ok(());
+                }
+                pending = true;
+                full |= resized;
+            }
+            if pending && pacer.frame_due() {
+                pacer.begin_frame();
+                self.finish(backend, size, full)?;
+                pacer.presented();
+                pending = false;
+                full = false;
+            }
+        }
+    }
+
+    /// Draws everything to the backend for the first time before an event loop.
+    pub(crate) fn start(&mut self, backend: &mut dyn Backend, size: (u16, u16)) -> io::Result<()>
+    where
+        T: Clone + PartialEq,
+    {
+        self.set_screen_size(size.0, size.1);
+        self.applied_mouse = None;
+        self.apply_mouse(backend)?;
+        self.update_bindings();
+        self.refresh();
+        self.present_all(backend, size.0, size.1)
+    }
+
+    /// Tells the backend which mouse events to report if [`mouse`](Self::mouse) has
+    /// changed since it was last told. A drag in progress is dropped if drags are no
+    /// longer reported, as its end never would be.
+    pub(crate) fn apply_mouse(&mut self, backend: &mut dyn Backend) -> io::Result<()> {
+        if self.applied_mouse == Some(self.mouse) {
+            return Ok(());
+        }
+        match self.mouse {
+            Some(mode) => backend.enable_mouse(mode)?,
+            None => backend.disable_mouse()?,
+        }
+        if !self.mouse.is_some_and(MouseMode::reports_drag) {
+            self.drag = None;
+        }
+        self.applied_mouse = Some(self.mouse);
+        Ok(())
+    }
+
+    /// Removes expired notifications and takes the events of the timers that are due,
+    /// for an event loop. Returns the events, and whether any notifications were
+    /// removed.
+    pub(crate) fn due_events(&mut self) -> (Vec<Event>, bool) {
+        let expired = self.expire_toasts() > 0;
+        (self.timers.fire(Instant::now()), expired)
+    }
+
+    /// Returns how long an event loop may wait for input before a timer or
+    /// notification is due, waiting no longer than `max`.
+    pub(crate) fn until_due(&self, max: Duration) -> Duration {
+        let due = self
+            .timers
+            .next_due()
+            .into_iter()
+            .chain(self.next_toast_expiry())
+            .min();
+        due.map_or(max, |due| {
+            max.min(due.saturating_duration_since(Instant::now()))
+        })
+    }
+
+    /// Lets the container handle an event read by an event loop, updating the size of
+    /// the screen if it was resized. Returns the events to give to the callback, and
+    /// whether the whole screen should be redrawn.
+    pub(crate) fn handle_event(
+        &mut self,
+        event: Event,
+        size: &mut (u16, u16),
+    ) -> (VecDeque<Event>, bool)
+    where
+        T: Clone + Default,
+    {
+        let mut full = false;
+        let mut events = VecDeque::from([event.clone()]);
+        match event {
+            Event::Resize(w, h) => {
+                *size = (w, h);
+                self.set_screen_size(w, h);
+                full = true;
+            }
+            Event::Mouse(ev) => events.extend(self.handle_mouse(ev).map(Event::Window)),
+            Event::Tick => {
+                self.dispatch_messages();
+            }
+            Event::Window(WindowEvent::FlashEnded { .. }) => self.end_flashes(),
+            _ => {}
+        }
+        (events, full)
+    }
+
+    /// Gives events to the callback of an event loop, each followed by any events
+    /// posted while handling it. With no events, any already posted are given.
+    pub(crate) fn deliver(
+        &mut self,
+        mut events: VecDeque<Event>,
+        f: &mut impl FnMut(Event, &mut Self) -> Control,
+    ) -> Control {
+        if events.is_empty() {
+            events.extend(self.posted.drain(..));
+        }
+        while let Some(ev) = events.pop_front() {
+            if f(ev, self) == Control::Quit {
+                return Control::Quit;
+            }
+            events.extend(self.posted.drain(..));
+        }
+        Control::Continue
+    }
+
+    /// Updates bound windows, refreshes and presents the changes after an event,
+    /// skipping presentation if there are none.
+    pub(crate) fn finish(
+        &mut self,
+        backend: &mut dyn Backend,
+        size: (u16, u16),
+        full: bool,
+    ) -> io::Result<()>
+    where
+        T: Clone + PartialEq,
+    {
+        self.apply_mouse(backend)?;
+        self.update_bindings();
+        self.refresh();
+        // Nothing needs presenting if nothing changed, unless images might have.
+        if full {
+            self.present_all(backend, size.0, size.1)
+        } else if !self.is_unchanged() || self.windows.iter().any(|w| w.pixels.is_some()) {
+            self.present(backend, size.0, size.1)
+        } else if self.cursor_position() != self.placed_cursor {
+            self.place_cursor(backend)?;
+            backend.flush()
+        } else {
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,272 @@
+//! Switching between whole screens of an app, with transitions between them.
+
+use crate::backend::Backend;
+use crate::geometry::{Coord, Point};
+use crate::{Container, Direction};
+use std::fmt;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+/// Identifies a scene by its index in a [`SceneManager`].
+pub type SceneId = usize;
+
+/// How one scene gives way to the next.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Transition {
+    /// The next scene replaces the last at once.
+    #[default]
+    Cut,
+    /// Both scenes move in the direction, with the next pushing the last off screen.
+    Slide(Direction),
+    /// An edge moves across the screen in the direction, showing the next scene
+    /// behind it.
+    Wipe(Direction),
+}
+
+impl Transition {
+    /// Returns whether the cell at a position shows the next scene, and where in that
+    /// scene, `done` cells through a transition on a `wid` by `hgt` screen.
+    fn locate(self, p: Point, wid: Coord, hgt: Coord, done: Coord) -> (bool, Point) {
+        match self {
+            Transition::Cut => (true, p),
+            Transition::Slide(dir) => {
+                let (dx, dy, extent) = match dir {
+                    Direction::Left => (-1, 0, wid),
+                    Direction::Right => (1, 0, wid),
+                    Direction::Up => (0, -1, hgt),
+                    Direction::Down => (0, 1, hgt),
+                };
+                let last = Point::new(p.x - dx * done, p.y - dy * done);
+                if last.x >= 0 && last.y >= 0 && last.x < wid && last.y < hgt {
+                    (false, last)
+                } else {
+                    let left = extent - done;
+                    (true, Point::new(p.x + dx * left, p.y + dy * left))
+                }
+            }
+            Transition::Wipe(dir) => {
+                let next = match dir {
+                    Direction::Left => p.x >= wid - done,
+                    Direction::Right => p.x < done,
+                    Direction::Up => p.y >= hgt - done,
+                    Direction::Down => p.y < done,
+                };
+                (next, p)
+            }
+        }
+    }
+
+    /// Returns how many cells the transition covers on a `wid` by `hgt` screen.
+    fn extent(self, wid: Coord, hgt: Coord) -> Coord {
+        match self {
+            Transition::Cut => 1,
+            Transition::Slide(Direction::Left | Direction::Right)
+            | Transition::Wipe(Direction::Left | Direction::Right) => wid,
+            Transition::Slide(Direction::Up | Direction::Down)
+            | Transition::Wipe(Direction::Up | Direction::Down) => hgt,
+        }
+    }
+}
+
+/// A transition being shown.
+#[derive(Clone, Copy, Debug)]
+struct Switch {
+    from: SceneId,
+    transition: Transition,
+    start: Instant,
+    duration: Duration,
+}
+
+/// Several containers, each a whole screen of an app such as a menu or a settings
+/// page, of which one is shown at a time.
+///
+/// Switching to another scene with [`switch`](Self::switch) can show a transition,
+/// during which the frames are made up of both scenes. Only the scene being shown is
+/// meant to be given events, and only the scenes in a transition need refreshing.
+#[derive(Debug)]
+pub struct SceneManager<T: fmt::Display> {
+    scenes: Vec<Container<T>>,
+    current: SceneId,
+    switch: Option<Switch>,
+    // Whether the next frame must be presented in full, as the backend holds a
+    // transition or another scene.
+    redraw: bool,
+}
+
+impl<T: fmt::Display> SceneManager<T> {
+    /// Create a manager showing the given scene, which gets id 0.
+    pub fn new(first: Container<T>) -> Self {
+        Self {
+            scenes: vec![first],
+            current: 0,
+            switch: None,
+            redraw: true,
+        }
+    }
+
+    /// Adds a scene without showing it, returning its id.
+    pub fn add(&mut self, scene: Container<T>) -> SceneId {
+        self.scenes.push(scene);
+        self.scenes.len() - 1
+    }
+
+    /// Returns a scene, if it exists.
+    pub fn get(&self, scene: SceneId) -> Option<&Container<T>> {
+        self.scenes.get(scene)
+    }
+
+    /// Returns a scene mutably, if it exists.
+    pub fn get_mut(&mut self, scene: SceneId) -> Option<&mut Container<T>> {
+        self.scenes.get_mut(scene)
+    }
+
+    /// Returns the id of the scene being shown, or being switched to.
+    pub fn current_id(&self) -> SceneId {
+        self.current
+    }
+
+    /// Returns the scene being shown, or being switched to.
+    pub fn current(&self) -> &Container<T> {
+        &self.scenes[self.current]
+    }
+
+    /// Returns the scene being shown, or being switched to, mutably, such as to
+    /// handle an event.
+    pub fn current_mut(&mut self) -> &mut Container<T> {
+        &mut self.scenes[self.current]
+    }
+
+    /// Starts showing another scene, moving to it over the given duration. Switching
+    /// during a transition starts a new one from the scene that was being switched
+    /// to. Switching to the scene shown or to one that doesn't exist does nothing.
+    pub fn switch(&mut self, scene: SceneId, transition: Transition, duration: Duration) {
+        if scene == self.current || scene >= self.scenes.len() {
+            return;
+        }
+        let from = std::mem::replace(&mut self.current, scene);
+        self.redraw = true;
+        self.switch = (transition != Transition::Cut && !duration.is_zero()).then(|| Switch {
+            from,
+            transition,
+            start: Instant::now(),
+            duration,
+        });
+    }
+
+    /// Returns true if a transition is being shown.
+    pub fn is_transitioning(&self) -> bool {
+        self.switch.is_some()
+    }
+
+    /// Returns how far through the transition being shown is, from 0 to 1, or None
+    /// if there isn't one.
+    pub fn progress(&self) -> Option<f32> {
+        self.switch.map(|switch| {
+            (switch.start.elapsed().as_secs_f32() / switch.duration.as_secs_f32()).min(1.0)
+        })
+    }
+
+    /// Ends the transition being shown, if it has run for its whole duration.
+    /// Returns true if one ended.
+    pub fn update(&mut self) -> bool {
+        let ended = self.progress().is_some_and(|progress| progress >= 1.0);
+        if ended {
+            self.switch = None;
+            self.redraw = true;
+        }
+        ended
+    }
+
+    /// Refreshes the scene shown, and during a transition the scene being switched
+    /// from.
+    pub fn refresh(&mut self)
+    where
+        T: Clone + PartialEq,
+    {
+        if let Some(switch) = self.switch {
+            self.scenes[switch.from].refresh();
+        }
+        self.scenes[self.current].refresh();
+    }
+
+    /// Returns the transition being shown and how many cells through it is, on a
+    /// `wid` by `hgt` screen.
+    fn frame(&self, wid: u16, hgt: u16) -> Option<(Switch, Coord)> {
+        let switch = self.switch?;
+        let extent = switch.transition.extent(wid as Coord, hgt as Coord);
+        let done = (self.progress().unwrap_or(1.0) * extent as f32).round() as Coord;
+        Some((switch, done))
+    }
+
+    /// Returns what is shown at a position of a `wid` by `hgt` screen in a frame.
+    fn shown(&self, frame: Option<(Switch, Coord)>, p: Point, wid: u16, hgt: u16) -> Option<&T> {
+        let Some((switch, done)) = frame else {
+            return self.current().cell_at(p);
+        };
+        match switch
+            .transition
+            .locate(p, wid as Coord, hgt as Coord, done)
+        {
+            (true, p) => self.current().cell_at(p),
+            (false, p) => self.scenes[switch.from].cell_at(p),
+        }
+    }
+
+    /// Returns what is shown at a position of a `wid` by `hgt` screen as of the last
+    /// refresh, made up of both scenes during a transition.
+    pub fn cell_at(&self, p: Point, wid: u16, hgt: u16) -> Option<&T> {
+        self.shown(self.frame(wid, hgt), p, wid, hgt)
+    }
+
+    /// Creates a string representation of what is shown from (0, 0) to (wid, hgt),
+    /// using the provided default for positions nothing is drawn at.
+    pub fn to_string_with_default(&self, wid: u16, hgt: u16, default: T) -> String {
+        let frame = self.frame(wid, hgt);
+        let mut out = String::new();
+        for y in 0..hgt as Coord {
+            for x in 0..wid as Coord {
+                let value = self.shown(frame, Point::new(x, y), wid, hgt);
+                out.push_str(&value.unwrap_or(&default).to_string());
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Draws what is shown to the screen, like
+    /// [`Container::draw_with_default`].
+    pub fn draw_with_default(&self, wid: u16, hgt: u16, default: T) {
+        #[cfg(any(unix, windows))]
+        let _ = crate::sys::enable_ansi();
+
+        let frame = self.to_string_with_default(wid, hgt, default);
+        let mut out = io::stdout().lock();
+        let _ = out.write_all(frame.as_bytes()).and_then(|()| out.flush());
+    }
+
+    /// Writes what is shown within `wid` by `hgt` to the backend. Outside of
+    /// transitions only what the last refresh changed is written, as with
+    /// [`Container::present`]. During a transition every cell is written, leaving out
+    /// images and the cursor.
+    pub fn present(&mut self, backend: &mut dyn Backend, wid: u16, hgt: u16) -> io::Result<()> {
+        let frame = self.frame(wid, hgt);
+        if frame.is_some() {
+            backend.set_cursor(None)?;
+            for y in 0..hgt as Coord {
+                for x in 0..wid as Coord {
+                    let p = Point::new(x, y);
+                    let value = self.shown(frame, p, wid, hgt);
+                    backend.write_cell(p, value.map(|c| c as &dyn fmt::Display))?;
+                }
+            }
+            return backend.flush();
+        }
+        let redraw = std::mem::take(&mut self.redraw);
+        let scene = &mut self.scenes[self.current];
+        if redraw {
+            scene.present_all(backend, wid, hgt)
+        } else {
+            scene.present(backend, wid, hgt)
+        }
+    }
+}
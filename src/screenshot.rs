@@ -0,0 +1,211 @@
+//! Saving what a container shows as a PNG image, drawn with a built in bitmap font.
+
+use crate::Container;
+use crate::composite::box_arms;
+use crate::export::colors;
+use crate::style::Cell;
+use std::io;
+use std::path::Path;
+
+/// Width of a cell in a screenshot, in pixels.
+const CELL_WID: usize = 8;
+/// Height of a cell in a screenshot, in pixels.
+const CELL_HGT: usize = 16;
+
+/// Glyphs for printable ASCII, eight rows of eight pixels each with the leftmost
+/// pixel in the lowest bit. Each row is drawn twice to fill a cell.
+const FONT: [[u8; 8]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+    [0x18, 0x3C, 0x3C, 0x18, 0x18, 0x00, 0x18, 0x00],
+    [0x36, 0x36, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+    [0x36, 0x36, 0x7F, 0x36, 0x7F, 0x36, 0x36, 0x00],
+    [0x0C, 0x3E, 0x03, 0x1E, 0x30, 0x1F, 0x0C, 0x00],
+    [0x00, 0x63, 0x33, 0x18, 0x0C, 0x66, 0x63, 0x00],
+    [0x1C, 0x36, 0x1C, 0x6E, 0x3B, 0x33, 0x6E, 0x00],
+    [0x06, 0x06, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00],
+    [0x18, 0x0C, 0x06, 0x06, 0x06, 0x0C, 0x18, 0x00],
+    [0x06, 0x0C, 0x18, 0x18, 0x18, 0x0C, 0x06, 0x00],
+    [0x00, 0x66, 0x3C, 0xFF, 0x3C, 0x66, 0x00, 0x00],
+    [0x00, 0x0C, 0x0C, 0x3F, 0x0C, 0x0C, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C, 0x06],
+    [0x00, 0x00, 0x00, 0x3F, 0x00, 0x00, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C, 0x00],
+    [0x60, 0x30, 0x18, 0x0C, 0x06, 0x03, 0x01, 0x00],
+    [0x3E, 0x63, 0x73, 0x7B, 0x6F, 0x67, 0x3E, 0x00],
+    [0x0C, 0x0E, 0x0C, 0x0C, 0x0C, 0x0C, 0x3F, 0x00],
+    [0x1E, 0x33, 0x30, 0x1C, 0x06, 0x33, 0x3F, 0x00],
+    [0x1E, 0x33, 0x30, 0x1C, 0x30, 0x33, 0x1E, 0x00],
+    [0x38, 0x3C, 0x36, 0x33, 0x7F, 0x30, 0x78, 0x00],
+    [0x3F, 0x03, 0x1F, 0x30, 0x30, 0x33, 0x1E, 0x00],
+    [0x1C, 0x06, 0x03, 0x1F, 0x33, 0x33, 0x1E, 0x00],
+    [0x3F, 0x33, 0x30, 0x18, 0x0C, 0x0C, 0x0C, 0x00],
+    [0x1E, 0x33, 0x33, 0x1E, 0x33, 0x33, 0x1E, 0x00],
+    [0x1E, 0x33, 0x33, 0x3E, 0x30, 0x18, 0x0E, 0x00],
+    [0x00, 0x0C, 0x0C, 0x00, 0x00, 0x0C, 0x0C, 0x00],
+    [0x00, 0x0C, 0x0C, 0x00, 0x00, 0x0C, 0x0C, 0x06],
+    [0x18, 0x0C, 0x06, 0x03, 0x06, 0x0C, 0x18, 0x00],
+    [0x00, 0x00, 0x3F, 0x00, 0x00, 0x3F, 0x00, 0x00],
+    [0x06, 0x0C, 0x18, 0x30, 0x18, 0x0C, 0x06, 0x00],
+    [0x1E, 0x33, 0x30, 0x18, 0x0C, 0x00, 0x0C, 0x00],
+    [0x3E, 0x63, 0x7B, 0x7B, 0x7B, 0x03, 0x1E, 0x00],
+    [0x0C, 0x1E, 0x33, 0x33, 0x3F, 0x33, 0x33, 0x00],
+    [0x3F, 0x66, 0x66, 0x3E, 0x66, 0x66, 0x3F, 0x00],
+    [0x3C, 0x66, 0x03, 0x03, 0x03, 0x66, 0x3C, 0x00],
+    [0x1F, 0x36, 0x66, 0x66, 0x66, 0x36, 0x1F, 0x00],
+    [0x7F, 0x46, 0x16, 0x1E, 0x16, 0x46, 0x7F, 0x00],
+    [0x7F, 0x46, 0x16, 0x1E, 0x16, 0x06, 0x0F, 0x00],
+    [0x3C, 0x66, 0x03, 0x03, 0x73, 0x66, 0x7C, 0x00],
+    [0x33, 0x33, 0x33, 0x3F, 0x33, 0x33, 0x33, 0x00],
+    [0x1E, 0x0C, 0x0C, 0x0C, 0x0C, 0x0C, 0x1E, 0x00],
+    [0x78, 0x30, 0x30, 0x30, 0x33, 0x33, 0x1E, 0x00],
+    [0x67, 0x66, 0x36, 0x1E, 0x36, 0x66, 0x67, 0x00],
+    [0x0F, 0x06, 0x06, 0x06, 0x46, 0x66, 0x7F, 0x00],
+    [0x63, 0x77, 0x7F, 0x7F, 0x6B, 0x63, 0x63, 0x00],
+    [0x63, 0x67, 0x6F, 0x7B, 0x73, 0x63, 0x63, 0x00],
+    [0x1C, 0x36, 0x63, 0x63, 0x63, 0x36, 0x1C, 0x00],
+    [0x3F, 0x66, 0x66, 0x3E, 0x06, 0x06, 0x0F, 0x00],
+    [0x1E, 0x33, 0x33, 0x33, 0x3B, 0x1E, 0x38, 0x00],
+    [0x3F, 0x66, 0x66, 0x3E, 0x36, 0x66, 0x67, 0x00],
+    [0x1E, 0x33, 0x07, 0x0E, 0x38, 0x33, 0x1E, 0x00],
+    [0x3F, 0x2D, 0x0C, 0x0C, 0x0C, 0x0C, 0x1E, 0x00],
+    [0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x3F, 0x00],
+    [0x33, 0x33, 0x33, 0x33, 0x33, 0x1E, 0x0C, 0x00],
+    [0x63, 0x63, 0x63, 0x6B, 0x7F, 0x77, 0x63, 0x00],
+    [0x63, 0x63, 0x36, 0x1C, 0x1C, 0x36, 0x63, 0x00],
+    [0x33, 0x33, 0x33, 0x1E, 0x0C, 0x0C, 0x1E, 0x00],
+    [0x7F, 0x63, 0x31, 0x18, 0x4C, 0x66, 0x7F, 0x00],
+    [0x1E, 0x06, 0x06, 0x06, 0x06, 0x06, 0x1E, 0x00],
+    [0x03, 0x06, 0x0C, 0x18, 0x30, 0x60, 0x40, 0x00],
+    [0x1E, 0x18, 0x18, 0x18, 0x18, 0x18, 0x1E, 0x00],
+    [0x08, 0x1C, 0x36, 0x63, 0x00, 0x00, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF],
+    [0x0C, 0x0C, 0x18, 0x00, 0x00, 0x00, 0x00, 0x00],
+    [0x00, 0x00, 0x1E, 0x30, 0x3E, 0x33, 0x6E, 0x00],
+    [0x07, 0x06, 0x06, 0x3E, 0x66, 0x66, 0x3B, 0x00],
+    [0x00, 0x00, 0x1E, 0x33, 0x03, 0x33, 0x1E, 0x00],
+    [0x38, 0x30, 0x30, 0x3E, 0x33, 0x33, 0x6E, 0x00],
+    [0x00, 0x00, 0x1E, 0x33, 0x3F, 0x03, 0x1E, 0x00],
+    [0x1C, 0x36, 0x06, 0x0F, 0x06, 0x06, 0x0F, 0x00],
+    [0x00, 0x00, 0x6E, 0x33, 0x33, 0x3E, 0x30, 0x1F],
+    [0x07, 0x06, 0x36, 0x6E, 0x66, 0x66, 0x67, 0x00],
+    [0x0C, 0x00, 0x0E, 0x0C, 0x0C, 0x0C, 0x1E, 0x00],
+    [0x30, 0x00, 0x30, 0x30, 0x30, 0x33, 0x33, 0x1E],
+    [0x07, 0x06, 0x66, 0x36, 0x1E, 0x36, 0x67, 0x00],
+    [0x0E, 0x0C, 0x0C, 0x0C, 0x0C, 0x0C, 0x1E, 0x00],
+    [0x00, 0x00, 0x33, 0x7F, 0x7F, 0x6B, 0x63, 0x00],
+    [0x00, 0x00, 0x1F, 0x33, 0x33, 0x33, 0x33, 0x00],
+    [0x00, 0x00, 0x1E, 0x33, 0x33, 0x33, 0x1E, 0x00],
+    [0x00, 0x00, 0x3B, 0x66, 0x66, 0x3E, 0x06, 0x0F],
+    [0x00, 0x00, 0x6E, 0x33, 0x33, 0x3E, 0x30, 0x78],
+    [0x00, 0x00, 0x3B, 0x6E, 0x66, 0x06, 0x0F, 0x00],
+    [0x00, 0x00, 0x3E, 0x03, 0x1E, 0x30, 0x1F, 0x00],
+    [0x08, 0x0C, 0x3E, 0x0C, 0x0C, 0x2C, 0x18, 0x00],
+    [0x00, 0x00, 0x33, 0x33, 0x33, 0x33, 0x6E, 0x00],
+    [0x00, 0x00, 0x33, 0x33, 0x33, 0x1E, 0x0C, 0x00],
+    [0x00, 0x00, 0x63, 0x6B, 0x7F, 0x7F, 0x36, 0x00],
+    [0x00, 0x00, 0x63, 0x36, 0x1C, 0x36, 0x63, 0x00],
+    [0x00, 0x00, 0x33, 0x33, 0x33, 0x3E, 0x30, 0x1F],
+    [0x00, 0x00, 0x3F, 0x19, 0x0C, 0x26, 0x3F, 0x00],
+    [0x38, 0x0C, 0x0C, 0x07, 0x0C, 0x0C, 0x38, 0x00],
+    [0x18, 0x18, 0x18, 0x00, 0x18, 0x18, 0x18, 0x00],
+    [0x07, 0x0C, 0x0C, 0x38, 0x0C, 0x0C, 0x07, 0x00],
+    [0x6E, 0x3B, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+];
+
+/// Returns whether the pixel at the given position within a cell is drawn in the
+/// foreground colour for a character.
+fn lit(ch: char, x: usize, y: usize) -> bool {
+    let code = ch as u32;
+    match ch {
+        ' ' => false,
+        '!'..='~' => FONT[(code - 0x20) as usize][y / 2] >> x & 1 != 0,
+        '█' => true,
+        '▀' => y < CELL_HGT / 2,
+        // Lower eighths of a block, from ▁ to ▇.
+        '▁'..='▇' => {
+            let eighths = (code - 0x2580) as usize;
+            y >= CELL_HGT - eighths * CELL_HGT / 8
+        }
+        '\u{2800}'..='\u{28ff}' => {
+            // Each braille dot is a two by two square in a two by four grid.
+            const DOT_BITS: [[u32; 2]; 4] =
+                [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+            let (col, row) = (x / (CELL_WID / 2), y / (CELL_HGT / 4));
+            let (dx, dy) = (x % (CELL_WID / 2), y % (CELL_HGT / 4));
+            code & DOT_BITS[row][col] != 0 && (1..3).contains(&dx) && (1..3).contains(&dy)
+        }
+        _ => match box_arms(ch) {
+            Some([left, right, up, down]) => {
+                let (cx, cy) = (CELL_WID / 2, CELL_HGT / 2);
+                let thick = matches!(
+                    ch,
+                    '━' | '┃' | '┏' | '┓' | '┗' | '┛' | '┣' | '┫' | '┳' | '┻' | '╋' | '═'..='╬'
+                );
+                let near = |a: usize, b: usize| a == b || (thick && a + 1 == b);
+                (near(y, cy) && ((left && x <= cx) || (right && x >= cx - 1)))
+                    || (near(x, cx) && ((up && y <= cy) || (down && y >= cy - 1)))
+            }
+            // Draw anything else as an outlined box.
+            None => {
+                (1..CELL_WID - 1).contains(&x)
+                    && (2..CELL_HGT - 2).contains(&y)
+                    && (x == 1 || x == CELL_WID - 2 || y == 2 || y == CELL_HGT - 3)
+            }
+        },
+    }
+}
+
+impl Container<Cell> {
+    /// Returns the RGBA pixels of an image of the container with positions from
+    /// (0, 0) to (wid, hgt), row by row.
+    fn rasterize(&self, wid: u16, hgt: u16) -> Vec<u8> {
+        let img_wid = wid as usize * CELL_WID;
+        let mut pixels = vec![0; img_wid * hgt as usize * CELL_HGT * 4];
+
+        for (row_idx, row) in self.cell_rows(wid, hgt).iter().enumerate() {
+            for (col, cell) in row.iter().enumerate() {
+                let (fg, bg) = colors(cell.style);
+                for y in 0..CELL_HGT {
+                    for x in 0..CELL_WID {
+                        let underline = cell.style.underline && y == CELL_HGT - 2;
+                        let on = lit(cell.ch, x, y)
+                            || underline
+                            // Bold text is drawn a pixel wider.
+                            || (cell.style.bold && x > 0 && lit(cell.ch, x - 1, y));
+                        let (r, g, b) = if on { fg } else { bg };
+                        let idx = ((row_idx * CELL_HGT + y) * img_wid + col * CELL_WID + x) * 4;
+                        pixels[idx..idx + 4].copy_from_slice(&[r, g, b, 255]);
+                    }
+                }
+            }
+        }
+
+        pixels
+    }
+
+    /// Saves a PNG image of the container with positions from (0, 0) to (wid, hgt),
+    /// with each cell drawn eight pixels wide and sixteen tall. The image is a PNG
+    /// whatever the extension of the path.
+    pub fn screenshot(&self, path: impl AsRef<Path>, wid: u16, hgt: u16) -> io::Result<()> {
+        let (img_wid, img_hgt) = (wid as u32 * CELL_WID as u32, hgt as u32 * CELL_HGT as u32);
+        let img = ::image::RgbaImage::from_raw(img_wid, img_hgt, self.rasterize(wid, hgt))
+            .ok_or_else(|| io::Error::other("screenshot has the wrong number of pixels"))?;
+        img.save_with_format(path, ::image::ImageFormat::Png)
+            .map_err(io::Error::other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saves_a_png_whatever_the_extension() {
+        let container: Container<Cell> = Container::new();
+        let path = std::env::temp_dir().join(format!("windowed-shot-{}.dat", std::process::id()));
+        container.screenshot(&path, 2, 1).unwrap();
+        let bytes = std::fs::read(&path);
+        let _ = std::fs::remove_file(&path);
+        assert!(bytes.unwrap().starts_with(b"\x89PNG"));
+    }
+}
@@ -0,0 +1,152 @@
+//! Saving where windows are, so that they can be put back there the next time the
+//! program runs.
+
+use crate::geometry::{Coord, Point, Rect};
+use crate::layout::invalid;
+use crate::{Container, WindowId};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Where a window was and how it was shown, without its contents.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SessionWindow {
+    /// Title of the window, which it is matched by.
+    pub name: String,
+    pub x: Coord,
+    pub y: Coord,
+    pub width: usize,
+    pub height: usize,
+    /// Place of the window in the stacking order, with 0 at the bottom.
+    pub z: usize,
+    #[serde(default)]
+    pub hidden: bool,
+    #[serde(default)]
+    pub scroll: usize,
+}
+
+/// The arrangement of the titled windows of a container, saved with
+/// [`Container::save_session`] and put back with [`Container::restore_session`].
+///
+/// Windows are matched by title, as that is what they are known by to the user. If
+/// several windows share a title, they are matched in stacking order, bottom first.
+/// Untitled windows aren't saved, and windows with no match are left as they are.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Session {
+    #[serde(default)]
+    pub windows: Vec<SessionWindow>,
+}
+
+impl Session {
+    /// Parses a session written in JSON.
+    pub fn from_json(text: &str) -> io::Result<Self> {
+        serde_json::from_str(text).map_err(invalid)
+    }
+
+    /// Writes the session as JSON.
+    pub fn to_json(&self) -> io::Result<String> {
+        serde_json::to_string_pretty(self).map_err(invalid)
+    }
+}
+
+impl<T: fmt::Display> Container<T> {
+    /// Returns the arrangement of the titled windows.
+    pub fn session(&self) -> Session {
+        let windows = self
+            .windows
+            .iter()
+            .enumerate()
+            .filter_map(|(z, win)| {
+                let rect = win.rect();
+                Some(SessionWindow {
+                    name: win.title.clone()?,
+                    x: rect.top_left.x,
+                    y: rect.top_left.y,
+                    width: rect.wid,
+                    height: rect.hgt,
+                    z,
+                    hidden: win.hidden,
+                    scroll: win.scroll,
+                })
+            })
+            .collect();
+        Session { windows }
+    }
+
+    /// Moves, resizes, restacks, hides or shows and scrolls each window matched by
+    /// the session to how it was saved. New space in resized windows is filled with
+    /// default values. Returns how many windows were matched.
+    pub fn apply_session(&mut self, session: &Session) -> usize
+    where
+        T: Clone + Default,
+    {
+        let mut saved: Vec<&SessionWindow> = session.windows.iter().collect();
+        saved.sort_by_key(|w| w.z);
+
+        // Each window matched, with how it was saved, bottom first.
+        let mut matched: Vec<(WindowId, &SessionWindow)> = Vec::new();
+        for (id, win) in self.windows.iter().enumerate() {
+            let Some(title) = &win.title else {
+                continue;
+            };
+            if let Some(idx) = saved.iter().position(|w| &w.name == title) {
+                matched.push((id, saved.remove(idx)));
+            }
+        }
+
+        for &(id, w) in matched.iter() {
+            let rect = Rect::new(Point::new(w.x, w.y), w.width, w.height);
+            if self.windows[id].rect() != rect {
+                self.set_rect(id, rect, T::default());
+            }
+            self.set_hidden(id, w.hidden);
+            let win = &mut self.windows[id];
+            win.scroll = w.scroll.min(win.max_scroll());
+        }
+
+        // Put the matched windows in the places among themselves they were saved in,
+        // leaving the rest where they are.
+        let slots: Vec<WindowId> = matched.iter().map(|&(id, _)| id).collect();
+        matched.sort_by_key(|&(_, w)| w.z);
+        let mut new_ids: Vec<WindowId> = (0..self.windows.len()).collect();
+        for (&slot, &(id, _)) in slots.iter().zip(matched.iter()) {
+            new_ids[id] = slot;
+        }
+        if new_ids.iter().enumerate().any(|(old, &new)| old != new) {
+            let mut windows: Vec<_> = std::mem::take(&mut self.windows)
+                .into_iter()
+                .map(Some)
+                .collect();
+            let mut order: Vec<WindowId> = (0..windows.len()).collect();
+            order.sort_by_key(|&old| new_ids[old]);
+            self.windows = order
+                .into_iter()
+                .filter_map(|old| windows[old].take())
+                .collect();
+            for &slot in slots.iter() {
+                self.mark_dirty(self.windows[slot].footprint().collect::<Vec<_>>());
+            }
+            self.remap_ids(|id| Some(new_ids[id]));
+        }
+
+        matched.len()
+    }
+
+    /// Saves the arrangement of the titled windows to a file as JSON.
+    pub fn save_session(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.session().to_json()?)
+    }
+
+    /// Reads an arrangement saved with [`save_session`](Self::save_session) and puts
+    /// the windows back as they were, like [`apply_session`](Self::apply_session).
+    /// Returns how many windows were matched.
+    pub fn restore_session(&mut self, path: impl AsRef<Path>) -> io::Result<usize>
+    where
+        T: Clone + Default,
+    {
+        let session = Session::from_json(&fs::read_to_string(path)?)?;
+        Ok(self.apply_session(&session))
+    }
+}
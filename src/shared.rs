@@ -0,0 +1,187 @@
+//! A container shared between threads, presented by a thread of its own.
+
+use crate::Container;
+use crate::backend::Backend;
+use crate::event::Event;
+use crate::runtime::{Control, FramePacer};
+use std::collections::VecDeque;
+use std::fmt;
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// A handle to a container that can be cloned and used from any thread. Clones
+/// share the same container.
+///
+/// Changes made with [`update`](Self::update) happen all at once while the
+/// container is locked, so a frame never shows half of one.
+#[derive(Debug, Default)]
+pub struct SharedContainer<T: fmt::Display> {
+    inner: Arc<Mutex<Container<T>>>,
+}
+
+impl<T: fmt::Display> Clone for SharedContainer<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: fmt::Display> From<Container<T>> for SharedContainer<T> {
+    fn from(container: Container<T>) -> Self {
+        Self::new(container)
+    }
+}
+
+impl<T: fmt::Display> SharedContainer<T> {
+    /// Shares the given container.
+    pub fn new(container: Container<T>) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(container)),
+        }
+    }
+
+    /// Locks the container, ignoring panics of anything that held it before. Changes
+    /// made through the lock are only presented once a refresh is
+    /// [requested](Container::request_refresh).
+    pub fn lock(&self) -> MutexGuard<'_, Container<T>> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Changes the container, then requests a refresh so the changes are presented
+    /// with the next frame.
+    pub fn update<R>(&self, f: impl FnOnce(&mut Container<T>) -> R) -> R {
+        let mut container = self.lock();
+        let result = f(&mut container);
+        container.request_refresh();
+        result
+    }
+
+    /// Returns something worked out from the container.
+    pub fn read<R>(&self, f: impl FnOnce(&Container<T>) -> R) -> R {
+        f(&self.lock())
+    }
+
+    /// Takes the container back, if this is the only handle to it.
+    pub fn try_unwrap(self) -> Result<Container<T>, Self> {
+        Arc::try_unwrap(self.inner)
+            .map(|inner| inner.into_inner().unwrap_or_else(|e| e.into_inner()))
+            .map_err(|inner| Self { inner })
+    }
+
+    /// Starts a thread that presents the container to `backend` at up to `fps` frames
+    /// per second, until [stopped](RenderThread::stop).
+    ///
+    /// The thread reads input from the backend and lets the container handle it as
+    /// [`run`](Container::run) would. The events, along with ticks, those of timers
+    /// and any [posted](Container::post) ones, are sent to the returned handle. A
+    /// frame is only presented if something happened, a refresh was requested, or a
+    /// bound source changed. The container is locked once per frame, for as short a
+    /// time as possible, and never while waiting for input.
+    pub fn spawn_render_thread<B>(&self, mut backend: B, fps: u32) -> RenderThread<B>
+    where
+        B: Backend + Send + 'static,
+        T: Clone + PartialEq + Default + Send + 'static,
+    {
+        let shared = self.clone();
+        let stop = Arc::new(AtomicBool::new(false));
+        let (sender, events) = mpsc::channel();
+
+        let handle = thread::spawn({
+            let stop = stop.clone();
+            move || {
+                let mut size = backend.size()?;
+                let tick_rate = {
+                    let mut container = shared.lock();
+                    container.start(&mut backend, size)?;
+                    container.tick_rate
+                };
+                let mut pacer = FramePacer::new(Some(fps.max(1)), tick_rate);
+                pacer.presented();
+                // Events are dropped if nothing is listening.
+                let mut send = |ev, _: &mut Container<T>| {
+                    let _ = sender.send(ev);
+                    Control::Continue
+                };
+
+                while !stop.load(Ordering::Acquire) {
+                    pacer.wait();
+                    let mut input = Vec::new();
+                    while let Some(ev) = backend.read_event(Duration::ZERO)? {
+                        input.push(ev);
+                    }
+
+                    let mut container = shared.lock();
+                    pacer.tick_rate = container.tick_rate;
+                    let (mut due, mut pending) = container.due_events();
+                    if pacer.tick() {
+                        due.insert(0, Event::Tick);
+                    }
+                    due.extend(input);
+
+                    let mut full = false;
+                    for event in due {
+                        let (events, resized) = container.handle_event(event, &mut size);
+                        container.deliver(events, &mut send);
+                        pending = true;
+                        full |= resized;
+                    }
+                    container.deliver(VecDeque::new(), &mut send);
+
+                    pending |= container.take_refresh_request();
+                    pending |= container.update_bindings();
+                    if pending {
+                        container.finish(&mut backend, size, full)?;
+                        pacer.presented();
+                    }
+                }
+                Ok(backend)
+            }
+        });
+
+        RenderThread {
+            handle,
+            stop,
+            events,
+        }
+    }
+}
+
+/// A thread started by [`SharedContainer::spawn_render_thread`].
+#[derive(Debug)]
+pub struct RenderThread<B> {
+    handle: JoinHandle<io::Result<B>>,
+    stop: Arc<AtomicBool>,
+    events: Receiver<Event>,
+}
+
+impl<B> RenderThread<B> {
+    /// Returns the events handled by the thread, in the order they happened.
+    pub fn events(&self) -> &Receiver<Event> {
+        &self.events
+    }
+
+    /// Returns true if the thread has stopped, which it does early if the backend
+    /// fails.
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+
+    /// Stops the thread after the frame it is on, waiting for it to finish. Returns
+    /// the backend, or the error that stopped the thread early.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the thread panicked.
+    pub fn stop(self) -> io::Result<B> {
+        self.stop.store(true, Ordering::Release);
+        match self.handle.join() {
+            Ok(result) => result,
+            Err(panic) => std::panic::resume_unwind(panic),
+        }
+    }
+}
@@ -0,0 +1,81 @@
+//! Encoding of pixels as sixel graphics, which some terminals can display.
+
+use crate::image::Pixels;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Returns the index of the nearest colour in a 6 by 6 by 6 colour cube.
+fn palette_index([r, g, b, _]: [u8; 4]) -> usize {
+    let level = |c: u8| (c as usize * 5 + 127) / 255;
+    level(r) * 36 + level(g) * 6 + level(b)
+}
+
+/// Encodes pixels as a sixel sequence, leaving pixels less than half opaque
+/// transparent.
+pub(crate) fn encode(pixels: &Pixels) -> String {
+    // Background select 1 keeps pixels that aren't drawn transparent.
+    let mut out = format!("\x1bP0;1;0q\"1;1;{};{}", pixels.wid, pixels.hgt);
+
+    for idx in 0..216 {
+        let level = |l: usize| l * 100 / 5;
+        let _ = write!(
+            out,
+            "#{idx};2;{};{};{}",
+            level(idx / 36),
+            level(idx / 6 % 6),
+            level(idx % 6)
+        );
+    }
+
+    // Each band covers six rows of pixels, with a bit for each.
+    for band in 0..pixels.hgt.div_ceil(6) {
+        let mut colors: BTreeMap<usize, Vec<u8>> = BTreeMap::new();
+        for dy in 0..6 {
+            let y = band * 6 + dy;
+            for x in 0..pixels.wid {
+                let Some(pixel) = pixels.get(x, y) else {
+                    continue;
+                };
+                if pixel[3] < 128 {
+                    continue;
+                }
+                let bits = colors
+                    .entry(palette_index(pixel))
+                    .or_insert_with(|| vec![0; pixels.wid]);
+                bits[x] |= 1 << dy;
+            }
+        }
+
+        for (n, (idx, bits)) in colors.iter().enumerate() {
+            if n > 0 {
+                // Go back to the start of the band for the next colour.
+                out.push('$');
+            }
+            let _ = write!(out, "#{idx}");
+            write_run_lengths(&mut out, bits);
+        }
+        out.push('-');
+    }
+
+    out.push_str("\x1b\\");
+    out
+}
+
+/// Writes sixel characters for some columns, shortening repeats.
+fn write_run_lengths(out: &mut String, bits: &[u8]) {
+    let mut iter = bits.iter().peekable();
+    while let Some(&b) = iter.next() {
+        let mut count = 1;
+        while iter.next_if_eq(&&b).is_some() {
+            count += 1;
+        }
+        let ch = (b'?' + b) as char;
+        if count > 3 {
+            let _ = write!(out, "!{count}{ch}");
+        } else {
+            for _ in 0..count {
+                out.push(ch);
+            }
+        }
+    }
+}
@@ -0,0 +1,110 @@
+//! Reporting how much memory a container holds on to.
+
+use crate::geometry::Point;
+use crate::{Container, Window, WindowId};
+use std::fmt;
+use std::mem::size_of;
+
+/// Memory held by one window.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct WindowMemory {
+    /// Number of cells stored.
+    pub cells: usize,
+    /// Number of rows stored.
+    pub rows: usize,
+    /// Number of rows of a scrollable window that aren't shown, above or below its
+    /// view.
+    pub scrollback: usize,
+    /// Estimated bytes allocated for the rows, not counting anything the cells
+    /// themselves allocate.
+    pub bytes: usize,
+}
+
+/// How much memory a container holds on to, from [`Container::memory_stats`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct MemoryStats {
+    /// Memory held by each window, by id.
+    pub windows: Vec<WindowMemory>,
+    /// Number of positions in the buffer.
+    pub buffer: usize,
+    /// Number of positions the buffer has room for.
+    pub buffer_capacity: usize,
+    /// Number of positions changed by the last refresh.
+    pub changed: usize,
+    /// Number of positions the list of changes has room for.
+    pub changed_capacity: usize,
+    /// Number of positions waiting to be reported as changed.
+    pub dirty: usize,
+    /// Number of events waiting to be given to the event loop.
+    pub posted: usize,
+    /// Estimated bytes allocated altogether, not counting anything the cells
+    /// themselves allocate.
+    pub bytes: usize,
+}
+
+impl MemoryStats {
+    /// Returns the number of cells stored by all the windows.
+    pub fn cells(&self) -> usize {
+        self.windows.iter().map(|w| w.cells).sum()
+    }
+
+    /// Returns the number of rows of scrollback kept by all the windows.
+    pub fn scrollback(&self) -> usize {
+        self.windows.iter().map(|w| w.scrollback).sum()
+    }
+
+    /// Returns the ids of the `n` windows holding the most memory, along with their
+    /// stats, largest first.
+    pub fn largest(&self, n: usize) -> Vec<(WindowId, WindowMemory)> {
+        let mut windows: Vec<_> = self.windows.iter().copied().enumerate().collect();
+        windows.sort_by_key(|(_, mem)| std::cmp::Reverse(mem.bytes));
+        windows.truncate(n);
+        windows
+    }
+}
+
+impl<T: fmt::Display> Window<T> {
+    /// Returns how much memory the window holds on to.
+    pub fn memory(&self) -> WindowMemory {
+        let scrollback = self
+            .view_hgt
+            .map_or(0, |view| self.data.len().saturating_sub(view));
+        WindowMemory {
+            cells: self.data.iter().map(Vec::len).sum(),
+            rows: self.data.len(),
+            scrollback,
+            bytes: self.data.capacity() * size_of::<Vec<T>>()
+                + self
+                    .data
+                    .iter()
+                    .map(|row| row.capacity() * size_of::<T>())
+                    .sum::<usize>(),
+        }
+    }
+}
+
+impl<T: fmt::Display> Container<T> {
+    /// Returns how much memory the container and each of its windows hold on to, to
+    /// help find things that grow without limit.
+    pub fn memory_stats(&self) -> MemoryStats {
+        let windows: Vec<_> = self.windows.iter().map(Window::memory).collect();
+        // Each entry of a hash map also takes about a byte of control data.
+        let entry = |size: usize| size + 1;
+        let bytes = windows.iter().map(|w| w.bytes).sum::<usize>()
+            + self.buffer.capacity() * size_of::<Option<T>>()
+            + self.owners.capacity() * size_of::<Option<WindowId>>()
+            + self.dirty.capacity() * entry(size_of::<Point>())
+            + self.changed.capacity() * size_of::<Point>();
+
+        MemoryStats {
+            windows,
+            buffer: self.buffer.len(),
+            buffer_capacity: self.buffer.capacity(),
+            changed: self.changed.len(),
+            changed_capacity: self.changed.capacity(),
+            dirty: self.dirty.len(),
+            posted: self.posted.len(),
+            bytes,
+        }
+    }
+}
@@ -0,0 +1,173 @@
+//! Running a container inside a tokio runtime.
+
+use crate::Container;
+use crate::backend::Backend;
+use crate::event::Event;
+use crate::runtime::{Control, FramePacer};
+use futures_core::Stream;
+use std::collections::VecDeque;
+use std::fmt;
+use std::future::{Future, poll_fn};
+use std::io;
+use std::pin::{Pin, pin};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tokio::time::{Sleep, sleep};
+
+/// How long to wait between checks of the backend for input.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Something given to the callback of [`Container::run_async`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum AsyncEvent<M> {
+    /// An event read from the backend, or produced by the container.
+    Event(Event),
+    /// A message from the application's own stream.
+    Message(M),
+}
+
+/// The events read from a backend, as a stream. A [`Event::Tick`] is produced
+/// whenever `tick_rate` passes. The backend is checked for input every few
+/// milliseconds, so it doesn't need to support waking the task itself.
+pub struct EventStream<'a, B: Backend> {
+    backend: &'a mut B,
+    tick_rate: Duration,
+    last_tick: Instant,
+    sleep: Pin<Box<Sleep>>,
+}
+
+impl<'a, B: Backend> EventStream<'a, B> {
+    /// Create a stream of the events read from a backend.
+    pub fn new(backend: &'a mut B, tick_rate: Duration) -> Self {
+        Self {
+            backend,
+            tick_rate,
+            last_tick: Instant::now(),
+            sleep: Box::pin(sleep(Duration::ZERO)),
+        }
+    }
+}
+
+impl<B: Backend> Stream for EventStream<'_, B> {
+    type Item = io::Result<Event>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            if this.last_tick.elapsed() >= this.tick_rate {
+                this.last_tick = Instant::now();
+                return Poll::Ready(Some(Ok(Event::Tick)));
+            }
+            match this.backend.read_event(Duration::ZERO) {
+                Ok(Some(ev)) => return Poll::Ready(Some(Ok(ev))),
+                Ok(None) => {}
+                Err(e) => return Poll::Ready(Some(Err(e))),
+            }
+
+            let wait = POLL_INTERVAL.min(this.tick_rate.saturating_sub(this.last_tick.elapsed()));
+            this.sleep
+                .as_mut()
+                .reset(tokio::time::Instant::now() + wait);
+            if this.sleep.as_mut().poll(cx).is_pending() {
+                return Poll::Pending;
+            }
+        }
+    }
+}
+
+impl<T: fmt::Display> Container<T> {
+    /// Runs the container like [`run`](Self::run), but as a future, until the callback
+    /// returns [`Control::Quit`]. Besides the events read from the backend and those
+    /// of [`timers`](Self::timers), the callback is given each message from
+    /// `messages`, after which the container is refreshed and the changes presented in
+    /// the same way.
+    pub async fn run_async<B, S, F>(
+        &mut self,
+        backend: &mut B,
+        messages: S,
+        mut f: F,
+    ) -> io::Result<()>
+    where
+        B: Backend,
+        S: Stream,
+        F: FnMut(AsyncEvent<S::Item>, &mut Self) -> Control,
+        T: Clone + PartialEq + Default,
+    {
+        let mut size = backend.size()?;
+        self.start(backend, size)?;
+
+        let mut messages = pin!(messages);
+        let mut messages_done = false;
+        let mut wait = pin!(sleep(Duration::ZERO));
+
+        let mut pacer = FramePacer::new(self.max_fps, self.tick_rate);
+        pacer.presented();
+        // Whether anything has been handled since the last frame, and whether the
+        // next frame must redraw everything.
+        let mut pending = false;
+        let mut full = false;
+
+        loop {
+            pacer.max_fps = self.max_fps;
+            pacer.tick_rate = self.tick_rate;
+            let (mut due, expired) = self.due_events();
+            pending |= expired || self.take_refresh_request();
+            if pacer.tick() {
+                due.insert(0, Event::Tick);
+            }
+            if due.is_empty() {
+                due.extend(backend.read_event(Duration::ZERO)?);
+            }
+
+            let handled = !due.is_empty();
+            for event in due {
+                let (events, resized) = self.handle_event(event, &mut size);
+                let control = self.deliver(events, &mut |ev, c| f(AsyncEvent::Event(ev), c));
+                if control == Control::Quit {
+                    return Ok(());
+                }
+                pending = true;
+                full |= resized;
+            }
+            if pending && pacer.frame_due() {
+                pacer.begin_frame();
+                self.finish(backend, size, full)?;
+                pacer.presented();
+                pending = false;
+                full = false;
+            }
+            // Check for more input straight away while it keeps coming.
+            if handled {
+                continue;
+            }
+
+            // Wait for a message until it is time to check for input again, or to
+            // present a frame that is waiting.
+            let timeout = self.until_due(POLL_INTERVAL.min(pacer.timeout(pending)));
+            wait.as_mut().reset(tokio::time::Instant::now() + timeout);
+            let message = poll_fn(|cx| {
+                if !messages_done && let Poll::Ready(msg) = messages.as_mut().poll_next(cx) {
+                    return Poll::Ready(Some(msg));
+                }
+                wait.as_mut().poll(cx).map(|()| None)
+            })
+            .await;
+
+            match message {
+                Some(Some(msg)) => {
+                    if f(AsyncEvent::Message(msg), self) == Control::Quit {
+                        return Ok(());
+                    }
+                    let posted =
+                        self.deliver(VecDeque::new(), &mut |ev, c| f(AsyncEvent::Event(ev), c));
+                    if posted == Control::Quit {
+                        return Ok(());
+                    }
+                    pending = true;
+                }
+                Some(None) => messages_done = true,
+                None => {}
+            }
+        }
+    }
+}
@@ -0,0 +1,451 @@
+//! Colours and text attributes, and a cell type that carries them.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// A terminal colour.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "config",
+    derive(serde::Deserialize),
+    serde(try_from = "String")
+)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    Gray,
+    /// A colour from the 256 colour palette.
+    Indexed(u8),
+    /// A true colour.
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    /// Returns the red, green and blue components of the colour, using the usual
+    /// xterm values for the named and palette colours.
+    pub fn to_rgb(self) -> (u8, u8, u8) {
+        const NAMED: [(u8, u8, u8); 16] = [
+            (0, 0, 0),
+            (205, 0, 0),
+            (0, 205, 0),
+            (205, 205, 0),
+            (0, 0, 238),
+            (205, 0, 205),
+            (0, 205, 205),
+            (229, 229, 229),
+            (127, 127, 127),
+            (255, 0, 0),
+            (0, 255, 0),
+            (255, 255, 0),
+            (92, 92, 255),
+            (255, 0, 255),
+            (0, 255, 255),
+            (255, 255, 255),
+        ];
+
+        match self {
+            Color::Black => NAMED[0],
+            Color::Red => NAMED[1],
+            Color::Green => NAMED[2],
+            Color::Yellow => NAMED[3],
+            Color::Blue => NAMED[4],
+            Color::Magenta => NAMED[5],
+            Color::Cyan => NAMED[6],
+            Color::White => NAMED[7],
+            Color::Gray => NAMED[8],
+            Color::Indexed(i @ 0..=15) => NAMED[i as usize],
+            Color::Indexed(i @ 16..=231) => {
+                let level = |l: u8| if l == 0 { 0 } else { 55 + l * 40 };
+                let i = i - 16;
+                (level(i / 36), level(i / 6 % 6), level(i % 6))
+            }
+            Color::Indexed(i) => {
+                let v = 8 + (i - 232) * 10;
+                (v, v, v)
+            }
+            Color::Rgb(r, g, b) => (r, g, b),
+        }
+    }
+
+    /// Returns the closest colour in the 256 colour palette.
+    pub fn to_ansi256(self) -> u8 {
+        let (r, g, b) = match self {
+            Color::Indexed(i) => return i,
+            Color::Rgb(r, g, b) => (r, g, b),
+            named => return named.to_ansi16(),
+        };
+
+        // The closest colour of the 6x6x6 cube, and the closest grey.
+        let level = |v: u8| match v {
+            0..48 => 0,
+            48..115 => 1,
+            _ => (v - 35) / 40,
+        };
+        let cube = 16 + 36 * level(r) + 6 * level(g) + level(b);
+        let avg = ((r as u16 + g as u16 + b as u16) / 3) as u8;
+        let grey = 232 + (avg.saturating_sub(3) / 10).min(23);
+
+        let dist = |i: u8| distance(Color::Indexed(i).to_rgb(), (r, g, b));
+        if dist(grey) < dist(cube) { grey } else { cube }
+    }
+
+    /// Returns the index of the closest of the 16 standard colours, the first eight
+    /// of which are the named colours other than gray and the rest their bright
+    /// versions.
+    pub fn to_ansi16(self) -> u8 {
+        match self {
+            Color::Black => 0,
+            Color::Red => 1,
+            Color::Green => 2,
+            Color::Yellow => 3,
+            Color::Blue => 4,
+            Color::Magenta => 5,
+            Color::Cyan => 6,
+            Color::White => 7,
+            Color::Gray => 8,
+            Color::Indexed(i @ 0..16) => i,
+            other => {
+                let rgb = other.to_rgb();
+                (0..16)
+                    .min_by_key(|&i| distance(Color::Indexed(i).to_rgb(), rgb))
+                    .unwrap_or(0)
+            }
+        }
+    }
+
+    /// Returns the closest colour a terminal with the given colour depth can show,
+    /// or None if it can't show colours.
+    pub fn downgrade(self, depth: ColorDepth) -> Option<Self> {
+        match depth {
+            ColorDepth::Mono => None,
+            ColorDepth::Ansi16 => Some(Color::Indexed(self.to_ansi16())),
+            ColorDepth::Ansi256 if matches!(self, Color::Rgb(..)) => {
+                Some(Color::Indexed(self.to_ansi256()))
+            }
+            _ => Some(self),
+        }
+    }
+
+    /// Writes the SGR parameters selecting this colour, as a foreground colour if `fg`
+    /// is true and a background colour otherwise.
+    fn write_sgr(self, f: &mut fmt::Formatter<'_>, fg: bool) -> fmt::Result {
+        let base = if fg { 30 } else { 40 };
+        match self {
+            Color::Black => write!(f, "{base}"),
+            Color::Red => write!(f, "{}", base + 1),
+            Color::Green => write!(f, "{}", base + 2),
+            Color::Yellow => write!(f, "{}", base + 3),
+            Color::Blue => write!(f, "{}", base + 4),
+            Color::Magenta => write!(f, "{}", base + 5),
+            Color::Cyan => write!(f, "{}", base + 6),
+            Color::White => write!(f, "{}", base + 7),
+            Color::Gray => write!(f, "{}", base + 60),
+            Color::Indexed(i) => write!(f, "{};5;{i}", base + 8),
+            Color::Rgb(r, g, b) => write!(f, "{};2;{r};{g};{b}", base + 8),
+        }
+    }
+}
+
+/// Returns the squared distance between two colours.
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| (x as i32 - y as i32).pow(2) as u32;
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+
+/// How many colours a terminal can show.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ColorDepth {
+    /// No colours at all.
+    Mono,
+    /// The eight named colours and their bright versions.
+    Ansi16,
+    /// The 256 colour palette.
+    Ansi256,
+    /// Any colour.
+    #[default]
+    TrueColor,
+}
+
+/// Error from parsing a [`Color`] that isn't a known name, palette index or
+/// `#rrggbb` colour.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ParseColorError(String);
+
+impl fmt::Display for ParseColorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown colour {:?}", self.0)
+    }
+}
+
+impl std::error::Error for ParseColorError {}
+
+impl FromStr for Color {
+    type Err = ParseColorError;
+
+    /// Parses a colour name such as `red`, a palette index from 0 to 255, or a true
+    /// colour written as `#rrggbb`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseColorError(s.to_string());
+        if let Some(hex) = s.strip_prefix('#') {
+            if hex.len() != 6 {
+                return Err(err());
+            }
+            let channel = |i: usize| {
+                u8::from_str_radix(hex.get(i..i + 2).ok_or_else(err)?, 16).map_err(|_| err())
+            };
+            return Ok(Color::Rgb(channel(0)?, channel(2)?, channel(4)?));
+        }
+        if let Ok(idx) = s.parse() {
+            return Ok(Color::Indexed(idx));
+        }
+
+        Ok(match s.to_ascii_lowercase().as_str() {
+            "black" => Color::Black,
+            "red" => Color::Red,
+            "green" => Color::Green,
+            "yellow" => Color::Yellow,
+            "blue" => Color::Blue,
+            "magenta" => Color::Magenta,
+            "cyan" => Color::Cyan,
+            "white" => Color::White,
+            "gray" | "grey" => Color::Gray,
+            _ => return Err(err()),
+        })
+    }
+}
+
+impl TryFrom<String> for Color {
+    type Error = ParseColorError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+/// How a cell is displayed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "config", derive(serde::Deserialize), serde(default))]
+pub struct Style {
+    /// Colour of the character, or the terminal's default if None.
+    pub fg: Option<Color>,
+    /// Colour behind the character, or the terminal's default if None.
+    pub bg: Option<Color>,
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    /// Swaps the foreground and background colours.
+    pub reversed: bool,
+}
+
+impl Style {
+    /// The terminal's default style.
+    pub const fn new() -> Self {
+        Self {
+            fg: None,
+            bg: None,
+            bold: false,
+            italic: false,
+            underline: false,
+            reversed: false,
+        }
+    }
+
+    /// Returns the style with the given foreground colour.
+    pub const fn fg(mut self, color: Color) -> Self {
+        self.fg = Some(color);
+        self
+    }
+
+    /// Returns the style with the given background colour.
+    pub const fn bg(mut self, color: Color) -> Self {
+        self.bg = Some(color);
+        self
+    }
+
+    /// Returns the style in bold.
+    pub const fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    /// Returns the style in italics.
+    pub const fn italic(mut self) -> Self {
+        self.italic = true;
+        self
+    }
+
+    /// Returns the style underlined.
+    pub const fn underline(mut self) -> Self {
+        self.underline = true;
+        self
+    }
+
+    /// Returns the style with its colours swapped.
+    pub const fn reversed(mut self) -> Self {
+        self.reversed = true;
+        self
+    }
+
+    /// Returns this style with anything set in `other` applied on top.
+    pub fn patch(self, other: Style) -> Self {
+        Self {
+            fg: other.fg.or(self.fg),
+            bg: other.bg.or(self.bg),
+            bold: self.bold || other.bold,
+            italic: self.italic || other.italic,
+            underline: self.underline || other.underline,
+            reversed: self.reversed || other.reversed,
+        }
+    }
+
+    /// Returns true if this is the terminal's default style.
+    pub fn is_plain(&self) -> bool {
+        *self == Self::new()
+    }
+}
+
+/// Styles for the parts of an interface, chosen to be readable against a particular
+/// background.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Theme {
+    /// Ordinary text.
+    pub text: Style,
+    /// Text of less importance, such as hints.
+    pub muted: Style,
+    /// Whatever is selected or focused.
+    pub highlight: Style,
+    /// Borders and separators.
+    pub border: Style,
+}
+
+impl Theme {
+    /// Light text for a dark background.
+    pub const DARK: Self = Self {
+        text: Style::new().fg(Color::White),
+        muted: Style::new().fg(Color::Gray),
+        highlight: Style::new().fg(Color::Black).bg(Color::Cyan),
+        border: Style::new().fg(Color::Gray),
+    };
+
+    /// Dark text for a light background.
+    pub const LIGHT: Self = Self {
+        text: Style::new().fg(Color::Black),
+        muted: Style::new().fg(Color::Gray),
+        highlight: Style::new().fg(Color::White).bg(Color::Blue),
+        border: Style::new().fg(Color::Gray),
+    };
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::DARK
+    }
+}
+
+/// A character with a style, which can be used as the contents of a window.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Cell {
+    pub ch: char,
+    pub style: Style,
+}
+
+impl Cell {
+    /// Create a new cell.
+    pub const fn new(ch: char, style: Style) -> Self {
+        Self { ch, style }
+    }
+
+    /// Returns this cell with another drawn over it. The other's character is shown
+    /// unless it is a space, and its style is applied on top of this one's, so a
+    /// space with only a background colour tints what is below.
+    pub fn blend(self, top: Cell) -> Self {
+        Self {
+            ch: if top.ch == ' ' { self.ch } else { top.ch },
+            style: self.style.patch(top.style),
+        }
+    }
+}
+
+impl Default for Cell {
+    /// A space in the default style.
+    fn default() -> Self {
+        Self::new(' ', Style::new())
+    }
+}
+
+impl From<char> for Cell {
+    fn from(ch: char) -> Self {
+        Self::new(ch, Style::new())
+    }
+}
+
+impl fmt::Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.style.is_plain() {
+            return write!(f, "{}", self.ch);
+        }
+
+        write!(f, "\x1b[0")?;
+        if self.style.bold {
+            write!(f, ";1")?;
+        }
+        if self.style.italic {
+            write!(f, ";3")?;
+        }
+        if self.style.underline {
+            write!(f, ";4")?;
+        }
+        if self.style.reversed {
+            write!(f, ";7")?;
+        }
+        if let Some(fg) = self.style.fg {
+            write!(f, ";")?;
+            fg.write_sgr(f, true)?;
+        }
+        if let Some(bg) = self.style.bg {
+            write!(f, ";")?;
+            bg.write_sgr(f, false)?;
+        }
+        write!(f, "m{}\x1b[0m", self.ch)
+    }
+}
+
+/// A piece of text in a single style.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Span {
+    pub text: String,
+    pub style: Style,
+}
+
+impl Span {
+    /// Create a new span.
+    pub fn new(text: impl Into<String>, style: Style) -> Self {
+        Self {
+            text: text.into(),
+            style,
+        }
+    }
+
+    /// Returns the cells of the span.
+    pub fn cells(&self) -> impl Iterator<Item = Cell> + '_ {
+        self.text.chars().map(|ch| Cell::new(ch, self.style))
+    }
+}
+
+impl From<&str> for Span {
+    fn from(text: &str) -> Self {
+        Self::new(text, Style::new())
+    }
+}
+
+impl From<String> for Span {
+    fn from(text: String) -> Self {
+        Self::new(text, Style::new())
+    }
+}
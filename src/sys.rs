@@ -0,0 +1,513 @@
+//! Switching the console the program is running in between the modes it is drawn in
+//! and the ones it was in before, on each platform.
+
+pub(crate) use imp::*;
+
+/// What [`wait`] found first.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Ready {
+    /// Standard input can be read without blocking.
+    Input,
+    /// The program was asked to stop, such as by Ctrl-Z outside raw mode or
+    /// `kill -TSTP`.
+    Stop,
+    /// The program was continued after being stopped, such as by `fg`.
+    Continue,
+    /// Nothing happened before the timeout.
+    Timeout,
+}
+
+#[cfg(unix)]
+mod imp {
+    use super::Ready;
+    use std::ffi::{c_int, c_short, c_ulong, c_void};
+    use std::fs::File;
+    use std::io;
+    use std::os::fd::AsRawFd;
+    use std::process::{Command, Stdio};
+    use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+    use std::time::Duration;
+
+    /// Request for the size of a terminal, where it is known.
+    #[cfg(all(
+        any(target_os = "linux", target_os = "android"),
+        not(any(
+            target_arch = "mips",
+            target_arch = "mips64",
+            target_arch = "powerpc",
+            target_arch = "powerpc64",
+            target_arch = "sparc64"
+        ))
+    ))]
+    const TIOCGWINSZ: Option<c_ulong> = Some(0x5413);
+    #[cfg(any(
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "netbsd",
+        target_os = "dragonfly",
+        all(
+            any(target_os = "linux", target_os = "android"),
+            any(
+                target_arch = "mips",
+                target_arch = "mips64",
+                target_arch = "powerpc",
+                target_arch = "powerpc64",
+                target_arch = "sparc64"
+            )
+        )
+    ))]
+    const TIOCGWINSZ: Option<c_ulong> = Some(0x40087468);
+    #[cfg(not(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "netbsd",
+        target_os = "dragonfly"
+    )))]
+    const TIOCGWINSZ: Option<c_ulong> = None;
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct Winsize {
+        rows: u16,
+        cols: u16,
+        x_pixels: u16,
+        y_pixels: u16,
+    }
+
+    /// Numbers of SIGTSTP and SIGCONT, where they are known.
+    #[cfg(all(
+        any(target_os = "linux", target_os = "android"),
+        not(any(target_arch = "mips", target_arch = "mips64", target_arch = "sparc64"))
+    ))]
+    const JOB_SIGNALS: Option<(c_int, c_int)> = Some((20, 18));
+    #[cfg(all(
+        any(target_os = "linux", target_os = "android"),
+        any(target_arch = "mips", target_arch = "mips64")
+    ))]
+    const JOB_SIGNALS: Option<(c_int, c_int)> = Some((24, 25));
+    #[cfg(any(
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "netbsd",
+        target_os = "dragonfly",
+        all(
+            any(target_os = "linux", target_os = "android"),
+            target_arch = "sparc64"
+        )
+    ))]
+    const JOB_SIGNALS: Option<(c_int, c_int)> = Some((18, 19));
+    #[cfg(not(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "netbsd",
+        target_os = "dragonfly"
+    )))]
+    const JOB_SIGNALS: Option<(c_int, c_int)> = None;
+
+    const SIG_ERR: usize = usize::MAX;
+    const POLLIN: c_short = 0x1;
+
+    #[cfg(any(target_os = "macos", target_os = "ios"))]
+    type Nfds = std::ffi::c_uint;
+    #[cfg(not(any(target_os = "macos", target_os = "ios")))]
+    type Nfds = c_ulong;
+
+    #[repr(C)]
+    struct PollFd {
+        fd: c_int,
+        events: c_short,
+        revents: c_short,
+    }
+
+    unsafe extern "C" {
+        fn ioctl(fd: c_int, request: c_ulong, ...) -> c_int;
+        fn signal(signum: c_int, handler: Option<extern "C" fn(c_int)>) -> usize;
+        fn raise(signum: c_int) -> c_int;
+        fn pipe(fds: *mut c_int) -> c_int;
+        fn read(fd: c_int, buf: *mut c_void, count: usize) -> isize;
+        fn write(fd: c_int, buf: *const c_void, count: usize) -> isize;
+        fn poll(fds: *mut PollFd, nfds: Nfds, timeout: c_int) -> c_int;
+    }
+
+    // Ends of the pipe the signal handler writes the signals it catches to, so that
+    // they are dealt with outside it. Both are -1 until the pipe is made.
+    static SIGNAL_READ: AtomicI32 = AtomicI32::new(-1);
+    static SIGNAL_WRITE: AtomicI32 = AtomicI32::new(-1);
+    // Whether the handler is installed.
+    static CATCHING: AtomicBool = AtomicBool::new(false);
+
+    extern "C" fn on_signal(signum: c_int) {
+        let fd = SIGNAL_WRITE.load(Ordering::Relaxed);
+        let byte = signum as u8;
+        // SAFETY: write is safe to call from a signal handler, and the byte outlives
+        // the call. If the pipe is full the signal is already waiting to be read.
+        unsafe { write(fd, (&byte as *const u8).cast(), 1) };
+    }
+
+    /// Sets what happens when the program gets a signal, which is [`on_signal`] or
+    /// what happens by default if None.
+    fn set_handler(signum: c_int, handler: Option<extern "C" fn(c_int)>) -> io::Result<()> {
+        // SAFETY: the handler only does what is safe in a signal handler.
+        if unsafe { signal(signum, handler) } == SIG_ERR {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    /// Starts catching the signals that stop and continue the program, so that
+    /// [`wait`] reports them rather than the program stopping with the terminal
+    /// still in raw mode.
+    pub(crate) fn catch_job_signals() -> io::Result<()> {
+        let Some((stop, cont)) = JOB_SIGNALS else {
+            return Ok(());
+        };
+        if SIGNAL_READ.load(Ordering::Relaxed) < 0 {
+            let mut fds = [0; 2];
+            // SAFETY: `fds` has room for both ends.
+            if unsafe { pipe(fds.as_mut_ptr()) } != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            SIGNAL_READ.store(fds[0], Ordering::Relaxed);
+            SIGNAL_WRITE.store(fds[1], Ordering::Relaxed);
+        }
+        set_handler(stop, Some(on_signal))?;
+        set_handler(cont, Some(on_signal))?;
+        CATCHING.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Stops catching the signals caught by [`catch_job_signals`].
+    pub(crate) fn release_job_signals() -> io::Result<()> {
+        let Some((stop, cont)) = JOB_SIGNALS else {
+            return Ok(());
+        };
+        CATCHING.store(false, Ordering::Relaxed);
+        set_handler(stop, None).and(set_handler(cont, None))
+    }
+
+    /// Waits up to `timeout` for standard input to be readable or for a signal
+    /// caught by [`catch_job_signals`].
+    pub(crate) fn wait(timeout: Duration) -> io::Result<Ready> {
+        let signals = SIGNAL_READ.load(Ordering::Relaxed);
+        // Negative descriptors are skipped by poll.
+        let mut fds = [
+            PollFd {
+                fd: signals,
+                events: POLLIN,
+                revents: 0,
+            },
+            PollFd {
+                fd: 0,
+                events: POLLIN,
+                revents: 0,
+            },
+        ];
+        let ms = timeout.as_millis().min(c_int::MAX as u128) as c_int;
+        // SAFETY: `fds` holds as many entries as are passed and outlives the call.
+        if unsafe { poll(fds.as_mut_ptr(), fds.len() as Nfds, ms) } < 0 {
+            let err = io::Error::last_os_error();
+            // A signal arriving interrupts the wait, and is read next time.
+            return match err.kind() {
+                io::ErrorKind::Interrupted => Ok(Ready::Timeout),
+                _ => Err(err),
+            };
+        }
+
+        if fds[0].revents != 0 {
+            let mut byte = 0u8;
+            // SAFETY: the byte outlives the call.
+            if unsafe { read(signals, (&mut byte as *mut u8).cast(), 1) } == 1 {
+                match JOB_SIGNALS {
+                    Some((stop, _)) if byte as c_int == stop => return Ok(Ready::Stop),
+                    Some((_, cont)) if byte as c_int == cont => return Ok(Ready::Continue),
+                    _ => {}
+                }
+            }
+        }
+        // Errors and hang ups count too, so that reading reports them.
+        if fds[1].revents != 0 {
+            return Ok(Ready::Input);
+        }
+        Ok(Ready::Timeout)
+    }
+
+    /// Settings of the console to put back when the program is done with it.
+    #[derive(Clone, Debug, Default)]
+    pub(crate) struct Settings(String);
+
+    /// Runs `stty` on the controlling terminal with the given arguments, returning
+    /// what it printed.
+    fn stty(args: &[&str]) -> io::Result<String> {
+        let out = Command::new("stty")
+            .args(args)
+            .stdin(File::open("/dev/tty")?)
+            .stderr(Stdio::null())
+            .output()?;
+
+        if !out.status.success() {
+            return Err(io::Error::other("stty failed"));
+        }
+
+        Ok(String::from_utf8_lossy(&out.stdout).trim().to_string())
+    }
+
+    /// Reads the current settings of the console.
+    pub(crate) fn save() -> io::Result<Settings> {
+        stty(&["-g"]).map(Settings)
+    }
+
+    /// Puts back settings read by [`save`].
+    pub(crate) fn restore(settings: &Settings) -> io::Result<()> {
+        stty(&[&settings.0]).map(drop)
+    }
+
+    /// Switches the console to raw mode, where keys are read as they are pressed and
+    /// aren't echoed.
+    pub(crate) fn raw() -> io::Result<()> {
+        stty(&["raw", "-echo"]).map(drop)
+    }
+
+    /// Lets escape sequences written to standard output take effect, which they
+    /// already do.
+    pub(crate) fn enable_ansi() -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Reads the width and height of the console. This is checked often, so it asks
+    /// the terminal directly rather than running `stty` where it can.
+    pub(crate) fn size() -> io::Result<(u16, u16)> {
+        if let Some(request) = TIOCGWINSZ {
+            let tty = File::open("/dev/tty")?;
+            let mut size = Winsize::default();
+            // SAFETY: the descriptor is open for the whole call and the request only
+            // writes a Winsize to the pointer given.
+            let res = unsafe { ioctl(tty.as_raw_fd(), request, &mut size as *mut Winsize) };
+            if res == 0 && size.cols > 0 && size.rows > 0 {
+                return Ok((size.cols, size.rows));
+            }
+        }
+
+        let out = stty(&["size"])?;
+        let mut parts = out.split_whitespace().map(|p| p.parse::<u16>());
+
+        match (parts.next(), parts.next()) {
+            (Some(Ok(hgt)), Some(Ok(wid))) => Ok((wid, hgt)),
+            _ => Err(io::Error::other("could not read terminal size")),
+        }
+    }
+
+    /// Stops the program as if the user had pressed Ctrl-Z in a shell, returning once
+    /// it is continued.
+    pub(crate) fn stop_self() -> io::Result<()> {
+        let Some((stop, _)) = JOB_SIGNALS else {
+            let status = Command::new("kill")
+                .args(["-TSTP", &std::process::id().to_string()])
+                .stdin(Stdio::null())
+                .stderr(Stdio::null())
+                .status()?;
+
+            if !status.success() {
+                return Err(io::Error::other("could not suspend"));
+            }
+            return Ok(());
+        };
+
+        // The signal has to do what it would by default for the program to stop.
+        let catching = CATCHING.load(Ordering::Relaxed);
+        if catching {
+            set_handler(stop, None)?;
+        }
+        // SAFETY: raise has no preconditions.
+        let raised = unsafe { raise(stop) };
+        if catching {
+            set_handler(stop, Some(on_signal))?;
+        }
+        if raised != 0 {
+            return Err(io::Error::other("could not suspend"));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(windows)]
+mod imp {
+    use super::Ready;
+    use std::ffi::c_void;
+    use std::io;
+    use std::time::Duration;
+
+    type Handle = *mut c_void;
+
+    const STD_INPUT_HANDLE: u32 = -10i32 as u32;
+    const STD_OUTPUT_HANDLE: u32 = -11i32 as u32;
+
+    const ENABLE_PROCESSED_INPUT: u32 = 0x1;
+    const ENABLE_LINE_INPUT: u32 = 0x2;
+    const ENABLE_ECHO_INPUT: u32 = 0x4;
+    const ENABLE_VIRTUAL_TERMINAL_INPUT: u32 = 0x200;
+    const ENABLE_PROCESSED_OUTPUT: u32 = 0x1;
+    const ENABLE_VIRTUAL_TERMINAL_PROCESSING: u32 = 0x4;
+
+    const WAIT_OBJECT_0: u32 = 0;
+    const WAIT_TIMEOUT: u32 = 0x102;
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct Coord {
+        x: i16,
+        y: i16,
+    }
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct SmallRect {
+        left: i16,
+        top: i16,
+        right: i16,
+        bottom: i16,
+    }
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct ScreenBufferInfo {
+        size: Coord,
+        cursor: Coord,
+        attributes: u16,
+        window: SmallRect,
+        max_size: Coord,
+    }
+
+    #[link(name = "kernel32")]
+    unsafe extern "system" {
+        fn GetStdHandle(which: u32) -> Handle;
+        fn GetConsoleMode(console: Handle, mode: *mut u32) -> i32;
+        fn SetConsoleMode(console: Handle, mode: u32) -> i32;
+        fn GetConsoleScreenBufferInfo(console: Handle, info: *mut ScreenBufferInfo) -> i32;
+        fn WaitForSingleObject(handle: Handle, ms: u32) -> u32;
+    }
+
+    /// Settings of the console to put back when the program is done with it.
+    #[derive(Clone, Debug, Default)]
+    pub(crate) struct Settings {
+        input: u32,
+        output: u32,
+    }
+
+    fn handle(which: u32) -> io::Result<Handle> {
+        // SAFETY: GetStdHandle has no preconditions.
+        let handle = unsafe { GetStdHandle(which) };
+        if handle.is_null() || handle as isize == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(handle)
+    }
+
+    fn get_mode(which: u32) -> io::Result<u32> {
+        let mut mode = 0;
+        // SAFETY: the handle is valid and `mode` outlives the call.
+        if unsafe { GetConsoleMode(handle(which)?, &mut mode) } == 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(mode)
+    }
+
+    fn set_mode(which: u32, mode: u32) -> io::Result<()> {
+        // SAFETY: the handle is valid.
+        if unsafe { SetConsoleMode(handle(which)?, mode) } == 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    /// Reads the current settings of the console.
+    pub(crate) fn save() -> io::Result<Settings> {
+        Ok(Settings {
+            input: get_mode(STD_INPUT_HANDLE)?,
+            output: get_mode(STD_OUTPUT_HANDLE)?,
+        })
+    }
+
+    /// Puts back settings read by [`save`].
+    pub(crate) fn restore(settings: &Settings) -> io::Result<()> {
+        let input = set_mode(STD_INPUT_HANDLE, settings.input);
+        let output = set_mode(STD_OUTPUT_HANDLE, settings.output);
+        input.and(output)
+    }
+
+    /// Switches the console to raw mode, where keys are read as they are pressed and
+    /// aren't echoed. Keys are sent as the same escape sequences other terminals
+    /// send, so they are decoded the same way.
+    pub(crate) fn raw() -> io::Result<()> {
+        let input = get_mode(STD_INPUT_HANDLE)?;
+        let cooked = ENABLE_PROCESSED_INPUT | ENABLE_LINE_INPUT | ENABLE_ECHO_INPUT;
+        set_mode(
+            STD_INPUT_HANDLE,
+            (input & !cooked) | ENABLE_VIRTUAL_TERMINAL_INPUT,
+        )?;
+        enable_ansi()
+    }
+
+    /// Lets escape sequences written to standard output take effect, rather than be
+    /// shown as text. Older consoles that can't do this are left as they are.
+    pub(crate) fn enable_ansi() -> io::Result<()> {
+        let output = get_mode(STD_OUTPUT_HANDLE)?;
+        set_mode(
+            STD_OUTPUT_HANDLE,
+            output | ENABLE_PROCESSED_OUTPUT | ENABLE_VIRTUAL_TERMINAL_PROCESSING,
+        )
+    }
+
+    /// Reads the width and height of the console's visible area.
+    pub(crate) fn size() -> io::Result<(u16, u16)> {
+        let mut info = ScreenBufferInfo::default();
+        // SAFETY: the handle is valid and `info` outlives the call.
+        if unsafe { GetConsoleScreenBufferInfo(handle(STD_OUTPUT_HANDLE)?, &mut info) } == 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let wid = info.window.right - info.window.left + 1;
+        let hgt = info.window.bottom - info.window.top + 1;
+        Ok((wid.max(0) as u16, hgt.max(0) as u16))
+    }
+
+    /// Programs aren't stopped and continued by signals on Windows, so there is
+    /// nothing to catch.
+    pub(crate) fn catch_job_signals() -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Does nothing, as nothing is caught by [`catch_job_signals`].
+    pub(crate) fn release_job_signals() -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Waits up to `timeout` for the console to have input.
+    pub(crate) fn wait(timeout: Duration) -> io::Result<Ready> {
+        let ms = timeout.as_millis().min(u32::MAX as u128 - 1) as u32;
+        // SAFETY: the handle is valid.
+        match unsafe { WaitForSingleObject(handle(STD_INPUT_HANDLE)?, ms) } {
+            WAIT_OBJECT_0 => Ok(Ready::Input),
+            WAIT_TIMEOUT => Ok(Ready::Timeout),
+            _ => Err(io::Error::last_os_error()),
+        }
+    }
+
+    /// Programs can't be suspended from the console on Windows.
+    pub(crate) fn stop_self() -> io::Result<()> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "suspending isn't supported on Windows",
+        ))
+    }
+}
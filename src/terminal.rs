@@ -0,0 +1,704 @@
+//! A [`Backend`] that draws to the terminal the program is running in using ANSI
+//! escape sequences.
+
+use crate::backend::Backend;
+use crate::caps::{self, Capabilities};
+use crate::event::{Event, KeyCode, KeyEvent, Modifiers, MouseMode};
+use crate::geometry::Point;
+use crate::image::Pixels;
+use crate::input::Parser;
+use crate::kitty;
+use crate::style::{ColorDepth, Theme};
+use crate::sys::{self, Ready};
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::{self, BufRead, BufWriter, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// How often to check whether the terminal has been resized.
+const SIZE_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Longest the thread reading input waits before checking whether to carry on.
+const INPUT_POLL: Duration = Duration::from_millis(100);
+
+/// How long to wait for the terminal to reply to a query.
+const QUERY_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// Enhancements to how keys are reported, in terminals that support the kitty
+/// keyboard protocol.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct KeyboardFlags {
+    /// Reports keys that can't otherwise be told apart, such as Ctrl-Enter, Ctrl-I
+    /// and Tab, or Esc and the start of a sequence, unambiguously.
+    pub disambiguate: bool,
+    /// Reports releases of keys as [`Event::KeyRelease`]. Repeats are still reported
+    /// as presses.
+    pub report_events: bool,
+}
+
+impl KeyboardFlags {
+    /// No enhancements, as with terminals that don't support the protocol.
+    pub const NONE: Self = Self {
+        disambiguate: false,
+        report_events: false,
+    };
+    /// Only disambiguation.
+    pub const DISAMBIGUATE: Self = Self {
+        disambiguate: true,
+        report_events: false,
+    };
+    /// Disambiguation and releases.
+    pub const ALL: Self = Self {
+        disambiguate: true,
+        report_events: true,
+    };
+
+    /// Returns the flags as the protocol encodes them.
+    fn bits(self) -> u8 {
+        self.disambiguate as u8 | (self.report_events as u8) << 1
+    }
+}
+
+/// Stops every kind of mouse reporting.
+const MOUSE_OFF: &str = "\x1b[?1000l\x1b[?1002l\x1b[?1003l\x1b[?1006l";
+
+/// Returns the sequence that starts reporting the given mouse events, with
+/// positions encoded as SGR parameters so they aren't limited to 223 columns.
+fn mouse_sequence(mode: MouseMode) -> &'static str {
+    match mode {
+        MouseMode::Click => "\x1b[?1000h\x1b[?1006h",
+        MouseMode::Drag => "\x1b[?1002h\x1b[?1006h",
+        MouseMode::Motion => "\x1b[?1003h\x1b[?1006h",
+    }
+}
+
+/// What the thread reading input passes on to the [`Terminal`].
+enum Input {
+    Bytes(Vec<u8>),
+    // The program was asked to stop.
+    Stop,
+    // The program was continued after being stopped.
+    Continue,
+}
+
+/// What is needed to give the terminal back, while a [`Terminal`] has taken it over.
+#[derive(Clone, Debug, Default)]
+struct Restore {
+    saved: sys::Settings,
+    kitty_graphics: bool,
+    // Whether the title was saved before it was first set.
+    title: bool,
+    // Whether keyboard flags were pushed.
+    keyboard: bool,
+}
+
+impl Restore {
+    /// Returns the sequence that switches off everything the [`Terminal`] switched
+    /// on.
+    fn sequence(&self) -> String {
+        let mut seq = String::new();
+        if self.kitty_graphics {
+            // Remove every image this program placed.
+            seq.push_str("\x1b_Ga=d,q=2,d=A\x1b\\");
+        }
+        if self.title {
+            seq.push_str("\x1b[23;0t");
+        }
+        if self.keyboard {
+            seq.push_str("\x1b[<u");
+        }
+        seq.push_str("\x1b[0m");
+        seq.push_str(MOUSE_OFF);
+        seq.push_str("\x1b[?25h\x1b[?2004l\x1b[?1049l");
+        seq
+    }
+}
+
+/// The terminal taken over by a [`Terminal`], if any, shared with the panic hook.
+static ACTIVE: Mutex<Option<Restore>> = Mutex::new(None);
+
+/// Locks what is needed to give the terminal back.
+fn active() -> MutexGuard<'static, Option<Restore>> {
+    ACTIVE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Gives the terminal back to the shell if a [`Terminal`] has taken it over, showing
+/// the cursor, leaving the alternate screen and turning off raw mode and mouse
+/// capture. Doing so more than once does nothing, and the [`Terminal`] doing it
+/// again when dropped does nothing either.
+///
+/// This is for when the [`Terminal`] won't be dropped in time, such as when it is
+/// owned by another thread or the program is about to exit.
+pub fn restore() -> io::Result<()> {
+    let Some(restore) = active().take() else {
+        return Ok(());
+    };
+    let mut out = io::stdout();
+    let written = write!(out, "{}", restore.sequence()).and_then(|()| out.flush());
+    let restored = sys::restore(&restore.saved);
+    written.and(restored)
+}
+
+/// Installs a panic hook that [restores](restore) the terminal before the panic
+/// message is printed, so the message can be read and the shell is usable
+/// afterwards. The hook that was installed before is kept and run after.
+pub fn install_panic_hook() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let _ = restore();
+        previous(info);
+    }));
+}
+
+/// [Restores](restore) the terminal when dropped, including while unwinding from a
+/// panic, unless the [`Terminal`] has already given it back.
+#[derive(Debug, Default)]
+#[must_use = "the terminal is restored when the guard is dropped"]
+pub struct RestoreGuard(());
+
+impl RestoreGuard {
+    /// Create a guard.
+    pub fn new() -> Self {
+        Self(())
+    }
+}
+
+impl Drop for RestoreGuard {
+    fn drop(&mut self) {
+        let _ = restore();
+    }
+}
+
+/// The terminal the program is running in. Creating one switches the terminal to raw
+/// mode, the alternate screen and bracketed paste mode, and dropping it switches back.
+/// A panic prints its message before anything is dropped, so use
+/// [`install_panic_hook`] to have the terminal switched back first.
+///
+/// While it has the terminal, the program being stopped and continued by job
+/// control, such as with `kill -TSTP` and `fg`, gives the terminal back and takes it
+/// over again as [`suspend`](Self::suspend) does.
+pub struct Terminal {
+    out: BufWriter<io::Stdout>,
+    input: Receiver<Input>,
+    // Thread reading input, and whether it should stop.
+    reader: Option<JoinHandle<()>>,
+    stop_reader: Arc<AtomicBool>,
+    parser: Parser,
+    pending: VecDeque<Event>,
+    size: (u16, u16),
+    size_checked: Instant,
+    // Terminal settings to restore when dropped.
+    saved: sys::Settings,
+    caps: Capabilities,
+    // Whether a frame has been started since the last flush.
+    in_frame: bool,
+    // Whether the cursor is shown.
+    cursor_visible: bool,
+    // Where the cursor is, if nothing has moved it since it was placed.
+    cursor_at: Option<Point>,
+    // Mouse events being reported, if any.
+    mouse: Option<MouseMode>,
+    // Whether Ctrl-Z suspends the program.
+    suspend_key: bool,
+    keyboard: KeyboardFlags,
+    // Title set, which is set again when the terminal is taken back.
+    title: Option<String>,
+}
+
+impl Terminal {
+    /// Take over the terminal.
+    pub fn new() -> io::Result<Self> {
+        let saved = sys::save()?;
+
+        sys::catch_job_signals()?;
+
+        let (tx, input) = mpsc::channel();
+        let stop_reader = Arc::new(AtomicBool::new(false));
+        let stop = Arc::clone(&stop_reader);
+        let reader = thread::spawn(move || {
+            let mut stdin = io::stdin().lock();
+            loop {
+                let ready = sys::wait(INPUT_POLL);
+                // Checked after waiting, so nothing is read once the terminal is
+                // being dropped and input goes to whatever reads it next.
+                if stop.load(Ordering::Relaxed) {
+                    break;
+                }
+                let input = match ready {
+                    // Only what is buffered is taken, so nothing is left in the
+                    // buffer that waiting wouldn't know about.
+                    Ok(Ready::Input) => match stdin.fill_buf() {
+                        Ok([]) => break,
+                        Ok(buf) => Input::Bytes(buf.to_vec()),
+                        Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                        Err(_) => break,
+                    },
+                    Ok(Ready::Stop) => Input::Stop,
+                    Ok(Ready::Continue) => Input::Continue,
+                    Ok(Ready::Timeout) => continue,
+                    Err(_) => break,
+                };
+                if let Input::Bytes(bytes) = &input {
+                    stdin.consume(bytes.len());
+                }
+                if tx.send(input).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut term = Self {
+            out: BufWriter::new(io::stdout()),
+            input,
+            reader: Some(reader),
+            stop_reader,
+            parser: Parser::default(),
+            pending: VecDeque::new(),
+            size: sys::size()?,
+            size_checked: Instant::now(),
+            saved,
+            caps: Capabilities::from_env(),
+            in_frame: false,
+            cursor_visible: false,
+            cursor_at: None,
+            mouse: None,
+            suspend_key: false,
+            keyboard: KeyboardFlags::NONE,
+            title: None,
+        };
+        term.enter()?;
+        let replies = term.query(Capabilities::QUERIES)?;
+        term.caps.apply_replies(&replies);
+        term.sync_restore();
+        term.set_keyboard_flags(KeyboardFlags::DISAMBIGUATE)?;
+
+        Ok(term)
+    }
+
+    /// Writes `request` followed by a request for the terminal's primary device
+    /// attributes, which every terminal replies to, and returns everything read
+    /// until that reply. Anything read after it is kept as input, but a key pressed
+    /// while waiting may be lost. Gives up waiting after a short while.
+    fn query(&mut self, request: &str) -> io::Result<Vec<u8>> {
+        write!(self.out, "{request}\x1b[c")?;
+        self.out.flush()?;
+
+        let deadline = Instant::now() + QUERY_TIMEOUT;
+        let mut replies = Vec::new();
+        loop {
+            if let Some(end) = caps::attributes_end(&replies) {
+                let rest = replies.split_off(end);
+                self.pending.extend(self.parser.feed(&rest));
+                return Ok(replies);
+            }
+            match self
+                .input
+                .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+            {
+                Ok(Input::Bytes(bytes)) => replies.extend(bytes),
+                // The terminal has only just been taken over, so there is nothing
+                // to put back.
+                Ok(Input::Stop | Input::Continue) => {}
+                Err(RecvTimeoutError::Timeout) => return Ok(replies),
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "stdin closed"));
+                }
+            }
+        }
+    }
+
+    /// Sets the title of the terminal window or tab. The title it had before is saved
+    /// the first time, and put back when the terminal is given back, in terminals
+    /// that can do so.
+    pub fn set_title(&mut self, title: &str) -> io::Result<()> {
+        // Control characters would end the sequence early.
+        self.title = Some(title.chars().filter(|c| !c.is_control()).collect());
+        self.write_title()?;
+        self.out.flush()
+    }
+
+    /// Writes the title set, if any, saving the one it replaces if that hasn't been
+    /// done since the terminal was taken over.
+    fn write_title(&mut self) -> io::Result<()> {
+        let Some(title) = &self.title else {
+            return Ok(());
+        };
+        if let Some(restore) = active().as_mut()
+            && !restore.title
+        {
+            write!(self.out, "\x1b[22;0t")?;
+            restore.title = true;
+        }
+        write!(self.out, "\x1b]2;{title}\x07")
+    }
+
+    /// Returns what the terminal can do.
+    pub fn capabilities(&self) -> &Capabilities {
+        &self.caps
+    }
+
+    /// Returns the colour behind the text, if the terminal said what it is.
+    pub fn background(&self) -> Option<(u8, u8, u8)> {
+        self.caps.background
+    }
+
+    /// Returns true if the terminal's background is dark, or isn't known.
+    pub fn is_dark_background(&self) -> bool {
+        self.caps.is_dark_background()
+    }
+
+    /// Returns a theme readable on the terminal's background.
+    pub fn theme(&self) -> Theme {
+        self.caps.theme()
+    }
+
+    /// Overrides what the terminal was found to be able to do.
+    pub fn set_capabilities(&mut self, caps: Capabilities) {
+        self.caps = caps;
+        self.sync_restore();
+    }
+
+    /// Returns the output for drawing, starting a frame if one hasn't been started.
+    /// The terminal is told to show the frame all at once if it supports that, and
+    /// otherwise the cursor is hidden while drawing so it isn't seen moving around.
+    fn frame(&mut self) -> io::Result<&mut BufWriter<io::Stdout>> {
+        if !self.in_frame {
+            if self.caps.synchronized_output {
+                write!(self.out, "\x1b[?2026h")?;
+            } else {
+                self.hide_cursor()?;
+            }
+            self.in_frame = true;
+        }
+        self.cursor_at = None;
+        Ok(&mut self.out)
+    }
+
+    /// Returns the mouse events being reported, if any.
+    pub fn mouse_mode(&self) -> Option<MouseMode> {
+        self.mouse
+    }
+
+    /// Returns true if the cursor is shown.
+    pub fn is_cursor_visible(&self) -> bool {
+        self.cursor_visible
+    }
+
+    /// Switches the terminal to the modes the program draws in.
+    fn enter(&mut self) -> io::Result<()> {
+        *active() = Some(Restore {
+            saved: self.saved.clone(),
+            kitty_graphics: self.caps.kitty_graphics,
+            title: false,
+            keyboard: false,
+        });
+        self.switch_modes()?;
+        if self.keyboard != KeyboardFlags::NONE {
+            self.push_keyboard_flags()?;
+        }
+        self.write_title()?;
+        self.out.flush()
+    }
+
+    /// Switches to raw mode and the alternate screen, clearing it, and turns on
+    /// bracketed paste and the mouse events in use.
+    fn switch_modes(&mut self) -> io::Result<()> {
+        sys::raw()?;
+        self.cursor_visible = false;
+        self.cursor_at = None;
+        write!(self.out, "\x1b[?1049h\x1b[?2004h\x1b[?25l\x1b[2J")?;
+        if let Some(mode) = self.mouse {
+            write!(self.out, "{}", mouse_sequence(mode))?;
+        }
+        Ok(())
+    }
+
+    /// Switches the terminal to the modes the program draws in again after the
+    /// program was continued, since whatever stopped it may not have let it give the
+    /// terminal back first, and queues a redraw. Nothing happens if the terminal has
+    /// been given back.
+    fn resume(&mut self) -> io::Result<()> {
+        let Some(pushed) = active().as_ref().map(|restore| restore.keyboard) else {
+            return Ok(());
+        };
+        self.switch_modes()?;
+        if pushed {
+            write!(self.out, "\x1b[={}u", self.keyboard.bits())?;
+        }
+        self.write_title()?;
+        self.out.flush()?;
+        self.queue_redraw()
+    }
+
+    /// Queues a [`Event::Resize`] with the current size, so that an event loop
+    /// redraws everything.
+    fn queue_redraw(&mut self) -> io::Result<()> {
+        self.size = sys::size()?;
+        self.size_checked = Instant::now();
+        self.pending
+            .push_front(Event::Resize(self.size.0, self.size.1));
+        Ok(())
+    }
+
+    /// Switches the terminal back to how it was before the program took it over.
+    fn leave(&mut self) -> io::Result<()> {
+        // Something else may have given the terminal back already.
+        let Some(restore) = active().take() else {
+            return Ok(());
+        };
+        let written = write!(self.out, "{}", restore.sequence()).and_then(|()| self.out.flush());
+
+        // Restore the settings even if the rest couldn't be written.
+        let restored = sys::restore(&self.saved);
+        written.and(restored)
+    }
+
+    /// Hands the terminal back to the shell and stops the program, as Ctrl-Z would
+    /// outside raw mode. Once the program is continued, such as with `fg`, the
+    /// terminal is taken over again with the title and keyboard enhancements it had,
+    /// and a [`Event::Resize`] is queued so that an event loop redraws everything.
+    /// Programs can't be stopped on Windows, so this returns an error there, with the
+    /// terminal still taken over.
+    pub fn suspend(&mut self) -> io::Result<()> {
+        self.leave()?;
+        // Take the terminal back even if the program couldn't be stopped.
+        let stopped = sys::stop_self();
+        self.enter()?;
+        stopped?;
+        self.queue_redraw()
+    }
+
+    /// Returns the keyboard enhancements in use.
+    pub fn keyboard_flags(&self) -> KeyboardFlags {
+        self.keyboard
+    }
+
+    /// Sets the keyboard enhancements of the kitty keyboard protocol to use, which
+    /// are [`KeyboardFlags::DISAMBIGUATE`] to begin with. Terminals that don't support
+    /// the protocol are left as they are, and report keys as they always have.
+    pub fn set_keyboard_flags(&mut self, flags: KeyboardFlags) -> io::Result<()> {
+        if !self.caps.kitty_keyboard || flags == self.keyboard {
+            return Ok(());
+        }
+        self.keyboard = flags;
+        let pushed = active().as_ref().is_some_and(|restore| restore.keyboard);
+        if pushed {
+            write!(self.out, "\x1b[={}u", flags.bits())?;
+        } else {
+            self.push_keyboard_flags()?;
+        }
+        self.out.flush()
+    }
+
+    /// Pushes the keyboard flags in use onto the terminal's stack, so they are popped
+    /// off when the terminal is given back.
+    fn push_keyboard_flags(&mut self) -> io::Result<()> {
+        write!(self.out, "\x1b[>{}u", self.keyboard.bits())?;
+        if let Some(restore) = active().as_mut() {
+            restore.keyboard = true;
+        }
+        Ok(())
+    }
+
+    /// Sets whether pressing Ctrl-Z [suspends](Self::suspend) the program rather than
+    /// being read as a key. This is off by default, since raw mode stops the terminal
+    /// doing it, and can't be turned on on Windows, where programs can't be suspended.
+    pub fn set_suspend_key(&mut self, enabled: bool) {
+        self.suspend_key = enabled && cfg!(unix);
+    }
+
+    /// Sets whether sixel graphics are written, overriding what the terminal was
+    /// found to support.
+    pub fn set_sixel(&mut self, enabled: bool) {
+        self.caps.sixel = enabled;
+    }
+
+    /// Sets whether images are shown with the kitty graphics protocol, overriding
+    /// what the terminal was found to support.
+    pub fn set_kitty_graphics(&mut self, enabled: bool) {
+        self.caps.kitty_graphics = enabled;
+        self.sync_restore();
+    }
+
+    /// Makes giving the terminal back, including from the panic hook, match what the
+    /// terminal is now thought to support.
+    fn sync_restore(&self) {
+        if let Some(restore) = active().as_mut() {
+            restore.kitty_graphics = self.caps.kitty_graphics;
+        }
+    }
+}
+
+impl Terminal {
+    /// Suspends the program instead of returning Ctrl-Z, if that is enabled.
+    fn filter_suspend(&mut self, ev: Event) -> io::Result<Option<Event>> {
+        let ctrl_z = KeyEvent::new(KeyCode::Char('z'), Modifiers::CTRL);
+        if self.suspend_key && ev == Event::Key(ctrl_z) {
+            self.suspend()?;
+            return Ok(self.pending.pop_front());
+        }
+        Ok(Some(ev))
+    }
+}
+
+impl Backend for Terminal {
+    fn size(&mut self) -> io::Result<(u16, u16)> {
+        Ok(self.size)
+    }
+
+    fn read_event(&mut self, timeout: Duration) -> io::Result<Option<Event>> {
+        // There is no portable way to be told about resizes without a signal handler,
+        // so check for them every so often while events are read.
+        if self.size_checked.elapsed() >= SIZE_CHECK_INTERVAL {
+            self.size_checked = Instant::now();
+            let size = sys::size()?;
+            if size != self.size {
+                self.size = size;
+                return Ok(Some(Event::Resize(size.0, size.1)));
+            }
+        }
+
+        if let Some(ev) = self.pending.pop_front() {
+            return self.filter_suspend(ev);
+        }
+
+        match self.input.recv_timeout(timeout) {
+            Ok(Input::Bytes(bytes)) => {
+                self.pending.extend(self.parser.feed(&bytes));
+                match self.pending.pop_front() {
+                    Some(ev) => self.filter_suspend(ev),
+                    None => Ok(None),
+                }
+            }
+            Ok(Input::Stop) => {
+                self.suspend()?;
+                Ok(self.pending.pop_front())
+            }
+            Ok(Input::Continue) => {
+                self.resume()?;
+                Ok(self.pending.pop_front())
+            }
+            Err(RecvTimeoutError::Timeout) => Ok(None),
+            Err(RecvTimeoutError::Disconnected) => {
+                Err(io::Error::new(io::ErrorKind::UnexpectedEof, "stdin closed"))
+            }
+        }
+    }
+
+    fn write_cell(&mut self, p: Point, cell: Option<&dyn fmt::Display>) -> io::Result<()> {
+        let depth = self.caps.color;
+        let out = self.frame()?;
+        write!(out, "\x1b[{};{}H", p.y + 1, p.x + 1)?;
+        match cell {
+            Some(cell) if depth < ColorDepth::TrueColor => {
+                write!(out, "{}", caps::downgrade(&cell.to_string(), depth))
+            }
+            Some(cell) => write!(out, "{cell}"),
+            None => write!(out, " "),
+        }
+    }
+
+    fn supports_sixel(&self) -> bool {
+        self.caps.sixel
+    }
+
+    fn write_sixel(&mut self, p: Point, sixel: &str) -> io::Result<()> {
+        write!(self.frame()?, "\x1b[{};{}H{sixel}", p.y + 1, p.x + 1)
+    }
+
+    fn supports_kitty_graphics(&self) -> bool {
+        self.caps.kitty_graphics
+    }
+
+    fn transmit_image(&mut self, image: u32, pixels: &Pixels) -> io::Result<()> {
+        write!(self.frame()?, "{}", kitty::transmit(image, pixels))
+    }
+
+    fn place_image(&mut self, image: u32, placement: u32, p: Point, z: i32) -> io::Result<()> {
+        write!(
+            self.frame()?,
+            "\x1b[{};{}H{}",
+            p.y + 1,
+            p.x + 1,
+            kitty::place(image, placement, z)
+        )
+    }
+
+    fn delete_image(&mut self, image: u32, placement: Option<u32>) -> io::Result<()> {
+        write!(self.frame()?, "{}", kitty::delete(image, placement))
+    }
+
+    fn set_cursor(&mut self, p: Option<Point>) -> io::Result<()> {
+        let Some(p) = p else {
+            return self.hide_cursor();
+        };
+        if self.cursor_at != Some(p) {
+            write!(self.out, "\x1b[{};{}H", p.y + 1, p.x + 1)?;
+            self.cursor_at = Some(p);
+        }
+        self.show_cursor()
+    }
+
+    fn show_cursor(&mut self) -> io::Result<()> {
+        if !self.cursor_visible {
+            write!(self.out, "\x1b[?25h")?;
+            self.cursor_visible = true;
+        }
+        Ok(())
+    }
+
+    fn hide_cursor(&mut self) -> io::Result<()> {
+        if self.cursor_visible {
+            write!(self.out, "\x1b[?25l")?;
+            self.cursor_visible = false;
+        }
+        Ok(())
+    }
+
+    /// Does nothing if the terminal can't report the mouse. Reporting stops when the
+    /// terminal is dropped, [restored](restore) or suspended, and starts again on
+    /// resuming.
+    fn enable_mouse(&mut self, mode: MouseMode) -> io::Result<()> {
+        if !self.caps.mouse || self.mouse == Some(mode) {
+            return Ok(());
+        }
+        write!(self.out, "{MOUSE_OFF}{}", mouse_sequence(mode))?;
+        self.mouse = Some(mode);
+        self.out.flush()
+    }
+
+    fn disable_mouse(&mut self) -> io::Result<()> {
+        if self.mouse.take().is_some() {
+            write!(self.out, "{MOUSE_OFF}")?;
+            self.out.flush()?;
+        }
+        Ok(())
+    }
+
+    fn clear(&mut self) -> io::Result<()> {
+        write!(self.frame()?, "\x1b[2J")
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.in_frame && self.caps.synchronized_output {
+            write!(self.out, "\x1b[?2026l")?;
+        }
+        self.in_frame = false;
+        self.out.flush()
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        self.stop_reader.store(true, Ordering::Relaxed);
+        if let Some(reader) = self.reader.take() {
+            let _ = reader.join();
+        }
+        let _ = self.leave();
+        let _ = sys::release_job_signals();
+    }
+}
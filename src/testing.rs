@@ -0,0 +1,156 @@
+//! Helpers for testing what a container shows against expected text.
+//!
+//! ```ignore
+//! assert_frame_eq!(container, "
+//!     ┌──┐
+//!     └──┘
+//! ");
+//! assert_golden!(container, "tests/golden/dialog.txt");
+//! ```
+//!
+//! Setting the `UPDATE_GOLDEN` environment variable makes [`assert_golden!`] write
+//! what was shown to its file instead of comparing against it.
+
+use crate::Container;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// Environment variable that makes golden file assertions update the files.
+pub const UPDATE_GOLDEN: &str = "UPDATE_GOLDEN";
+
+/// Returns the text with whitespace removed from the end of each line, blank lines
+/// removed from the start and end, and the indentation shared by every line removed,
+/// so that expected frames can be written as indented string literals.
+pub fn normalize(text: &str) -> String {
+    let lines: Vec<&str> = text.lines().map(str::trim_end).collect();
+    let start = lines
+        .iter()
+        .position(|l| !l.is_empty())
+        .unwrap_or(lines.len());
+    let end = lines
+        .iter()
+        .rposition(|l| !l.is_empty())
+        .map_or(start, |i| i + 1);
+    let lines = &lines[start..end];
+    let indent = lines
+        .iter()
+        .filter(|l| !l.is_empty())
+        .map(|l| l.len() - l.trim_start_matches(' ').len())
+        .min()
+        .unwrap_or(0);
+
+    let mut out = String::new();
+    for line in lines {
+        out.push_str(line.get(indent..).unwrap_or_default());
+        out.push('\n');
+    }
+    out
+}
+
+/// Returns a line by line diff from `expected` to `actual`, or None if they are the
+/// same. Lines only in `expected` start with `-`, lines only in `actual` with `+`.
+pub fn diff(expected: &str, actual: &str) -> Option<String> {
+    if expected == actual {
+        return None;
+    }
+    let old: Vec<&str> = expected.lines().collect();
+    let new: Vec<&str> = actual.lines().collect();
+
+    // Length of the longest common subsequence of the lines after each position.
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut out = String::from("--- expected\n+++ actual\n");
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            out.push_str(&format!(" {}\n", old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            out.push_str(&format!("-{}\n", old[i]));
+            i += 1;
+        } else {
+            out.push_str(&format!("+{}\n", new[j]));
+            j += 1;
+        }
+    }
+    Some(out)
+}
+
+/// Panics with a diff if the container doesn't show the expected text, compared
+/// after [`normalize`]ing both. Used by [`assert_frame_eq!`].
+#[track_caller]
+pub fn assert_frame<T>(container: &Container<T>, expected: &str)
+where
+    T: fmt::Display + Clone + Default,
+{
+    let actual = normalize(&container.to_string_trimmed());
+    if let Some(diff) = diff(&normalize(expected), &actual) {
+        panic!("frame doesn't match\n{diff}");
+    }
+}
+
+/// Panics with a diff if the container doesn't show the text in a golden file, or
+/// writes it to the file if the [`UPDATE_GOLDEN`] environment variable is set. Used
+/// by [`assert_golden!`].
+#[track_caller]
+pub fn assert_golden<T>(container: &Container<T>, path: impl AsRef<Path>)
+where
+    T: fmt::Display + Clone + Default,
+{
+    let path = path.as_ref();
+    let actual = normalize(&container.to_string_trimmed());
+
+    if std::env::var_os(UPDATE_GOLDEN).is_some() {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).expect("could not create golden file directory");
+        }
+        fs::write(path, &actual).expect("could not write golden file");
+        return;
+    }
+
+    let expected = fs::read_to_string(path).unwrap_or_else(|err| {
+        panic!(
+            "could not read golden file {}: {err}\nset {UPDATE_GOLDEN}=1 to create it",
+            path.display()
+        )
+    });
+    if let Some(diff) = diff(&normalize(&expected), &actual) {
+        panic!(
+            "frame doesn't match golden file {}\n{diff}set {UPDATE_GOLDEN}=1 to update it",
+            path.display()
+        );
+    }
+}
+
+/// Asserts that a container shows the expected text, ignoring indentation and
+/// whitespace at the ends, and panics with a diff if it doesn't.
+#[macro_export]
+macro_rules! assert_frame_eq {
+    ($container:expr, $expected:expr $(,)?) => {
+        $crate::testing::assert_frame(&$container, $expected)
+    };
+}
+
+/// Asserts that a container shows the text in a golden file, given relative to the
+/// calling crate's manifest directory. Set the `UPDATE_GOLDEN` environment variable
+/// to write the file instead.
+#[macro_export]
+macro_rules! assert_golden {
+    ($container:expr, $path:expr $(,)?) => {
+        $crate::testing::assert_golden(
+            &$container,
+            ::std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join($path),
+        )
+    };
+}
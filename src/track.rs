@@ -0,0 +1,38 @@
+//! Keeping hold of windows while the ids of others shift.
+
+use crate::{Container, WindowId};
+use std::fmt;
+
+/// Identifies a window however windows are added, removed or reordered, created with
+/// [`Container::track`]. Look up its current id with [`Container::tracked`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TrackedId(u64);
+
+impl<T: fmt::Display> Container<T> {
+    /// Starts keeping track of a window, so that its id can be found again after
+    /// windows below it are removed or restacked.
+    pub fn track(&mut self, win: WindowId) -> TrackedId {
+        let id = TrackedId(self.next_tracked);
+        self.next_tracked += 1;
+        self.tracked.push((id, win));
+        id
+    }
+
+    /// Returns the current id of a tracked window, or None if it has been removed or
+    /// is no longer tracked.
+    pub fn tracked(&self, id: TrackedId) -> Option<WindowId> {
+        self.tracked
+            .iter()
+            .find(|&&(tracked, _)| tracked == id)
+            .map(|&(_, win)| win)
+    }
+
+    /// Stops keeping track of a window, returning its current id if it is still there.
+    pub fn untrack(&mut self, id: TrackedId) -> Option<WindowId> {
+        let idx = self
+            .tracked
+            .iter()
+            .position(|&(tracked, _)| tracked == id)?;
+        Some(self.tracked.remove(idx).1)
+    }
+}
@@ -0,0 +1,152 @@
+//! Changing several windows at once, all or nothing.
+
+use crate::geometry::Point;
+use crate::{Container, Rect, WindowId};
+use std::collections::HashSet;
+use std::fmt;
+
+/// A change to a window waiting for its transaction to be committed.
+#[derive(Clone, Debug)]
+enum Op<T> {
+    Move(WindowId, Point),
+    Resize(WindowId, usize, usize, T),
+    Hide(WindowId, bool),
+    Title(WindowId, Option<String>),
+    Clip(WindowId, Option<Rect>),
+    Scroll(WindowId, isize),
+    Cell(WindowId, Point, T),
+}
+
+impl<T> Op<T> {
+    /// Returns the window the change is to.
+    fn win(&self) -> WindowId {
+        match *self {
+            Op::Move(win, _)
+            | Op::Resize(win, ..)
+            | Op::Hide(win, _)
+            | Op::Title(win, _)
+            | Op::Clip(win, _)
+            | Op::Scroll(win, _)
+            | Op::Cell(win, ..) => win,
+        }
+    }
+}
+
+/// Changes to windows made by [`Container::transaction`]. Nothing is changed until
+/// the transaction is committed, so the container seen through
+/// [`container`](Self::container) is as it was before.
+#[derive(Debug)]
+pub struct Transaction<'a, T: fmt::Display> {
+    container: &'a Container<T>,
+    ops: Vec<Op<T>>,
+}
+
+impl<T: fmt::Display> Transaction<'_, T> {
+    /// Returns the container as it was before the transaction.
+    pub fn container(&self) -> &Container<T> {
+        self.container
+    }
+
+    /// Adds a change, panicking if its window doesn't exist like the container's own
+    /// methods do.
+    fn push(&mut self, op: Op<T>) -> &mut Self {
+        assert!(
+            op.win() < self.container.windows.len(),
+            "no window with id {}",
+            op.win()
+        );
+        self.ops.push(op);
+        self
+    }
+
+    /// Moves a window so that its top left is at the given position.
+    pub fn move_to(&mut self, win: WindowId, top_left: Point) -> &mut Self {
+        self.push(Op::Move(win, top_left))
+    }
+
+    /// Resizes a window like [`Window::resize`](crate::Window::resize).
+    pub fn resize(&mut self, win: WindowId, wid: usize, hgt: usize, fill: T) -> &mut Self {
+        self.push(Op::Resize(win, wid, hgt, fill))
+    }
+
+    /// Moves and resizes a window to fill a rectangle.
+    pub fn set_rect(&mut self, win: WindowId, rect: Rect, fill: T) -> &mut Self {
+        self.move_to(win, rect.top_left)
+            .resize(win, rect.wid, rect.hgt, fill)
+    }
+
+    /// Hides or shows a window.
+    pub fn set_hidden(&mut self, win: WindowId, hidden: bool) -> &mut Self {
+        self.push(Op::Hide(win, hidden))
+    }
+
+    /// Sets the title of a window.
+    pub fn set_title(&mut self, win: WindowId, title: Option<String>) -> &mut Self {
+        self.push(Op::Title(win, title))
+    }
+
+    /// Sets the clip rectangle of a window.
+    pub fn set_clip(&mut self, win: WindowId, clip: Option<Rect>) -> &mut Self {
+        self.push(Op::Clip(win, clip))
+    }
+
+    /// Scrolls a window by the given number of rows.
+    pub fn scroll_by(&mut self, win: WindowId, rows: isize) -> &mut Self {
+        self.push(Op::Scroll(win, rows))
+    }
+
+    /// Sets a cell of a window, relative to its top left.
+    pub fn set_cell(&mut self, win: WindowId, p: Point, value: T) -> &mut Self {
+        self.push(Op::Cell(win, p, value))
+    }
+}
+
+impl<T: fmt::Display> Container<T> {
+    /// Makes several changes to windows at once. The changes are made by `f` through
+    /// a [`Transaction`], and only applied if it returns Ok, so that either all of
+    /// them happen or none do. Positions they uncover are worked out once, when they
+    /// are applied.
+    pub fn transaction<R, E>(
+        &mut self,
+        f: impl FnOnce(&mut Transaction<'_, T>) -> Result<R, E>,
+    ) -> Result<R, E>
+    where
+        T: Clone,
+    {
+        let (result, ops) = {
+            let mut tx = Transaction {
+                container: self,
+                ops: Vec::new(),
+            };
+            (f(&mut tx)?, tx.ops)
+        };
+
+        let changed: HashSet<WindowId> = ops.iter().map(Op::win).collect();
+        for &win in changed.iter() {
+            self.mark_dirty(self.windows[win].footprint().collect::<Vec<_>>());
+        }
+
+        for op in ops {
+            match op {
+                Op::Move(win, p) => self.windows[win].top_left = p,
+                Op::Resize(win, wid, hgt, fill) => self.windows[win].resize(wid, hgt, fill),
+                Op::Hide(win, hidden) => {
+                    self.windows[win].hidden = hidden;
+                    if hidden && self.focus == Some(win) {
+                        self.focus = None;
+                    }
+                }
+                Op::Title(win, title) => self.windows[win].title = title,
+                Op::Clip(win, clip) => self.windows[win].clip = clip,
+                Op::Scroll(win, rows) => {
+                    self.windows[win].scroll_by(rows);
+                }
+                Op::Cell(win, p, value) => {
+                    self.windows[win].set_cell(p, value);
+                }
+            }
+        }
+
+        Ok(result)
+    }
+}
@@ -0,0 +1,196 @@
+//! Flipping and rotating the contents of windows.
+
+use crate::Window;
+use crate::style::Cell;
+use std::fmt;
+
+/// A way of flipping or rotating a window.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Transform {
+    /// Mirror left to right.
+    FlipH,
+    /// Mirror top to bottom.
+    FlipV,
+    /// Rotate a quarter turn clockwise.
+    RotateCw,
+    /// Rotate a quarter turn anticlockwise.
+    RotateCcw,
+    /// Swap rows and columns, mirroring along the line from the top left to the
+    /// bottom right.
+    Transpose,
+}
+
+/// Values that point in a direction, and so need to change when a window is
+/// transformed for it to look right, such as `/` becoming `\` when flipped.
+pub trait Mirror {
+    /// Returns the value as it looks after the transform.
+    fn mirror(&self, transform: Transform) -> Self;
+}
+
+/// Characters swapped when mirroring left to right.
+const FLIP_H: &[(char, char)] = &[
+    ('/', '\\'),
+    ('(', ')'),
+    ('<', '>'),
+    ('[', ']'),
+    ('{', '}'),
+    ('┌', '┐'),
+    ('└', '┘'),
+    ('├', '┤'),
+    ('╭', '╮'),
+    ('╰', '╯'),
+    ('╔', '╗'),
+    ('╚', '╝'),
+    ('┏', '┓'),
+    ('┗', '┛'),
+    ('▌', '▐'),
+    ('◀', '▶'),
+    ('←', '→'),
+];
+
+/// Characters swapped when mirroring top to bottom.
+const FLIP_V: &[(char, char)] = &[
+    ('/', '\\'),
+    ('^', 'v'),
+    ('┌', '└'),
+    ('┐', '┘'),
+    ('┬', '┴'),
+    ('╭', '╰'),
+    ('╮', '╯'),
+    ('╔', '╚'),
+    ('╗', '╝'),
+    ('┏', '┗'),
+    ('┓', '┛'),
+    ('▀', '▄'),
+    ('▲', '▼'),
+    ('↑', '↓'),
+];
+
+/// Characters swapped when swapping rows and columns.
+const TRANSPOSE: &[(char, char)] = &[
+    ('-', '|'),
+    ('─', '│'),
+    ('━', '┃'),
+    ('═', '║'),
+    ('┐', '└'),
+    ('├', '┬'),
+    ('┤', '┴'),
+    ('╮', '╰'),
+    ('╗', '╚'),
+    ('┓', '┗'),
+    ('<', '^'),
+    ('>', 'v'),
+    ('▀', '▌'),
+    ('▄', '▐'),
+    ('◀', '▲'),
+    ('▶', '▼'),
+    ('←', '↑'),
+    ('→', '↓'),
+];
+
+/// Returns the character paired with `ch`, or `ch` if it has no pair.
+fn swap(ch: char, pairs: &[(char, char)]) -> char {
+    pairs
+        .iter()
+        .find_map(|&(a, b)| {
+            if ch == a {
+                Some(b)
+            } else if ch == b {
+                Some(a)
+            } else {
+                None
+            }
+        })
+        .unwrap_or(ch)
+}
+
+impl Mirror for char {
+    fn mirror(&self, transform: Transform) -> Self {
+        match transform {
+            Transform::FlipH => swap(*self, FLIP_H),
+            Transform::FlipV => swap(*self, FLIP_V),
+            Transform::Transpose => swap(*self, TRANSPOSE),
+            Transform::RotateCw => swap(swap(*self, TRANSPOSE), FLIP_H),
+            Transform::RotateCcw => swap(swap(*self, TRANSPOSE), FLIP_V),
+        }
+    }
+}
+
+impl Mirror for Cell {
+    fn mirror(&self, transform: Transform) -> Self {
+        Cell::new(self.ch.mirror(transform), self.style)
+    }
+}
+
+impl<T: fmt::Display + Clone + Default> Window<T> {
+    /// Returns a copy of the window with its contents transformed, keeping its top
+    /// left. Short rows are first padded with the default value of T.
+    pub fn transformed(&self, transform: Transform) -> Window<T> {
+        let (wid, hgt) = (self.width(), self.data.len());
+        let at = |x: usize, y: usize| self.data[y].get(x).cloned().unwrap_or_default();
+
+        let data = match transform {
+            Transform::FlipH => (0..hgt)
+                .map(|y| (0..wid).rev().map(|x| at(x, y)).collect())
+                .collect(),
+            Transform::FlipV => (0..hgt)
+                .rev()
+                .map(|y| (0..wid).map(|x| at(x, y)).collect())
+                .collect(),
+            Transform::Transpose => (0..wid)
+                .map(|x| (0..hgt).map(|y| at(x, y)).collect())
+                .collect(),
+            Transform::RotateCw => (0..wid)
+                .map(|x| (0..hgt).rev().map(|y| at(x, y)).collect())
+                .collect(),
+            Transform::RotateCcw => (0..wid)
+                .rev()
+                .map(|x| (0..hgt).map(|y| at(x, y)).collect())
+                .collect(),
+        };
+
+        let mut win = self.clone();
+        win.data = data;
+        // Pixels aren't transformed, so they would no longer line up.
+        win.pixels = None;
+        win
+    }
+
+    /// Like [`transformed`](Self::transformed), but also changes each value to look
+    /// right after the transform.
+    pub fn mirrored(&self, transform: Transform) -> Window<T>
+    where
+        T: Mirror,
+    {
+        let mut win = self.transformed(transform);
+        for cell in win.data.iter_mut().flatten() {
+            *cell = cell.mirror(transform);
+        }
+        win
+    }
+
+    /// Returns a copy of the window mirrored left to right.
+    pub fn flip_h(&self) -> Window<T> {
+        self.transformed(Transform::FlipH)
+    }
+
+    /// Returns a copy of the window mirrored top to bottom.
+    pub fn flip_v(&self) -> Window<T> {
+        self.transformed(Transform::FlipV)
+    }
+
+    /// Returns a copy of the window rotated a quarter turn clockwise.
+    pub fn rotate_cw(&self) -> Window<T> {
+        self.transformed(Transform::RotateCw)
+    }
+
+    /// Returns a copy of the window rotated a quarter turn anticlockwise.
+    pub fn rotate_ccw(&self) -> Window<T> {
+        self.transformed(Transform::RotateCcw)
+    }
+
+    /// Returns a copy of the window with its rows and columns swapped.
+    pub fn transpose(&self) -> Window<T> {
+        self.transformed(Transform::Transpose)
+    }
+}
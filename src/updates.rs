@@ -0,0 +1,156 @@
+//! Changes to a container queued from other threads.
+
+use crate::geometry::Point;
+use crate::runtime::RefreshHandle;
+use crate::{Container, Window, WindowId};
+use std::fmt;
+use std::sync::Mutex;
+use std::sync::mpsc::{self, Receiver, Sender};
+
+type EditFn<T> = Box<dyn FnOnce(&mut Window<T>) + Send>;
+
+/// A change queued with [`Updates`].
+enum Update<T: fmt::Display> {
+    Cell(WindowId, Point, T),
+    Replace(WindowId, Window<T>),
+    Edit(WindowId, EditFn<T>),
+    Add(Window<T>),
+}
+
+/// A handle for queueing changes to a container's windows from any thread, without
+/// waiting for a lock. Queued changes are applied in order at the start of the
+/// container's next [`refresh`](Container::refresh), and each one requests a
+/// refresh.
+///
+/// Window ids are those of the container when the change is applied, and changes
+/// to windows that no longer exist are dropped.
+pub struct Updates<T: fmt::Display> {
+    sender: Sender<Update<T>>,
+    refresh: RefreshHandle,
+}
+
+impl<T: fmt::Display> Clone for Updates<T> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+            refresh: self.refresh.clone(),
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Debug for Updates<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Updates").finish_non_exhaustive()
+    }
+}
+
+impl<T: fmt::Display> Updates<T> {
+    /// Queues a change. Returns false if the container no longer exists.
+    fn send(&self, update: Update<T>) -> bool {
+        let sent = self.sender.send(update).is_ok();
+        if sent {
+            self.refresh.request();
+        }
+        sent
+    }
+
+    /// Sets a cell of a window, relative to its top left.
+    pub fn set_cell(&self, win: WindowId, p: Point, value: T) -> bool {
+        self.send(Update::Cell(win, p, value))
+    }
+
+    /// Replaces a window.
+    pub fn replace(&self, win: WindowId, with: Window<T>) -> bool {
+        self.send(Update::Replace(win, with))
+    }
+
+    /// Changes a window with `f`.
+    pub fn edit(&self, win: WindowId, f: impl FnOnce(&mut Window<T>) + Send + 'static) -> bool {
+        self.send(Update::Edit(win, Box::new(f)))
+    }
+
+    /// Adds a window on top of the others.
+    pub fn add(&self, win: Window<T>) -> bool {
+        self.send(Update::Add(win))
+    }
+}
+
+/// The receiving end of a container's queued changes. Each copy of a container has
+/// its own.
+pub(crate) struct Queue<T: fmt::Display> {
+    sender: Sender<Update<T>>,
+    // Behind a mutex only so the container can be shared between threads. It is
+    // never locked, as the container has it to itself when applying changes.
+    receiver: Mutex<Receiver<Update<T>>>,
+}
+
+impl<T: fmt::Display> Default for Queue<T> {
+    fn default() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            sender,
+            receiver: Mutex::new(receiver),
+        }
+    }
+}
+
+impl<T: fmt::Display> Clone for Queue<T> {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl<T: fmt::Display> fmt::Debug for Queue<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Queue").finish_non_exhaustive()
+    }
+}
+
+impl<T: fmt::Display> Container<T> {
+    /// Returns a handle for queueing changes to the container from other threads.
+    pub fn updates(&self) -> Updates<T> {
+        Updates {
+            sender: self.updates.sender.clone(),
+            refresh: self.refresh_handle(),
+        }
+    }
+
+    /// Applies the changes queued with [`Updates`] so far, returning how many there
+    /// were. This is done by [`refresh`](Self::refresh).
+    pub fn apply_updates(&mut self) -> usize {
+        let queued: Vec<Update<T>> = self
+            .updates
+            .receiver
+            .get_mut()
+            .unwrap_or_else(|e| e.into_inner())
+            .try_iter()
+            .collect();
+        let count = queued.len();
+
+        for update in queued {
+            match update {
+                Update::Cell(win, p, value) => {
+                    if let Some(win) = self.windows.get_mut(win) {
+                        win.set_cell(p, value);
+                    }
+                }
+                Update::Replace(win, with) => {
+                    if let Some(old) = self.windows.get_mut(win) {
+                        let old = std::mem::replace(old, with);
+                        self.mark_dirty(old.footprint().collect::<Vec<_>>());
+                    }
+                }
+                Update::Edit(win, f) => {
+                    if win < self.windows.len() {
+                        self.mark_dirty(self.windows[win].footprint().collect::<Vec<_>>());
+                        f(&mut self.windows[win]);
+                    }
+                }
+                Update::Add(win) => {
+                    self.add_win(win);
+                }
+            }
+        }
+        count
+    }
+}
@@ -0,0 +1,165 @@
+//! Borrowed handles onto a rectangle of a window, with positions relative to the
+//! top left of the rectangle.
+
+use crate::geometry::{Coord, Point};
+use crate::style::Cell;
+use crate::widgets::Widget;
+use crate::{Rect, Window};
+use std::fmt;
+
+/// A rectangle of a window that can be read from. Created with [`Window::view`].
+#[derive(Debug)]
+pub struct WindowView<'a, T: fmt::Display> {
+    win: &'a Window<T>,
+    rect: Rect,
+}
+
+/// A rectangle of a window that can be drawn into. Created with
+/// [`Window::view_mut`].
+#[derive(Debug)]
+pub struct WindowViewMut<'a, T: fmt::Display> {
+    win: &'a mut Window<T>,
+    rect: Rect,
+}
+
+/// Returns the position in the window of a position in a view, or None if it is
+/// outside the view.
+fn locate(rect: Rect, p: Point) -> Option<Point> {
+    let p = p + rect.top_left;
+    rect.contains(p).then_some(p)
+}
+
+/// Returns a rectangle given relative to a view relative to the window instead, cut
+/// down to the view.
+fn sub_rect(rect: Rect, sub: Rect) -> Rect {
+    let x = sub.top_left.x.clamp(0, rect.wid as Coord);
+    let y = sub.top_left.y.clamp(0, rect.hgt as Coord);
+    let right = (sub.top_left.x + sub.wid as Coord).clamp(x, rect.wid as Coord);
+    let bottom = (sub.top_left.y + sub.hgt as Coord).clamp(y, rect.hgt as Coord);
+    Rect::new(
+        Point::new(x, y) + rect.top_left,
+        (right - x) as usize,
+        (bottom - y) as usize,
+    )
+}
+
+impl<T: fmt::Display> Window<T> {
+    /// Returns a view of a rectangle of the window's data.
+    pub fn view(&self, rect: Rect) -> WindowView<'_, T> {
+        WindowView { win: self, rect }
+    }
+
+    /// Returns a view of a rectangle of the window's data that can be drawn into.
+    pub fn view_mut(&mut self, rect: Rect) -> WindowViewMut<'_, T> {
+        WindowViewMut { win: self, rect }
+    }
+}
+
+impl<'a, T: fmt::Display> WindowView<'a, T> {
+    /// Returns the width of the view.
+    pub fn width(&self) -> usize {
+        self.rect.wid
+    }
+
+    /// Returns the height of the view.
+    pub fn height(&self) -> usize {
+        self.rect.hgt
+    }
+
+    /// Returns the rectangle of the window the view covers.
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    /// Returns the cell at a position in the view, if there is one.
+    pub fn get(&self, p: Point) -> Option<&'a T> {
+        let p = locate(self.rect, p)?;
+        self.win.data.get(p.y as usize)?.get(p.x as usize)
+    }
+
+    /// Returns a view of a rectangle within this one, given relative to this one.
+    pub fn view(&self, rect: Rect) -> WindowView<'a, T> {
+        WindowView {
+            win: self.win,
+            rect: sub_rect(self.rect, rect),
+        }
+    }
+}
+
+impl<T: fmt::Display> WindowViewMut<'_, T> {
+    /// Returns the width of the view.
+    pub fn width(&self) -> usize {
+        self.rect.wid
+    }
+
+    /// Returns the height of the view.
+    pub fn height(&self) -> usize {
+        self.rect.hgt
+    }
+
+    /// Returns the rectangle of the window the view covers.
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    /// Returns the cell at a position in the view, if there is one.
+    pub fn get(&self, p: Point) -> Option<&T> {
+        let p = locate(self.rect, p)?;
+        self.win.data.get(p.y as usize)?.get(p.x as usize)
+    }
+
+    /// Sets the cell at a position in the view, returning false if there isn't one.
+    pub fn set(&mut self, p: Point, value: T) -> bool {
+        match locate(self.rect, p) {
+            Some(p) => self.win.set_cell(p, value),
+            None => false,
+        }
+    }
+
+    /// Sets every cell of the view.
+    pub fn fill(&mut self, value: T)
+    where
+        T: Clone,
+    {
+        let rect = self.rect;
+        self.win.draw_filled_rect(rect, value);
+    }
+
+    /// Copies the contents of a window into the view with its top left at `at`,
+    /// leaving out any that fall outside the view.
+    pub fn blit(&mut self, src: &Window<T>, at: Point)
+    where
+        T: Clone,
+    {
+        for (y, row) in src.data.iter().enumerate() {
+            for (x, value) in row.iter().enumerate() {
+                self.set(Point::new(x as Coord, y as Coord) + at, value.clone());
+            }
+        }
+    }
+
+    /// Returns a view of a rectangle within this one, given relative to this one.
+    pub fn view_mut(&mut self, rect: Rect) -> WindowViewMut<'_, T> {
+        WindowViewMut {
+            rect: sub_rect(self.rect, rect),
+            win: self.win,
+        }
+    }
+
+    /// Returns a read only view of the same rectangle.
+    pub fn as_view(&self) -> WindowView<'_, T> {
+        WindowView {
+            win: self.win,
+            rect: self.rect,
+        }
+    }
+}
+
+impl WindowViewMut<'_, Cell> {
+    /// Draws a widget filling the view.
+    pub fn render(&mut self, widget: &dyn Widget) {
+        let mut win = Window::new(Point::new(0, 0));
+        widget.render(&mut win, self.rect.wid, self.rect.hgt);
+        self.blit(&win, Point::new(0, 0));
+    }
+}
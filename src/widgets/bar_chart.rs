@@ -0,0 +1,190 @@
+use super::sparkline::BARS;
+use super::{Alignment, Widget, blank, put_truncated};
+use crate::Window;
+use crate::style::{Cell, Color, Style};
+
+/// A labelled group of bars in a [`BarChart`], one for each series.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BarGroup {
+    pub label: String,
+    pub values: Vec<f64>,
+}
+
+impl BarGroup {
+    /// Create a group with a value for each series.
+    pub fn new(label: impl Into<String>, values: impl IntoIterator<Item = f64>) -> Self {
+        Self {
+            label: label.into(),
+            values: values.into_iter().collect(),
+        }
+    }
+}
+
+/// Vertical bars scaled to fit the height of the window, with labels underneath and
+/// values above. Bars can be grouped, with each series in the group drawn in its own
+/// style.
+#[derive(Clone, Debug)]
+pub struct BarChart {
+    groups: Vec<BarGroup>,
+    series_styles: Vec<Style>,
+    style: Style,
+    bar_wid: usize,
+    bar_gap: usize,
+    group_gap: usize,
+    max: Option<f64>,
+    show_values: bool,
+}
+
+impl BarChart {
+    /// Create a chart with a single series of labelled values.
+    pub fn new<S: Into<String>>(data: impl IntoIterator<Item = (S, f64)>) -> Self {
+        Self::grouped(
+            data.into_iter()
+                .map(|(label, value)| BarGroup::new(label, [value])),
+        )
+    }
+
+    /// Create a chart of groups of bars.
+    pub fn grouped(groups: impl IntoIterator<Item = BarGroup>) -> Self {
+        Self {
+            groups: groups.into_iter().collect(),
+            series_styles: vec![
+                Style::new().fg(Color::Cyan),
+                Style::new().fg(Color::Magenta),
+                Style::new().fg(Color::Yellow),
+                Style::new().fg(Color::Green),
+            ],
+            style: Style::new(),
+            bar_wid: 3,
+            bar_gap: 1,
+            group_gap: 2,
+            max: None,
+            show_values: true,
+        }
+    }
+
+    /// Sets the style of each series. Series past the end use the last style.
+    pub fn series_styles(mut self, styles: impl IntoIterator<Item = Style>) -> Self {
+        self.series_styles = styles.into_iter().collect();
+        self
+    }
+
+    /// Sets the style of the labels and empty space.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets the width of each bar.
+    pub fn bar_width(mut self, wid: usize) -> Self {
+        self.bar_wid = wid.max(1);
+        self
+    }
+
+    /// Sets the space between bars in a group and between groups.
+    pub fn gaps(mut self, bar_gap: usize, group_gap: usize) -> Self {
+        self.bar_gap = bar_gap;
+        self.group_gap = group_gap;
+        self
+    }
+
+    /// Sets the value of a bar that fills the chart. If None, the largest value is
+    /// used.
+    pub fn max(mut self, max: Option<f64>) -> Self {
+        self.max = max;
+        self
+    }
+
+    /// Sets whether values are written above their bars.
+    pub fn show_values(mut self, show: bool) -> Self {
+        self.show_values = show;
+        self
+    }
+
+    fn series_style(&self, series: usize) -> Style {
+        self.series_styles
+            .get(series)
+            .or(self.series_styles.last())
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+/// Formats a value as briefly as possible.
+fn format_value(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{value:.0}")
+    } else {
+        format!("{value:.1}")
+    }
+}
+
+impl Widget for BarChart {
+    fn render(&self, win: &mut Window<Cell>, wid: usize, hgt: usize) {
+        let mut data = blank(wid, hgt, self.style);
+        // Leave the bottom row for labels.
+        let bar_hgt = hgt.saturating_sub(1);
+        let max = self.max.unwrap_or_else(|| {
+            self.groups
+                .iter()
+                .flat_map(|g| g.values.iter().copied())
+                .fold(0.0, f64::max)
+        });
+
+        let mut x = 0;
+        for group in self.groups.iter() {
+            let group_wid = group.values.len() * self.bar_wid
+                + group.values.len().saturating_sub(1) * self.bar_gap;
+
+            if let Some(row) = data.last_mut() {
+                let len = group.label.chars().count().min(group_wid);
+                let label_x = x + Alignment::Center.offset(len, group_wid);
+                put_truncated(row, label_x, len, &group.label, self.style);
+            }
+
+            for (series, &value) in group.values.iter().enumerate() {
+                let style = self.style.patch(self.series_style(series));
+                let ratio = if max > 0.0 {
+                    (value / max).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+                let eighths = (ratio * (bar_hgt * 8) as f64).round() as usize;
+
+                for y in 0..bar_hgt {
+                    let fill = eighths.saturating_sub(y * 8).min(8);
+                    if fill == 0 {
+                        continue;
+                    }
+                    let row = &mut data[bar_hgt - 1 - y];
+                    for cell in row.iter_mut().skip(x).take(self.bar_wid) {
+                        *cell = Cell::new(BARS[fill - 1], style);
+                    }
+                }
+
+                if self.show_values && bar_hgt > 0 {
+                    // Put the value just above the bar, or on its top row if it is full.
+                    let full_rows = eighths.div_ceil(8);
+                    let text = format_value(value);
+                    let (y, value_style) = if full_rows < bar_hgt {
+                        (bar_hgt - 1 - full_rows, style)
+                    } else {
+                        (0, style.reversed())
+                    };
+                    let len = text.chars().count().min(self.bar_wid);
+                    let value_x = x + Alignment::Center.offset(len, self.bar_wid);
+                    put_truncated(&mut data[y], value_x, len, &text, value_style);
+                }
+
+                x += self.bar_wid + self.bar_gap;
+            }
+
+            if !group.values.is_empty() {
+                x -= self.bar_gap;
+            }
+            x += self.group_gap;
+        }
+
+        win.data = data;
+    }
+}
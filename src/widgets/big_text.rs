@@ -0,0 +1,335 @@
+use super::{Alignment, Widget, blank};
+use crate::Window;
+use crate::style::{Cell, Style};
+use std::collections::HashMap;
+#[cfg(feature = "figlet")]
+use std::{fs, io, path::Path};
+
+/// Glyphs of the built in font, where `#` is filled in.
+const STANDARD: &[(char, [&str; 5])] = &[
+    ('A', [".#.", "#.#", "###", "#.#", "#.#"]),
+    ('B', ["##.", "#.#", "##.", "#.#", "##."]),
+    ('C', [".##", "#..", "#..", "#..", ".##"]),
+    ('D', ["##.", "#.#", "#.#", "#.#", "##."]),
+    ('E', ["###", "#..", "##.", "#..", "###"]),
+    ('F', ["###", "#..", "##.", "#..", "#.."]),
+    ('G', [".##", "#..", "#.#", "#.#", ".##"]),
+    ('H', ["#.#", "#.#", "###", "#.#", "#.#"]),
+    ('I', ["###", ".#.", ".#.", ".#.", "###"]),
+    ('J', ["..#", "..#", "..#", "#.#", ".#."]),
+    ('K', ["#.#", "#.#", "##.", "#.#", "#.#"]),
+    ('L', ["#..", "#..", "#..", "#..", "###"]),
+    ('M', ["#...#", "##.##", "#.#.#", "#...#", "#...#"]),
+    ('N', ["#..#", "##.#", "#.##", "#..#", "#..#"]),
+    ('O', [".#.", "#.#", "#.#", "#.#", ".#."]),
+    ('P', ["##.", "#.#", "##.", "#..", "#.."]),
+    ('Q', [".#.", "#.#", "#.#", "##.", ".##"]),
+    ('R', ["##.", "#.#", "##.", "#.#", "#.#"]),
+    ('S', [".##", "#..", ".#.", "..#", "##."]),
+    ('T', ["###", ".#.", ".#.", ".#.", ".#."]),
+    ('U', ["#.#", "#.#", "#.#", "#.#", "###"]),
+    ('V', ["#.#", "#.#", "#.#", "#.#", ".#."]),
+    ('W', ["#...#", "#...#", "#.#.#", "##.##", "#...#"]),
+    ('X', ["#.#", "#.#", ".#.", "#.#", "#.#"]),
+    ('Y', ["#.#", "#.#", ".#.", ".#.", ".#."]),
+    ('Z', ["###", "..#", ".#.", "#..", "###"]),
+    ('0', ["###", "#.#", "#.#", "#.#", "###"]),
+    ('1', [".#.", "##.", ".#.", ".#.", "###"]),
+    ('2', ["##.", "..#", ".#.", "#..", "###"]),
+    ('3', ["##.", "..#", ".#.", "..#", "##."]),
+    ('4', ["#.#", "#.#", "###", "..#", "..#"]),
+    ('5', ["###", "#..", "##.", "..#", "##."]),
+    ('6', [".##", "#..", "###", "#.#", "###"]),
+    ('7', ["###", "..#", ".#.", ".#.", ".#."]),
+    ('8', ["###", "#.#", "###", "#.#", "###"]),
+    ('9', ["###", "#.#", "###", "..#", "##."]),
+    (' ', ["..", "..", "..", "..", ".."]),
+    ('!', ["#", "#", "#", ".", "#"]),
+    ('?', ["##.", "..#", ".#.", "...", ".#."]),
+    ('.', [".", ".", ".", ".", "#"]),
+    (',', ["..", "..", "..", ".#", "#."]),
+    (':', [".", "#", ".", "#", "."]),
+    ('-', ["...", "...", "###", "...", "..."]),
+    ('+', ["...", ".#.", "###", ".#.", "..."]),
+    ('=', ["...", "###", "...", "###", "..."]),
+    ('_', ["...", "...", "...", "...", "###"]),
+    ('/', ["..#", "..#", ".#.", "#..", "#.."]),
+    ('\'', ["#", "#", ".", ".", "."]),
+    ('(', [".#", "#.", "#.", "#.", ".#"]),
+    (')', ["#.", ".#", ".#", ".#", "#."]),
+];
+
+/// Characters each several rows tall, used to draw large text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Font {
+    height: usize,
+    // Columns left between characters.
+    spacing: usize,
+    glyphs: HashMap<char, Vec<Vec<char>>>,
+}
+
+impl Default for Font {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+impl Font {
+    /// Returns the built in font, five rows tall and drawn with full blocks. It only
+    /// has capital letters, so lowercase ones are drawn as capitals.
+    pub fn standard() -> Self {
+        let glyphs = STANDARD
+            .iter()
+            .map(|(ch, rows)| {
+                let rows = rows
+                    .iter()
+                    .map(|row| {
+                        row.chars()
+                            .map(|c| if c == '#' { '█' } else { ' ' })
+                            .collect()
+                    })
+                    .collect();
+                (*ch, rows)
+            })
+            .collect();
+
+        Self {
+            height: 5,
+            spacing: 1,
+            glyphs,
+        }
+    }
+
+    /// Parses a FIGlet font from the contents of a `.flf` file. Characters are placed
+    /// side by side without smushing them together.
+    #[cfg(feature = "figlet")]
+    pub fn from_flf(text: &str) -> io::Result<Self> {
+        let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidData, msg.to_string());
+        let mut lines = text.lines();
+
+        let header = lines.next().ok_or_else(|| invalid("empty font"))?;
+        let Some(rest) = header.strip_prefix("flf2a") else {
+            return Err(invalid("not a FIGlet font"));
+        };
+        let mut chars = rest.chars();
+        let hardblank = chars.next().ok_or_else(|| invalid("missing hardblank"))?;
+        let params: Vec<usize> = chars
+            .as_str()
+            .split_whitespace()
+            .map_while(|p| p.parse().ok())
+            .collect();
+        let (Some(&height), Some(&comments)) = (params.first(), params.get(4)) else {
+            return Err(invalid("incomplete header"));
+        };
+
+        let mut lines = lines.skip(comments);
+        let read_glyph = |lines: &mut dyn Iterator<Item = &str>| -> Option<Vec<Vec<char>>> {
+            (0..height)
+                .map(|_| {
+                    let line = lines.next()?;
+                    // Each line ends with one or more copies of an end mark.
+                    let end = line.chars().last()?;
+                    let row = line.trim_end_matches(end);
+                    Some(
+                        row.chars()
+                            .map(|c| if c == hardblank { ' ' } else { c })
+                            .collect(),
+                    )
+                })
+                .collect()
+        };
+
+        let mut glyphs = HashMap::new();
+        // Printable ASCII followed by seven German characters are always given.
+        let required = (' '..='~').chain(['Ä', 'Ö', 'Ü', 'ä', 'ö', 'ü', 'ß']);
+        for ch in required {
+            match read_glyph(&mut lines) {
+                Some(glyph) => glyphs.insert(ch, glyph),
+                None if ch > '~' => break,
+                None => return Err(invalid("missing characters")),
+            };
+        }
+        // Then any others, each after a line giving its code.
+        while let Some(tag) = lines.next() {
+            let code = tag.split_whitespace().next().unwrap_or_default();
+            let code = match code.strip_prefix("0x").or_else(|| code.strip_prefix("0X")) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                None => code.parse().ok(),
+            };
+            let Some(glyph) = read_glyph(&mut lines) else {
+                break;
+            };
+            if let Some(ch) = code.and_then(char::from_u32) {
+                glyphs.insert(ch, glyph);
+            }
+        }
+
+        Ok(Self {
+            height,
+            spacing: 0,
+            glyphs,
+        })
+    }
+
+    /// Reads a FIGlet font from a `.flf` file.
+    #[cfg(feature = "figlet")]
+    pub fn load_flf(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::from_flf(&fs::read_to_string(path)?)
+    }
+
+    /// Returns the number of rows each character takes up.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the rows of a character, falling back on its capital form and then a
+    /// question mark if the font doesn't have it.
+    fn glyph(&self, ch: char) -> Option<&Vec<Vec<char>>> {
+        self.glyphs
+            .get(&ch)
+            .or_else(|| ch.to_uppercase().next().and_then(|c| self.glyphs.get(&c)))
+            .or_else(|| self.glyphs.get(&'?'))
+    }
+
+    /// Returns the rows of some text drawn in this font, ignoring line breaks.
+    pub fn render_line(&self, text: &str) -> Vec<Vec<char>> {
+        let mut rows = vec![Vec::new(); self.height];
+        for (n, glyph) in text.chars().filter_map(|c| self.glyph(c)).enumerate() {
+            let glyph_wid = glyph.iter().map(|r| r.len()).max().unwrap_or(0);
+            for (y, row) in rows.iter_mut().enumerate() {
+                if n > 0 {
+                    row.extend(std::iter::repeat_n(' ', self.spacing));
+                }
+                let part = glyph.get(y).map_or(&[][..], |r| &r[..]);
+                row.extend(part);
+                row.extend(std::iter::repeat_n(' ', glyph_wid - part.len()));
+            }
+        }
+        rows
+    }
+}
+
+/// Text drawn large using a [`Font`], with each line of text taking up several rows.
+#[derive(Clone, Debug, Default)]
+pub struct BigText {
+    text: String,
+    font: Font,
+    style: Style,
+    align: Alignment,
+}
+
+impl BigText {
+    /// Create large text in the built in font.
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the font the text is drawn in.
+    pub fn font(mut self, font: Font) -> Self {
+        self.font = font;
+        self
+    }
+
+    /// Sets the style of the text.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets how each line of text is aligned.
+    pub fn align(mut self, align: Alignment) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Sets the text.
+    pub fn set_text(&mut self, text: impl Into<String>) {
+        self.text = text.into();
+    }
+
+    /// Returns the width and height the text takes up.
+    pub fn size(&self) -> (usize, usize) {
+        let lines: Vec<_> = self
+            .text
+            .lines()
+            .map(|l| self.font.render_line(l))
+            .collect();
+        let wid = lines.iter().flatten().map(|r| r.len()).max().unwrap_or(0);
+        (wid, lines.len() * self.font.height)
+    }
+}
+
+impl Widget for BigText {
+    fn render(&self, win: &mut Window<Cell>, wid: usize, hgt: usize) {
+        let mut data = blank(wid, hgt, self.style);
+        let rows = self.text.lines().flat_map(|l| {
+            let rows = self.font.render_line(l);
+            let len = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+            let x = self.align.offset(len, wid);
+            rows.into_iter().map(move |r| (x, r))
+        });
+
+        for (row, (x, chars)) in data.iter_mut().zip(rows) {
+            for (cell, ch) in row.iter_mut().skip(x).zip(chars) {
+                cell.ch = ch;
+            }
+        }
+
+        win.data = data;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(rows: Vec<Vec<char>>) -> Vec<String> {
+        rows.into_iter().map(|r| r.into_iter().collect()).collect()
+    }
+
+    #[test]
+    fn standard_font() {
+        let font = Font::standard();
+        let shown = rows(font.render_line("hi"));
+        assert_eq!(shown.len(), font.height());
+        assert_eq!(shown, rows(font.render_line("HI")));
+        // Characters the font doesn't have are drawn as question marks.
+        assert_eq!(
+            rows(font.render_line("\u{263A}")),
+            rows(font.render_line("?"))
+        );
+    }
+
+    #[cfg(feature = "figlet")]
+    #[test]
+    fn parse_flf() {
+        let mut flf = String::from("flf2a$ 2 2 4 0 1\na comment\n");
+        for ch in (' '..='~').chain(['Ä', 'Ö', 'Ü', 'ä', 'ö', 'ü', 'ß']) {
+            let end = if ch == '@' { '#' } else { '@' };
+            flf.push_str(&format!("{ch}${end}\n{ch}{end}{end}\n"));
+        }
+        flf.push_str("0x263A  SMILING FACE\n:)@\n(:@@\n");
+        let font = Font::from_flf(&flf).unwrap();
+
+        assert_eq!(font.height(), 2);
+        assert_eq!(rows(font.render_line("a@")), ["a @ ", "a @ "]);
+        assert_eq!(rows(font.render_line("\u{263A}")), [":)", "(:"]);
+    }
+
+    #[cfg(feature = "figlet")]
+    #[test]
+    fn invalid_flf() {
+        for flf in [
+            "",
+            "tlf2a$ 2 2 4 0 1",
+            "flf2a$ 2 2",
+            "flf2a$ 1 1 2 0 0\n @\n!@\n",
+        ] {
+            let err = Font::from_flf(flf).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        }
+    }
+}
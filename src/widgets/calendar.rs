@@ -0,0 +1,271 @@
+use super::{Alignment, Widget, blank, put_str};
+use crate::Window;
+use crate::event::{KeyCode, KeyEvent};
+use crate::style::{Cell, Style, Theme};
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const MONTH_NAMES: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+/// A day in the proleptic Gregorian calendar.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub year: i32,
+    /// Month from 1 to 12.
+    pub month: u8,
+    /// Day of the month from 1.
+    pub day: u8,
+}
+
+impl Date {
+    /// Create a date, returning None if it doesn't exist.
+    pub fn new(year: i32, month: u8, day: u8) -> Option<Self> {
+        let valid = (1..=12).contains(&month) && day >= 1 && day <= days_in_month(year, month);
+        valid.then_some(Self { year, month, day })
+    }
+
+    /// Returns today's date in UTC.
+    pub fn today() -> Self {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        Self::from_days((secs / 86_400) as i64)
+    }
+
+    /// Returns the date the given number of days after 1970-01-01.
+    pub fn from_days(days: i64) -> Self {
+        // Based on Howard Hinnant's civil_from_days.
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u8;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+        let year = (yoe + era * 400 + i64::from(month <= 2)) as i32;
+        Self { year, month, day }
+    }
+
+    /// Returns the number of days after 1970-01-01 this date is.
+    pub fn to_days(self) -> i64 {
+        let year = self.year as i64 - i64::from(self.month <= 2);
+        let era = year.div_euclid(400);
+        let yoe = year.rem_euclid(400);
+        let month = self.month as i64;
+        let doy =
+            (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + self.day as i64 - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146_097 + doe - 719_468
+    }
+
+    /// Returns the day of the week, where Monday is 0 and Sunday is 6.
+    pub fn weekday(self) -> u8 {
+        // 1970-01-01 was a Thursday.
+        (self.to_days() + 3).rem_euclid(7) as u8
+    }
+
+    /// Returns the date the given number of days later, or earlier if negative.
+    pub fn add_days(self, days: i64) -> Self {
+        Self::from_days(self.to_days() + days)
+    }
+
+    /// Returns the same day the given number of months later, or earlier if negative,
+    /// moved back to the end of the month if it doesn't exist.
+    pub fn add_months(self, months: i32) -> Self {
+        let total = self.year * 12 + (self.month as i32 - 1) + months;
+        let year = total.div_euclid(12);
+        let month = (total.rem_euclid(12) + 1) as u8;
+        let day = self.day.min(days_in_month(year, month));
+        Self { year, month, day }
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// A month laid out in weeks starting on Monday, showing the month of the selected
+/// date. Today is underlined and the selected date highlighted.
+#[derive(Clone, Debug)]
+pub struct Calendar {
+    selected: Date,
+    today: Date,
+    style: Style,
+    today_style: Style,
+    highlight: Style,
+}
+
+impl Default for Calendar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Calendar {
+    /// Create a calendar with today selected.
+    pub fn new() -> Self {
+        let today = Date::today();
+        Self {
+            selected: today,
+            today,
+            style: Style::new(),
+            today_style: Style::new().underline().bold(),
+            highlight: Style::new().reversed(),
+        }
+    }
+
+    /// Sets the date treated as today.
+    pub fn today(mut self, today: Date) -> Self {
+        self.today = today;
+        self
+    }
+
+    /// Sets the style of the calendar.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets the style applied on top of today.
+    pub fn today_style(mut self, style: Style) -> Self {
+        self.today_style = style;
+        self
+    }
+
+    /// Sets the style applied on top of the selected date.
+    pub fn highlight_style(mut self, style: Style) -> Self {
+        self.highlight = style;
+        self
+    }
+
+    /// Sets the style and highlight from a theme.
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.style = theme.text;
+        self.highlight = theme.highlight;
+        self
+    }
+
+    /// Returns the selected date.
+    pub fn selected(&self) -> Date {
+        self.selected
+    }
+
+    /// Selects a date, showing its month.
+    pub fn select(&mut self, date: Date) {
+        self.selected = date;
+    }
+
+    /// Shows the next month, keeping the same day selected where possible.
+    pub fn next_month(&mut self) {
+        self.selected = self.selected.add_months(1);
+    }
+
+    /// Shows the previous month, keeping the same day selected where possible.
+    pub fn prev_month(&mut self) {
+        self.selected = self.selected.add_months(-1);
+    }
+
+    /// Moves the selection according to a key press: arrows move by a day or week
+    /// and page up and down by a month. Returns the selected date if enter was
+    /// pressed.
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<Date> {
+        match key.code {
+            KeyCode::Left => self.selected = self.selected.add_days(-1),
+            KeyCode::Right => self.selected = self.selected.add_days(1),
+            KeyCode::Up => self.selected = self.selected.add_days(-7),
+            KeyCode::Down => self.selected = self.selected.add_days(7),
+            KeyCode::PageUp => self.prev_month(),
+            KeyCode::PageDown => self.next_month(),
+            KeyCode::Enter => return Some(self.selected),
+            _ => {}
+        }
+        None
+    }
+
+    /// Returns the first day of the shown month.
+    fn first(&self) -> Date {
+        Date {
+            day: 1,
+            ..self.selected
+        }
+    }
+
+    /// Returns the date drawn at the given position relative to the calendar, if any.
+    pub fn date_at(&self, x: usize, y: usize) -> Option<Date> {
+        // Two header rows, then a row per week of three columns per day.
+        let week = y.checked_sub(2)?;
+        let weekday = x / 3;
+        if weekday >= 7 {
+            return None;
+        }
+        let first = self.first();
+        let offset = (week * 7 + weekday) as i64 - first.weekday() as i64;
+        let date = first.add_days(offset);
+        (date.month == first.month).then_some(date)
+    }
+}
+
+impl Widget for Calendar {
+    fn render(&self, win: &mut Window<Cell>, wid: usize, hgt: usize) {
+        let mut data = blank(wid, hgt, self.style);
+        let first = self.first();
+        let title = format!("{} {}", MONTH_NAMES[first.month as usize - 1], first.year);
+
+        if let Some(row) = data.get_mut(0) {
+            let x = Alignment::Center.offset(title.chars().count(), 20);
+            put_str(row, x, &title, self.style.bold());
+        }
+        if let Some(row) = data.get_mut(1) {
+            put_str(row, 0, "Mo Tu We Th Fr Sa Su", self.style);
+        }
+
+        let days = days_in_month(first.year, first.month);
+        let start = first.weekday() as usize;
+        for day in 1..=days {
+            let pos = start + day as usize - 1;
+            let Some(row) = data.get_mut(2 + pos / 7) else {
+                break;
+            };
+            let date = Date { day, ..first };
+            let mut style = self.style;
+            if date == self.today {
+                style = style.patch(self.today_style);
+            }
+            if date == self.selected {
+                style = style.patch(self.highlight);
+            }
+            put_str(row, (pos % 7) * 3, &format!("{day:>2}"), style);
+        }
+
+        win.data = data;
+    }
+}
@@ -0,0 +1,232 @@
+use super::{Widget, blank, put_str};
+use crate::Window;
+use crate::braille::BrailleGrid;
+use crate::draw::clip_line;
+use crate::style::{Cell, Style};
+
+/// Something drawn on a [`Canvas`], in the canvas's coordinates.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Shape {
+    /// Single dots.
+    Points(Vec<(f64, f64)>),
+    /// A straight line between two points.
+    Line((f64, f64), (f64, f64)),
+    /// Straight lines joining each point to the next.
+    Polyline(Vec<(f64, f64)>),
+    /// Text starting in the cell containing a point.
+    Label((f64, f64), String),
+}
+
+/// Returns bounds that can be shown, widening empty ones by one each way and taking
+/// those that aren't finite as 0 to 1.
+pub(crate) fn span((min, max): (f64, f64)) -> (f64, f64) {
+    if !min.is_finite() || !max.is_finite() {
+        (0.0, 1.0)
+    } else if min == max {
+        (min - 1.0, max + 1.0)
+    } else {
+        (min, max)
+    }
+}
+
+/// Maps coordinates within some bounds to the dots of a grid, with x increasing to the
+/// right and y upwards, and the edges of the bounds on the outermost dots.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Projection {
+    x_bounds: (f64, f64),
+    y_bounds: (f64, f64),
+    // Positions of the last dots across and down.
+    last_x: f64,
+    last_y: f64,
+}
+
+impl Projection {
+    /// Create a projection of the given bounds onto `dots_wid` by `dots_hgt` dots,
+    /// fixing up bounds that can't be shown with [`span`].
+    pub(crate) fn new(
+        x_bounds: (f64, f64),
+        y_bounds: (f64, f64),
+        dots_wid: usize,
+        dots_hgt: usize,
+    ) -> Self {
+        Self {
+            x_bounds: span(x_bounds),
+            y_bounds: span(y_bounds),
+            last_x: dots_wid.saturating_sub(1) as f64,
+            last_y: dots_hgt.saturating_sub(1) as f64,
+        }
+    }
+
+    /// Returns the dot a point falls on, which is off the grid for points outside the
+    /// bounds.
+    pub(crate) fn dot(&self, (x, y): (f64, f64)) -> (i64, i64) {
+        let (x_min, x_max) = self.x_bounds;
+        let (y_min, y_max) = self.y_bounds;
+        let dx = (x - x_min) / (x_max - x_min) * self.last_x;
+        let dy = (y_max - y) / (y_max - y_min) * self.last_y;
+        (dx.round() as i64, dy.round() as i64)
+    }
+
+    /// Sets the dots of the part of the line between two points within the bounds.
+    pub(crate) fn line(&self, grid: &mut BrailleGrid, from: (f64, f64), to: (f64, f64)) {
+        let order = |(a, b): (f64, f64)| (a.min(b), a.max(b));
+        let (x_min, x_max) = order(self.x_bounds);
+        let (y_min, y_max) = order(self.y_bounds);
+        if let Some((from, to)) = clip_line(from, to, (x_min, y_min), (x_max, y_max)) {
+            grid.line(self.dot(from), self.dot(to));
+        }
+    }
+}
+
+/// An area with its own coordinate system, where x increases to the right and y
+/// upwards, on which shapes are drawn with braille dots.
+#[derive(Clone, Debug)]
+pub struct Canvas {
+    x_bounds: (f64, f64),
+    y_bounds: (f64, f64),
+    shapes: Vec<(Shape, Style)>,
+    style: Style,
+}
+
+impl Canvas {
+    /// Create an empty canvas showing the given ranges of x and y.
+    pub fn new(x_bounds: (f64, f64), y_bounds: (f64, f64)) -> Self {
+        Self {
+            x_bounds,
+            y_bounds,
+            shapes: Vec::new(),
+            style: Style::new(),
+        }
+    }
+
+    /// Sets the style of the canvas.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets the range of x values shown.
+    pub fn set_x_bounds(&mut self, bounds: (f64, f64)) {
+        self.x_bounds = bounds;
+    }
+
+    /// Sets the range of y values shown.
+    pub fn set_y_bounds(&mut self, bounds: (f64, f64)) {
+        self.y_bounds = bounds;
+    }
+
+    /// Adds a shape, drawn over those added before.
+    pub fn draw(&mut self, shape: Shape, style: Style) {
+        self.shapes.push((shape, style));
+    }
+
+    /// Draws a single dot.
+    pub fn point(&mut self, p: (f64, f64), style: Style) {
+        self.draw(Shape::Points(vec![p]), style);
+    }
+
+    /// Draws a straight line.
+    pub fn line(&mut self, from: (f64, f64), to: (f64, f64), style: Style) {
+        self.draw(Shape::Line(from, to), style);
+    }
+
+    /// Draws straight lines joining each point to the next.
+    pub fn polyline(&mut self, points: impl IntoIterator<Item = (f64, f64)>, style: Style) {
+        self.draw(Shape::Polyline(points.into_iter().collect()), style);
+    }
+
+    /// Writes text starting in the cell containing a point.
+    pub fn label(&mut self, p: (f64, f64), text: impl Into<String>, style: Style) {
+        self.draw(Shape::Label(p, text.into()), style);
+    }
+
+    /// Removes every shape.
+    pub fn clear(&mut self) {
+        self.shapes.clear();
+    }
+
+    /// Returns the shapes on the canvas and their styles.
+    pub fn shapes(&self) -> &[(Shape, Style)] {
+        &self.shapes
+    }
+}
+
+impl Widget for Canvas {
+    fn render(&self, win: &mut Window<Cell>, wid: usize, hgt: usize) {
+        let mut data = blank(wid, hgt, self.style);
+        if wid == 0 || hgt == 0 {
+            win.data = data;
+            return;
+        }
+
+        let dots = Projection::new(self.x_bounds, self.y_bounds, wid * 2, hgt * 4);
+        let cells = Projection::new(self.x_bounds, self.y_bounds, wid, hgt);
+
+        for (shape, style) in self.shapes.iter() {
+            let style = self.style.patch(*style);
+            let mut grid = BrailleGrid::new(wid, hgt);
+
+            match shape {
+                Shape::Points(points) => {
+                    for &p in points {
+                        let (x, y) = dots.dot(p);
+                        grid.set_dot(x, y);
+                    }
+                }
+                Shape::Line(from, to) => dots.line(&mut grid, *from, *to),
+                Shape::Polyline(points) => {
+                    if let Some(&first) = points.first() {
+                        let (x, y) = dots.dot(first);
+                        grid.set_dot(x, y);
+                    }
+                    for pair in points.windows(2) {
+                        dots.line(&mut grid, pair[0], pair[1]);
+                    }
+                }
+                Shape::Label(p, text) => {
+                    let (x, y) = cells.dot(*p);
+                    if x >= 0
+                        && let Some(row) = usize::try_from(y).ok().and_then(|y| data.get_mut(y))
+                    {
+                        put_str(row, x as usize, text, style);
+                    }
+                    continue;
+                }
+            }
+
+            for (y, row) in data.iter_mut().enumerate() {
+                for (x, cell) in row.iter_mut().enumerate() {
+                    if let Some(ch) = grid.char_at(x, y) {
+                        // Combine with the dots of shapes drawn before.
+                        let prev = (cell.ch as u32).wrapping_sub(0x2800);
+                        let bits = if prev <= 0xff { prev } else { 0 };
+                        let ch = char::from_u32(ch as u32 | bits).unwrap_or(ch);
+                        *cell = Cell::new(ch, style);
+                    }
+                }
+            }
+        }
+
+        win.data = data;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn far_off_and_empty_bounds() {
+        let mut win = Window::new(crate::Point::new(0, 0));
+        let mut canvas = Canvas::new((0.0, 10.0), (0.0, 10.0));
+        canvas.line((0.0, 0.0), (1e8, 5.0), Style::new());
+        canvas.line((f64::MIN, 0.0), (f64::MAX, 0.0), Style::new());
+        canvas.render(&mut win, 20, 10);
+        assert_ne!(win.data[9][0].ch, ' ');
+
+        let mut canvas = Canvas::new((3.0, 3.0), (0.0, 0.0));
+        canvas.line((0.0, 0.0), (6.0, 0.0), Style::new());
+        canvas.render(&mut win, 20, 10);
+        assert_eq!(win.data.len(), 10);
+    }
+}
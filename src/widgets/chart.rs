@@ -0,0 +1,210 @@
+use super::{Widget, blank, put_str};
+use crate::Window;
+use crate::braille::BrailleGrid;
+use crate::style::{Cell, Style};
+use crate::widgets::canvas::{Projection, span};
+
+/// A named series of points plotted by a [`Chart`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Dataset {
+    pub name: String,
+    pub points: Vec<(f64, f64)>,
+    pub style: Style,
+}
+
+impl Dataset {
+    /// Create a dataset from some points.
+    pub fn new(name: impl Into<String>, points: impl IntoIterator<Item = (f64, f64)>) -> Self {
+        Self {
+            name: name.into(),
+            points: points.into_iter().collect(),
+            style: Style::new(),
+        }
+    }
+
+    /// Sets the style the dataset is drawn in.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+}
+
+/// Lines through one or more datasets, drawn with braille dots for finer detail,
+/// along with axes labelled at their ends and middle.
+#[derive(Clone, Debug, Default)]
+pub struct Chart {
+    datasets: Vec<Dataset>,
+    x_bounds: Option<(f64, f64)>,
+    y_bounds: Option<(f64, f64)>,
+    style: Style,
+    axis_style: Style,
+}
+
+impl Chart {
+    /// Create a chart of the given datasets, with bounds fitted to the data.
+    pub fn new(datasets: impl IntoIterator<Item = Dataset>) -> Self {
+        Self {
+            datasets: datasets.into_iter().collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the range of x values shown. If None, it is fitted to the data.
+    pub fn x_bounds(mut self, bounds: Option<(f64, f64)>) -> Self {
+        self.x_bounds = bounds;
+        self
+    }
+
+    /// Sets the range of y values shown. If None, it is fitted to the data.
+    pub fn y_bounds(mut self, bounds: Option<(f64, f64)>) -> Self {
+        self.y_bounds = bounds;
+        self
+    }
+
+    /// Sets the style of the chart.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets the style of the axes and their labels.
+    pub fn axis_style(mut self, style: Style) -> Self {
+        self.axis_style = style;
+        self
+    }
+
+    /// Returns the datasets of the chart.
+    pub fn datasets_mut(&mut self) -> &mut Vec<Dataset> {
+        &mut self.datasets
+    }
+
+    /// Returns the smallest and largest value picked out of every point, widened if
+    /// they are equal so that the range isn't empty.
+    fn fit(&self, value: impl Fn(&(f64, f64)) -> f64) -> (f64, f64) {
+        let (min, max) = self
+            .datasets
+            .iter()
+            .flat_map(|d| d.points.iter())
+            .map(value)
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| {
+                (min.min(v), max.max(v))
+            });
+
+        if min > max {
+            (0.0, 1.0)
+        } else if min == max {
+            (min - 1.0, max + 1.0)
+        } else {
+            (min, max)
+        }
+    }
+}
+
+/// Formats a tick label as briefly as possible.
+fn label(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e9 {
+        format!("{value:.0}")
+    } else {
+        format!("{value:.2}")
+    }
+}
+
+impl Widget for Chart {
+    fn render(&self, win: &mut Window<Cell>, wid: usize, hgt: usize) {
+        let mut data = blank(wid, hgt, self.style);
+        let (x_min, x_max) = span(self.x_bounds.unwrap_or_else(|| self.fit(|p| p.0)));
+        let (y_min, y_max) = span(self.y_bounds.unwrap_or_else(|| self.fit(|p| p.1)));
+        let axis_style = self.style.patch(self.axis_style);
+
+        let y_labels = [label(y_max), label((y_min + y_max) / 2.0), label(y_min)];
+        let label_wid = y_labels
+            .iter()
+            .map(|l| l.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        // The plot sits right of the y labels and axis, and above the x axis and labels.
+        let plot_x = label_wid + 1;
+        let plot_wid = wid.saturating_sub(plot_x);
+        let plot_hgt = hgt.saturating_sub(2);
+        if plot_wid == 0 || plot_hgt == 0 {
+            win.data = data;
+            return;
+        }
+
+        for (row, text) in [0, plot_hgt / 2, plot_hgt - 1].into_iter().zip(&y_labels) {
+            let len = text.chars().count();
+            put_str(&mut data[row], label_wid - len, text, axis_style);
+        }
+        for row in data.iter_mut().take(plot_hgt) {
+            put_str(row, label_wid, "│", axis_style);
+        }
+        let axis = format!("└{}", "─".repeat(plot_wid));
+        put_str(&mut data[plot_hgt], label_wid, &axis, axis_style);
+
+        let x_labels = [label(x_min), label((x_min + x_max) / 2.0), label(x_max)];
+        let x_row = &mut data[plot_hgt + 1];
+        put_str(x_row, plot_x, &x_labels[0], axis_style);
+        let mid_len = x_labels[1].chars().count();
+        put_str(
+            x_row,
+            plot_x + (plot_wid.saturating_sub(mid_len)) / 2,
+            &x_labels[1],
+            axis_style,
+        );
+        let end_len = x_labels[2].chars().count();
+        put_str(x_row, wid.saturating_sub(end_len), &x_labels[2], axis_style);
+
+        let dots = Projection::new((x_min, x_max), (y_min, y_max), plot_wid * 2, plot_hgt * 4);
+
+        for dataset in self.datasets.iter() {
+            let mut grid = BrailleGrid::new(plot_wid, plot_hgt);
+            if let Some(&first) = dataset.points.first() {
+                let (x, y) = dots.dot(first);
+                grid.set_dot(x, y);
+            }
+            // Only the parts of lines within the bounds are drawn, however far off
+            // their ends are.
+            for pair in dataset.points.windows(2) {
+                dots.line(&mut grid, pair[0], pair[1]);
+            }
+
+            let style = self.style.patch(dataset.style);
+            for (y, row) in data.iter_mut().take(plot_hgt).enumerate() {
+                for x in 0..plot_wid {
+                    if let Some(ch) = grid.char_at(x, y) {
+                        // Combine with the dots of datasets drawn before.
+                        let cell = &mut row[plot_x + x];
+                        let prev = (cell.ch as u32).wrapping_sub(0x2800);
+                        let bits = if prev <= 0xff { prev } else { 0 };
+                        let ch = char::from_u32(ch as u32 | bits).unwrap_or(ch);
+                        *cell = Cell::new(ch, style);
+                    }
+                }
+            }
+        }
+
+        win.data = data;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn outliers_and_empty_bounds() {
+        let mut win = Window::new(crate::Point::new(0, 0));
+        let data = Dataset::new("spike", [(0.0, 0.0), (1.0, 1e9), (2.0, 50.0)]);
+        Chart::new([data.clone()])
+            .y_bounds(Some((0.0, 100.0)))
+            .render(&mut win, 30, 10);
+        assert_eq!(win.data.len(), 10);
+
+        Chart::new([data])
+            .x_bounds(Some((1.0, 1.0)))
+            .y_bounds(Some((5.0, 5.0)))
+            .render(&mut win, 30, 10);
+        assert_eq!(win.data.len(), 10);
+    }
+}
@@ -0,0 +1,226 @@
+use super::{Alignment, Paragraph, blank, put_str};
+use crate::event::{Event, KeyCode, MouseButton, MouseKind, WindowEvent};
+use crate::geometry::{Coord, Point};
+use crate::style::{Cell, Style};
+use crate::{Container, Window, WindowId};
+
+/// Widest the text of a dialog is allowed to be before it wraps.
+const MAX_TEXT_WID: usize = 60;
+
+/// A bordered message with a row of buttons, centered on the screen on top of every
+/// window other than notifications. The dialog is pushed onto the container's
+/// [modal stack](Container::push_modal), so input only reaches it until one of its
+/// buttons is chosen. A dialog opened from another stacks on top of it.
+///
+/// Pass events to [`handle_event`](Self::handle_event). When a button is chosen the
+/// dialog's window is removed, input and focus go back to where they were before it
+/// was shown, and a [`WindowEvent::DialogClosed`] is posted to the container.
+#[derive(Clone, Debug)]
+pub struct Dialog {
+    // Entry of the container's modal stack holding the dialog's window, which keeps
+    // track of its id as other windows come and go.
+    modal: u64,
+    buttons: Vec<String>,
+    selected: usize,
+    // Columns each button covers, relative to the window.
+    button_cols: Vec<(usize, usize)>,
+    style: Style,
+}
+
+impl Dialog {
+    /// Show a dialog with the given title, text and buttons. The first button is
+    /// selected to begin with.
+    pub fn message(
+        container: &mut Container<Cell>,
+        title: &str,
+        text: &str,
+        buttons: &[&str],
+    ) -> Self {
+        Self::with_style(container, title, text, buttons, Style::new())
+    }
+
+    /// Show a dialog asking a question, with an OK button and a Cancel button.
+    pub fn confirm(container: &mut Container<Cell>, title: &str, text: &str) -> Self {
+        Self::message(container, title, text, &["OK", "Cancel"])
+    }
+
+    /// Show a dialog drawn in the given style.
+    pub fn with_style(
+        container: &mut Container<Cell>,
+        title: &str,
+        text: &str,
+        buttons: &[&str],
+        style: Style,
+    ) -> Self {
+        let mut dialog = Self {
+            modal: 0,
+            buttons: buttons.iter().map(|b| b.to_string()).collect(),
+            selected: 0,
+            button_cols: Vec::new(),
+            style,
+        };
+
+        let win = dialog.build(container, title, text);
+        let win = container.add_win(win);
+        dialog.modal = container.start_modal(win, None);
+        dialog
+    }
+
+    /// Returns the id of the dialog's window, or None once it has closed or its
+    /// window has been removed.
+    pub fn window(&self, container: &Container<Cell>) -> Option<WindowId> {
+        container.modal_window(self.modal)
+    }
+
+    /// Returns the index of the selected button.
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Draws the dialog into a new window centered on the screen.
+    fn build(&mut self, container: &Container<Cell>, title: &str, text: &str) -> Window<Cell> {
+        let (screen_wid, screen_hgt) = container.screen_size();
+        let buttons_wid = self
+            .buttons
+            .iter()
+            .map(|b| b.chars().count() + 4)
+            .sum::<usize>()
+            + 2 * self.buttons.len().saturating_sub(1);
+        let text_wid = text
+            .lines()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0)
+            .min(MAX_TEXT_WID)
+            .min((screen_wid as usize).saturating_sub(6));
+        let inner = text_wid.max(buttons_wid).max(title.chars().count() + 2) + 2;
+
+        let lines = Paragraph::new([text]).wrap(true).lines(inner - 2);
+        let hgt = lines.len() + 4;
+        let mut data = blank(inner + 2, hgt, self.style);
+
+        let top = format!(
+            "┌─{}{}┐",
+            title,
+            "─".repeat(inner - 1 - title.chars().count())
+        );
+        put_str(&mut data[0], 0, &top, self.style);
+        put_str(
+            &mut data[hgt - 1],
+            0,
+            &format!("└{}┘", "─".repeat(inner)),
+            self.style,
+        );
+        for row in data[1..hgt - 1].iter_mut() {
+            put_str(row, 0, "│", self.style);
+            put_str(row, inner + 1, "│", self.style);
+        }
+
+        for (row, line) in data[1..].iter_mut().zip(lines) {
+            for (cell, ch) in row[2..].iter_mut().zip(line) {
+                cell.ch = ch.ch;
+            }
+        }
+
+        let mut x = 1 + Alignment::Center.offset(buttons_wid, inner);
+        self.button_cols.clear();
+        for button in self.buttons.iter() {
+            let end = x + button.chars().count() + 4;
+            self.button_cols.push((x, end));
+            x = end + 2;
+        }
+        self.draw_buttons(&mut data[hgt - 2]);
+
+        let x = (screen_wid as Coord - data[0].len() as Coord) / 2;
+        let y = (screen_hgt as Coord - hgt as Coord) / 2;
+        let mut win = Window::new(Point::new(x.max(0), y.max(0)));
+        win.data = data;
+        win
+    }
+
+    /// Draws the row of buttons, highlighting the selected one.
+    fn draw_buttons(&self, row: &mut [Cell]) {
+        for (idx, (button, &(start, _))) in self.buttons.iter().zip(&self.button_cols).enumerate() {
+            let style = if idx == self.selected {
+                self.style.reversed()
+            } else {
+                self.style
+            };
+            put_str(row, start, &format!("[ {button} ]"), style);
+        }
+    }
+
+    /// Removes the dialog's window and reports the chosen button.
+    fn choose(&mut self, button: usize, container: &mut Container<Cell>) -> Option<usize> {
+        let win = container.end_modal(self.modal)?;
+        container.remove_win(win);
+        container.post(Event::Window(WindowEvent::DialogClosed { win, button }));
+        Some(button)
+    }
+
+    /// Handles an event, returning the index of the chosen button if one was chosen.
+    /// Left, right and tab move between buttons, enter chooses the selected button
+    /// and escape chooses the last one.
+    pub fn handle_event(
+        &mut self,
+        event: &Event,
+        container: &mut Container<Cell>,
+    ) -> Option<usize> {
+        let id = self.window(container)?;
+        let len = self.buttons.len();
+        match event {
+            Event::Key(key) => match key.code {
+                KeyCode::Left | KeyCode::BackTab if len > 0 => {
+                    self.selected = (self.selected + len - 1) % len;
+                }
+                KeyCode::Right | KeyCode::Tab if len > 0 => {
+                    self.selected = (self.selected + 1) % len;
+                }
+                KeyCode::Enter => return self.choose(self.selected, container),
+                KeyCode::Esc => return self.choose(len.saturating_sub(1), container),
+                _ => return None,
+            },
+            Event::Mouse(ev) if ev.kind == MouseKind::Down(MouseButton::Left) => {
+                let win = &container.windows[id];
+                let Point { x, y } = win.to_local(ev.pos)?;
+                if y != win.height() as Coord - 2 {
+                    return None;
+                }
+                let button = self
+                    .button_cols
+                    .iter()
+                    .position(|&(start, end)| x >= start as Coord && x < end as Coord)?;
+                return self.choose(button, container);
+            }
+            _ => return None,
+        }
+
+        let win = &mut container.windows[id];
+        let row = win.data.len() - 2;
+        self.draw_buttons(&mut win.data[row]);
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::KeyEvent;
+
+    #[test]
+    fn follows_its_window_when_one_below_is_removed() {
+        let mut container: Container<Cell> = Container::new();
+        container.set_screen_size(40, 12);
+        let below = container.add_win(Window::new(Point::new(0, 0)));
+        let mut dialog = Dialog::confirm(&mut container, "Quit", "Are you sure?");
+        container.remove_win(below);
+        assert_eq!(dialog.window(&container), Some(0));
+
+        let right = Event::Key(KeyEvent::from(KeyCode::Right));
+        assert_eq!(dialog.handle_event(&right, &mut container), None);
+        let enter = Event::Key(KeyEvent::from(KeyCode::Enter));
+        assert_eq!(dialog.handle_event(&enter, &mut container), Some(1));
+        assert!(container.windows.is_empty());
+        assert_eq!(dialog.window(&container), None);
+    }
+}
@@ -0,0 +1,118 @@
+use super::{Widget, blank, put_str};
+use crate::Window;
+use crate::style::{Cell, Color, Style};
+
+/// A labelled horizontal meter for a value between a minimum and maximum, coloured
+/// according to which threshold the value is under.
+#[derive(Clone, Debug)]
+pub struct Gauge {
+    label: String,
+    value: f64,
+    min: f64,
+    max: f64,
+    // Upper bounds as ratios of the range, and the colour used below each.
+    thresholds: Vec<(f64, Color)>,
+    above: Color,
+    style: Style,
+    bar: char,
+}
+
+impl Gauge {
+    /// Create a gauge for values between 0 and 100, which is green below 70, yellow
+    /// below 90 and red above.
+    pub fn new(label: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            value: 0.0,
+            min: 0.0,
+            max: 100.0,
+            thresholds: vec![(0.7, Color::Green), (0.9, Color::Yellow)],
+            above: Color::Red,
+            style: Style::new(),
+            bar: '|',
+        }
+    }
+
+    /// Sets the range of values.
+    pub fn range(mut self, min: f64, max: f64) -> Self {
+        self.min = min;
+        self.max = max;
+        self
+    }
+
+    /// Sets the thresholds as ratios of the range along with the colour used below
+    /// each, and the colour used above all of them.
+    pub fn thresholds(
+        mut self,
+        thresholds: impl IntoIterator<Item = (f64, Color)>,
+        above: Color,
+    ) -> Self {
+        self.thresholds = thresholds.into_iter().collect();
+        self.thresholds.sort_by(|a, b| a.0.total_cmp(&b.0));
+        self.above = above;
+        self
+    }
+
+    /// Sets the style of the gauge.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets the character the meter is drawn with.
+    pub fn bar(mut self, bar: char) -> Self {
+        self.bar = bar;
+        self
+    }
+
+    /// Sets the value shown.
+    pub fn set_value(&mut self, value: f64) {
+        self.value = value;
+    }
+
+    /// Returns the value shown.
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// Returns how far the value is through the range, between 0 and 1.
+    pub fn ratio(&self) -> f64 {
+        let span = self.max - self.min;
+        if span <= 0.0 {
+            return 0.0;
+        }
+        ((self.value - self.min) / span).clamp(0.0, 1.0)
+    }
+
+    /// Returns the colour of the meter for the current value.
+    pub fn color(&self) -> Color {
+        let ratio = self.ratio();
+        self.thresholds
+            .iter()
+            .find(|(limit, _)| ratio < *limit)
+            .map_or(self.above, |(_, color)| *color)
+    }
+}
+
+impl Widget for Gauge {
+    fn render(&self, win: &mut Window<Cell>, wid: usize, hgt: usize) {
+        let mut data = blank(wid, hgt, self.style);
+
+        if let Some(row) = data.get_mut(hgt / 2) {
+            let percent = format!("{:>4.0}%", self.ratio() * 100.0);
+            let x = put_str(row, 0, &self.label, self.style);
+            let x = put_str(row, x, " [", self.style);
+
+            // Leave space for the closing bracket and the percentage.
+            let bar_wid = wid.saturating_sub(x + 1 + percent.len());
+            let filled = (self.ratio() * bar_wid as f64).round() as usize;
+            let bar: String = std::iter::repeat_n(self.bar, filled).collect();
+            put_str(row, x, &bar, self.style.fg(self.color()));
+
+            let x = put_str(row, x + bar_wid, "]", self.style);
+            put_str(row, x, &percent, self.style);
+        }
+
+        win.data = data;
+    }
+}
@@ -0,0 +1,157 @@
+use super::{Widget, blank, put_str, put_truncated};
+use crate::Window;
+use crate::style::{Cell, Color, Style};
+
+/// A grid of values shown as background colours picked from a colour ramp, with
+/// optional labels for the rows and columns.
+#[derive(Clone, Debug)]
+pub struct Heatmap {
+    values: Vec<Vec<f64>>,
+    ramp: Vec<(u8, u8, u8)>,
+    range: Option<(f64, f64)>,
+    row_labels: Vec<String>,
+    col_labels: Vec<String>,
+    cell_wid: usize,
+    style: Style,
+}
+
+impl Heatmap {
+    /// Create a heatmap of rows of values, using a ramp from blue through green and
+    /// yellow to red.
+    pub fn new(values: Vec<Vec<f64>>) -> Self {
+        Self {
+            values,
+            ramp: vec![(0, 0, 255), (0, 200, 0), (255, 220, 0), (255, 0, 0)],
+            range: None,
+            row_labels: Vec::new(),
+            col_labels: Vec::new(),
+            cell_wid: 2,
+            style: Style::new(),
+        }
+    }
+
+    /// Sets the colours values are mapped onto, from lowest to highest. Values between
+    /// two colours get a colour between them.
+    pub fn ramp(mut self, ramp: impl IntoIterator<Item = (u8, u8, u8)>) -> Self {
+        self.ramp = ramp.into_iter().collect();
+        self
+    }
+
+    /// Sets the values mapped to the ends of the ramp. If None, it is fitted to the
+    /// values.
+    pub fn range(mut self, range: Option<(f64, f64)>) -> Self {
+        self.range = range;
+        self
+    }
+
+    /// Sets labels shown left of each row.
+    pub fn row_labels<S: Into<String>>(mut self, labels: impl IntoIterator<Item = S>) -> Self {
+        self.row_labels = labels.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets labels shown above each column, cut off to the width of a column.
+    pub fn col_labels<S: Into<String>>(mut self, labels: impl IntoIterator<Item = S>) -> Self {
+        self.col_labels = labels.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets how many cells wide each value is.
+    pub fn cell_width(mut self, wid: usize) -> Self {
+        self.cell_wid = wid.max(1);
+        self
+    }
+
+    /// Sets the style of the labels and empty space.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Replaces the values.
+    pub fn set_values(&mut self, values: Vec<Vec<f64>>) {
+        self.values = values;
+    }
+
+    /// Returns the values mapped to the ends of the ramp.
+    pub fn bounds(&self) -> (f64, f64) {
+        self.range.unwrap_or_else(|| {
+            self.values
+                .iter()
+                .flatten()
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &v| {
+                    (min.min(v), max.max(v))
+                })
+        })
+    }
+
+    /// Returns the colour of the given value.
+    pub fn color(&self, value: f64) -> Color {
+        self.color_within(value, self.bounds())
+    }
+
+    /// Returns the colour of the given value when the ends of the ramp are `min` and
+    /// `max`.
+    fn color_within(&self, value: f64, (min, max): (f64, f64)) -> Color {
+        let ratio = if max > min {
+            ((value - min) / (max - min)).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+
+        match self.ramp.len() {
+            0 => Color::White,
+            1 => {
+                let (r, g, b) = self.ramp[0];
+                Color::Rgb(r, g, b)
+            }
+            len => {
+                let pos = ratio * (len - 1) as f64;
+                let idx = (pos.floor() as usize).min(len - 2);
+                let t = pos - idx as f64;
+                let (a, b) = (self.ramp[idx], self.ramp[idx + 1]);
+                let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+                Color::Rgb(mix(a.0, b.0), mix(a.1, b.1), mix(a.2, b.2))
+            }
+        }
+    }
+}
+
+impl Widget for Heatmap {
+    fn render(&self, win: &mut Window<Cell>, wid: usize, hgt: usize) {
+        let mut data = blank(wid, hgt, self.style);
+        let label_wid = self
+            .row_labels
+            .iter()
+            .map(|l| l.chars().count() + 1)
+            .max()
+            .unwrap_or(0);
+        let top = usize::from(!self.col_labels.is_empty());
+        let bounds = self.bounds();
+
+        if let Some(row) = data.first_mut().filter(|_| top > 0) {
+            for (col, label) in self.col_labels.iter().enumerate() {
+                let x = label_wid + col * self.cell_wid;
+                put_truncated(row, x, self.cell_wid, label, self.style);
+            }
+        }
+
+        for (y, values) in self.values.iter().enumerate() {
+            let Some(row) = data.get_mut(y + top) else {
+                break;
+            };
+            if let Some(label) = self.row_labels.get(y) {
+                put_str(row, 0, label, self.style);
+            }
+            for (col, &value) in values.iter().enumerate() {
+                let style = self.style.bg(self.color_within(value, bounds));
+                let x = label_wid + col * self.cell_wid;
+                for cell in row.iter_mut().skip(x).take(self.cell_wid) {
+                    *cell = Cell::new(' ', style);
+                }
+            }
+        }
+
+        win.data = data;
+    }
+}
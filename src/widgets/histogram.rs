@@ -0,0 +1,169 @@
+use super::sparkline::BARS;
+use super::{Widget, blank, put_str};
+use crate::Window;
+use crate::style::{Cell, Color, Style};
+
+/// The distribution of a stream of samples, drawn as a vertical bar per bucket with
+/// the range of the samples labelled underneath.
+#[derive(Clone, Debug)]
+pub struct Histogram {
+    samples: Vec<f64>,
+    capacity: Option<usize>,
+    buckets: usize,
+    range: Option<(f64, f64)>,
+    log_scale: bool,
+    style: Style,
+    bar_style: Style,
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self::new(10)
+    }
+}
+
+impl Histogram {
+    /// Create an empty histogram with the given number of buckets.
+    pub fn new(buckets: usize) -> Self {
+        Self {
+            samples: Vec::new(),
+            capacity: None,
+            buckets: buckets.max(1),
+            range: None,
+            log_scale: false,
+            style: Style::new(),
+            bar_style: Style::new().fg(Color::Cyan),
+        }
+    }
+
+    /// Sets the most samples kept, dropping the oldest when more are added. If None,
+    /// every sample is kept.
+    pub fn capacity(mut self, capacity: Option<usize>) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Sets the range the buckets cover. If None, it is fitted to the samples.
+    /// Samples outside the range are counted in the first or last bucket.
+    pub fn range(mut self, range: Option<(f64, f64)>) -> Self {
+        self.range = range;
+        self
+    }
+
+    /// Sets whether bar heights are proportional to the logarithm of their counts,
+    /// which keeps small buckets visible next to large ones.
+    pub fn log_scale(mut self, log_scale: bool) -> Self {
+        self.log_scale = log_scale;
+        self
+    }
+
+    /// Sets the style of the histogram.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets the style of the bars.
+    pub fn bar_style(mut self, style: Style) -> Self {
+        self.bar_style = style;
+        self
+    }
+
+    /// Adds a sample.
+    pub fn push(&mut self, sample: f64) {
+        self.samples.push(sample);
+        if let Some(capacity) = self.capacity
+            && self.samples.len() > capacity
+        {
+            let extra = self.samples.len() - capacity;
+            self.samples.drain(..extra);
+        }
+    }
+
+    /// Adds several samples.
+    pub fn extend(&mut self, samples: impl IntoIterator<Item = f64>) {
+        for sample in samples {
+            self.push(sample);
+        }
+    }
+
+    /// Removes every sample.
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    /// Returns the range the buckets cover.
+    pub fn bounds(&self) -> (f64, f64) {
+        if let Some(range) = self.range {
+            return range;
+        }
+        let min = self.samples.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = self
+            .samples
+            .iter()
+            .copied()
+            .fold(f64::NEG_INFINITY, f64::max);
+        if min > max {
+            (0.0, 1.0)
+        } else if min == max {
+            (min - 0.5, max + 0.5)
+        } else {
+            (min, max)
+        }
+    }
+
+    /// Returns the number of samples in each bucket.
+    pub fn counts(&self) -> Vec<usize> {
+        let (min, max) = self.bounds();
+        let mut counts = vec![0; self.buckets];
+        for &sample in self.samples.iter() {
+            let pos = ((sample - min) / (max - min) * self.buckets as f64).floor();
+            let idx = (pos.max(0.0) as usize).min(self.buckets - 1);
+            counts[idx] += 1;
+        }
+        counts
+    }
+}
+
+impl Widget for Histogram {
+    fn render(&self, win: &mut Window<Cell>, wid: usize, hgt: usize) {
+        let mut data = blank(wid, hgt, self.style);
+        let bar_hgt = hgt.saturating_sub(1);
+        let counts = self.counts();
+        let scale = |count: usize| {
+            if self.log_scale {
+                (count as f64).ln_1p()
+            } else {
+                count as f64
+            }
+        };
+        let max = counts.iter().map(|&c| scale(c)).fold(0.0, f64::max);
+        let style = self.style.patch(self.bar_style);
+
+        if max > 0.0 {
+            for (x, bucket) in (0..wid).map(|x| (x, x * self.buckets / wid)) {
+                let eighths = (scale(counts[bucket]) / max * (bar_hgt * 8) as f64).round() as usize;
+                for y in 0..bar_hgt {
+                    let fill = eighths.saturating_sub(y * 8).min(8);
+                    if fill > 0 {
+                        data[bar_hgt - 1 - y][x] = Cell::new(BARS[fill - 1], style);
+                    }
+                }
+            }
+        }
+
+        if let Some(row) = data.last_mut() {
+            let (min, max) = self.bounds();
+            let (min, max) = (format!("{min:.1}"), format!("{max:.1}"));
+            put_str(row, 0, &min, self.style);
+            put_str(
+                row,
+                wid.saturating_sub(max.chars().count()),
+                &max,
+                self.style,
+            );
+        }
+
+        win.data = data;
+    }
+}
@@ -0,0 +1,256 @@
+use super::{Widget, blank};
+use crate::Window;
+use crate::event::{Event, KeyCode, KeyEvent};
+use crate::geometry::{Coord, Point};
+use crate::style::{Cell, Style};
+
+/// An editable single line of text with a cursor. Long values scroll horizontally to
+/// keep the cursor visible.
+#[derive(Clone, Debug, Default)]
+pub struct Input {
+    value: Vec<char>,
+    // Index of the character the cursor is before.
+    cursor: usize,
+    mask: Option<char>,
+    style: Style,
+    cursor_style: Style,
+    offset: std::cell::Cell<usize>,
+}
+
+impl Input {
+    /// Create an empty input.
+    pub fn new() -> Self {
+        Self {
+            cursor_style: Style::new().reversed(),
+            ..Default::default()
+        }
+    }
+
+    /// Sets a character shown in place of every character of the value, such as for
+    /// passwords.
+    pub fn mask(mut self, mask: Option<char>) -> Self {
+        self.mask = mask;
+        self
+    }
+
+    /// Sets the style of the input.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets the style applied on top of the cell the cursor is on.
+    pub fn cursor_style(mut self, style: Style) -> Self {
+        self.cursor_style = style;
+        self
+    }
+
+    /// Returns the value.
+    pub fn value(&self) -> String {
+        self.value.iter().collect()
+    }
+
+    /// Replaces the value, moving the cursor to the end.
+    pub fn set_value(&mut self, value: &str) {
+        self.value = value.chars().collect();
+        self.cursor = self.value.len();
+    }
+
+    /// Removes the whole value.
+    pub fn clear(&mut self) {
+        self.value.clear();
+        self.cursor = 0;
+    }
+
+    /// Returns the index of the character the cursor is before.
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Moves the cursor before the character at the given index, or to the end.
+    pub fn set_cursor(&mut self, cursor: usize) {
+        self.cursor = cursor.min(self.value.len());
+    }
+
+    /// Inserts a character at the cursor.
+    pub fn insert(&mut self, ch: char) {
+        self.value.insert(self.cursor, ch);
+        self.cursor += 1;
+    }
+
+    /// Inserts text at the cursor. Line breaks are left out.
+    pub fn insert_str(&mut self, s: &str) {
+        for ch in s.chars().filter(|ch| *ch != '\n' && *ch != '\r') {
+            self.insert(ch);
+        }
+    }
+
+    /// Removes the character before the cursor.
+    pub fn backspace(&mut self) {
+        if self.cursor > 0 {
+            self.cursor -= 1;
+            self.value.remove(self.cursor);
+        }
+    }
+
+    /// Removes the character after the cursor.
+    pub fn delete(&mut self) {
+        if self.cursor < self.value.len() {
+            self.value.remove(self.cursor);
+        }
+    }
+
+    /// Moves the cursor one character left.
+    pub fn left(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    /// Moves the cursor one character right.
+    pub fn right(&mut self) {
+        self.cursor = (self.cursor + 1).min(self.value.len());
+    }
+
+    /// Moves the cursor to the start.
+    pub fn home(&mut self) {
+        self.cursor = 0;
+    }
+
+    /// Moves the cursor to the end.
+    pub fn end(&mut self) {
+        self.cursor = self.value.len();
+    }
+
+    /// Returns the index of the start of the word before the cursor.
+    fn word_start(&self) -> usize {
+        let mut idx = self.cursor;
+        while idx > 0 && self.value[idx - 1].is_whitespace() {
+            idx -= 1;
+        }
+        while idx > 0 && !self.value[idx - 1].is_whitespace() {
+            idx -= 1;
+        }
+        idx
+    }
+
+    /// Returns the index of the end of the word after the cursor.
+    fn word_end(&self) -> usize {
+        let mut idx = self.cursor;
+        while idx < self.value.len() && self.value[idx].is_whitespace() {
+            idx += 1;
+        }
+        while idx < self.value.len() && !self.value[idx].is_whitespace() {
+            idx += 1;
+        }
+        idx
+    }
+
+    /// Moves the cursor to the start of the word before it.
+    pub fn word_left(&mut self) {
+        self.cursor = self.word_start();
+    }
+
+    /// Moves the cursor to the end of the word after it.
+    pub fn word_right(&mut self) {
+        self.cursor = self.word_end();
+    }
+
+    /// Removes the word before the cursor.
+    pub fn delete_word_back(&mut self) {
+        let start = self.word_start();
+        self.value.drain(start..self.cursor);
+        self.cursor = start;
+    }
+
+    /// Removes the word after the cursor.
+    pub fn delete_word_forward(&mut self) {
+        let end = self.word_end();
+        self.value.drain(self.cursor..end);
+    }
+
+    /// Edits the value according to a key press, using the usual readline bindings
+    /// for control keys. Returns true if the key was used.
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        let mods = key.modifiers;
+        match key.code {
+            KeyCode::Char(ch) if mods.ctrl => match ch {
+                'a' => self.home(),
+                'e' => self.end(),
+                'b' => self.left(),
+                'f' => self.right(),
+                'h' => self.backspace(),
+                'd' => self.delete(),
+                'w' => self.delete_word_back(),
+                'u' => {
+                    self.value.drain(..self.cursor);
+                    self.cursor = 0;
+                }
+                'k' => {
+                    self.value.truncate(self.cursor);
+                }
+                _ => return false,
+            },
+            KeyCode::Char('b') if mods.alt => self.word_left(),
+            KeyCode::Char('f') if mods.alt => self.word_right(),
+            KeyCode::Char('d') if mods.alt => self.delete_word_forward(),
+            KeyCode::Char(ch) if !mods.alt => self.insert(ch),
+            KeyCode::Backspace if mods.ctrl || mods.alt => self.delete_word_back(),
+            KeyCode::Backspace => self.backspace(),
+            KeyCode::Delete if mods.ctrl => self.delete_word_forward(),
+            KeyCode::Delete => self.delete(),
+            KeyCode::Left if mods.ctrl || mods.alt => self.word_left(),
+            KeyCode::Left => self.left(),
+            KeyCode::Right if mods.ctrl || mods.alt => self.word_right(),
+            KeyCode::Right => self.right(),
+            KeyCode::Home => self.home(),
+            KeyCode::End => self.end(),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Edits the value according to a key press or paste. Returns true if the event
+    /// was used.
+    pub fn handle_event(&mut self, event: &Event) -> bool {
+        match event {
+            Event::Key(key) => self.handle_key(*key),
+            Event::Paste(text) => {
+                self.insert_str(text);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the column the cursor is drawn at in an input `wid` columns wide,
+    /// scrolling as little as possible from last time to keep it visible.
+    pub fn cursor_column(&self, wid: usize) -> usize {
+        let mut offset = self.offset.get();
+        if self.cursor < offset {
+            offset = self.cursor;
+        } else if wid > 0 && self.cursor >= offset + wid {
+            offset = self.cursor + 1 - wid;
+        }
+        self.offset.set(offset);
+        self.cursor - offset
+    }
+}
+
+impl Widget for Input {
+    fn render(&self, win: &mut Window<Cell>, wid: usize, hgt: usize) {
+        let mut data = blank(wid, hgt, self.style);
+        let cursor_x = self.cursor_column(wid);
+        let offset = self.offset.get();
+
+        if let Some(row) = data.first_mut() {
+            for (cell, ch) in row.iter_mut().zip(self.value.iter().skip(offset)) {
+                cell.ch = self.mask.unwrap_or(*ch);
+            }
+            if let Some(cell) = row.get_mut(cursor_x) {
+                cell.style = cell.style.patch(self.cursor_style);
+            }
+        }
+
+        win.data = data;
+        win.cursor = (cursor_x < wid && hgt > 0).then(|| Point::new(cursor_x as Coord, 0));
+    }
+}
@@ -0,0 +1,145 @@
+use super::{Widget, blank, put_str, scroll_to};
+use crate::Window;
+use crate::style::{Cell, Span, Style, Theme};
+
+/// A vertical list of items, one of which may be selected. The list scrolls to keep
+/// the selected item visible.
+#[derive(Clone, Debug, Default)]
+pub struct List {
+    items: Vec<Span>,
+    selected: Option<usize>,
+    style: Style,
+    highlight: Style,
+    marker: String,
+    // Index of the first item shown, kept between renders so the list only scrolls
+    // when the selection leaves the visible part.
+    offset: std::cell::Cell<usize>,
+}
+
+impl List {
+    /// Create a list with the given items and nothing selected.
+    pub fn new<S: Into<Span>>(items: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            items: items.into_iter().map(Into::into).collect(),
+            highlight: Style::new().reversed(),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the style of the space not covered by items.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets the style applied on top of the selected item.
+    pub fn highlight_style(mut self, style: Style) -> Self {
+        self.highlight = style;
+        self
+    }
+
+    /// Sets the style and highlight from a theme.
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.style = theme.text;
+        self.highlight = theme.highlight;
+        self
+    }
+
+    /// Sets text shown before the selected item. Other items are indented to match.
+    pub fn marker(mut self, marker: &str) -> Self {
+        self.marker = marker.to_string();
+        self
+    }
+
+    /// Returns the items of the list.
+    pub fn items(&self) -> &[Span] {
+        &self.items
+    }
+
+    /// Replaces the items of the list, keeping the selection within them.
+    pub fn set_items<S: Into<Span>>(&mut self, items: impl IntoIterator<Item = S>) {
+        self.items = items.into_iter().map(Into::into).collect();
+        self.select(self.selected);
+    }
+
+    /// Number of items in the list.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns true if the list has no items.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Returns the index of the selected item, if any.
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    /// Selects the item at the given index, or the last item if it is past the end.
+    pub fn select(&mut self, idx: Option<usize>) {
+        self.selected = match idx {
+            Some(_) if self.items.is_empty() => None,
+            Some(idx) => Some(idx.min(self.items.len() - 1)),
+            None => None,
+        };
+    }
+
+    /// Selects the item after the selected one, staying on the last item.
+    pub fn select_next(&mut self) {
+        self.select(Some(self.selected.map_or(0, |idx| idx + 1)));
+    }
+
+    /// Selects the item before the selected one, staying on the first item.
+    pub fn select_prev(&mut self) {
+        self.select(Some(self.selected.map_or(0, |idx| idx.saturating_sub(1))));
+    }
+
+    /// Selects the first item.
+    pub fn select_first(&mut self) {
+        self.select(Some(0));
+    }
+
+    /// Selects the last item.
+    pub fn select_last(&mut self) {
+        self.select(Some(self.items.len().saturating_sub(1)));
+    }
+
+    /// Returns the index of the first item shown in a list `hgt` rows tall, scrolled
+    /// as little as possible from last time to show the selection.
+    fn offset(&self, hgt: usize) -> usize {
+        let offset = scroll_to(self.offset.get(), self.selected, self.items.len(), hgt);
+        self.offset.set(offset);
+        offset
+    }
+}
+
+impl Widget for List {
+    fn render(&self, win: &mut Window<Cell>, wid: usize, hgt: usize) {
+        let mut data = blank(wid, hgt, self.style);
+        let offset = self.offset(hgt);
+        let indent = " ".repeat(self.marker.chars().count());
+
+        for (idx, (row, item)) in data
+            .iter_mut()
+            .zip(self.items.iter().skip(offset))
+            .enumerate()
+        {
+            let selected = self.selected == Some(idx + offset);
+            let (marker, base) = if selected {
+                (&self.marker, self.style.patch(self.highlight))
+            } else {
+                (&indent, self.style)
+            };
+
+            for cell in row.iter_mut() {
+                cell.style = base;
+            }
+            let x = put_str(row, 0, marker, base);
+            put_str(row, x, &item.text, base.patch(item.style));
+        }
+
+        win.data = data;
+    }
+}
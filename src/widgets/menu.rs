@@ -0,0 +1,426 @@
+use super::{Widget, blank, put_str};
+use crate::event::{Event, KeyCode, MouseButton, MouseKind};
+use crate::geometry::{Coord, Point};
+use crate::style::{Cell, Style, Theme};
+use crate::{Container, TrackedId, Window, WindowId};
+
+/// An entry in a dropdown [`Menu`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MenuEntry {
+    /// Something that can be chosen, unless it is disabled.
+    Item { label: String, enabled: bool },
+    /// A line between groups of items.
+    Separator,
+}
+
+impl MenuEntry {
+    /// Create an enabled item.
+    pub fn item(label: impl Into<String>) -> Self {
+        MenuEntry::Item {
+            label: label.into(),
+            enabled: true,
+        }
+    }
+
+    /// Create a disabled item.
+    pub fn disabled(label: impl Into<String>) -> Self {
+        MenuEntry::Item {
+            label: label.into(),
+            enabled: false,
+        }
+    }
+
+    /// Returns true if the entry can be chosen.
+    pub fn is_enabled(&self) -> bool {
+        matches!(self, MenuEntry::Item { enabled: true, .. })
+    }
+}
+
+/// A titled dropdown menu of a [`MenuBar`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Menu {
+    pub title: String,
+    pub entries: Vec<MenuEntry>,
+}
+
+impl Menu {
+    /// Create a menu with the given entries.
+    pub fn new(title: impl Into<String>, entries: impl IntoIterator<Item = MenuEntry>) -> Self {
+        Self {
+            title: title.into(),
+            entries: entries.into_iter().collect(),
+        }
+    }
+}
+
+/// What a [`MenuBar`] did with an event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MenuResponse {
+    /// The event had nothing to do with the menus.
+    Ignored,
+    /// The event was used by the menus.
+    Handled,
+    /// The item at index `item` of the menu at index `menu` was chosen.
+    Selected { menu: usize, item: usize },
+}
+
+/// A row of menu titles, each of which opens a dropdown menu. The dropdowns are
+/// windows added to and removed from the container as menus are opened and closed.
+///
+/// Render the bar itself into a window at the position given to [`at`](Self::at),
+/// and pass events to [`handle_event`](Self::handle_event). Menus are opened by
+/// clicking their title, pressing F10, or pressing alt and the first letter of their
+/// title.
+#[derive(Clone, Debug)]
+pub struct MenuBar {
+    menus: Vec<Menu>,
+    top_left: Point,
+    open: Option<usize>,
+    // Index of the highlighted entry of the open menu.
+    highlighted: usize,
+    // Dropdown window of the open menu, tracked as other windows come and go.
+    popup: Option<TrackedId>,
+    style: Style,
+    highlight: Style,
+    disabled: Style,
+}
+
+impl MenuBar {
+    /// Create a menu bar with the given menus, at the top left of the screen.
+    pub fn new(menus: impl IntoIterator<Item = Menu>) -> Self {
+        Self {
+            menus: menus.into_iter().collect(),
+            top_left: Point::new(0, 0),
+            style: Style::new().reversed(),
+            highlight: Style::new(),
+            disabled: Style::new().reversed().italic(),
+            open: None,
+            highlighted: 0,
+            popup: None,
+        }
+    }
+
+    /// Sets where the window the bar is rendered into is.
+    pub fn at(mut self, top_left: Point) -> Self {
+        self.top_left = top_left;
+        self
+    }
+
+    /// Sets the style of the bar and dropdowns.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets the style of the open title and highlighted item.
+    pub fn highlight_style(mut self, style: Style) -> Self {
+        self.highlight = style;
+        self
+    }
+
+    /// Sets the style of disabled items.
+    pub fn disabled_style(mut self, style: Style) -> Self {
+        self.disabled = style;
+        self
+    }
+
+    /// Sets the style, highlight and style of disabled items from a theme.
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.style = theme.text;
+        self.highlight = theme.highlight;
+        self.disabled = theme.muted;
+        self
+    }
+
+    /// Returns the menus of the bar.
+    pub fn menus(&self) -> &[Menu] {
+        &self.menus
+    }
+
+    /// Returns the index of the open menu, if any.
+    pub fn open_menu(&self) -> Option<usize> {
+        self.open
+    }
+
+    /// Returns the id of the window showing the open menu, if any.
+    pub fn popup(&self, container: &Container<Cell>) -> Option<WindowId> {
+        container.tracked(self.popup?)
+    }
+
+    /// Returns the column each title starts at, relative to the bar.
+    fn title_columns(&self) -> Vec<usize> {
+        let mut x = 0;
+        self.menus
+            .iter()
+            .map(|menu| {
+                let start = x;
+                x += menu.title.chars().count() + 2;
+                start
+            })
+            .collect()
+    }
+
+    /// Opens the menu at the given index, highlighting its first enabled entry.
+    pub fn open(&mut self, menu: usize, container: &mut Container<Cell>) {
+        if menu >= self.menus.len() {
+            return;
+        }
+        self.open = Some(menu);
+        self.highlighted = self.menus[menu]
+            .entries
+            .iter()
+            .position(MenuEntry::is_enabled)
+            .unwrap_or(0);
+        self.sync(container);
+    }
+
+    /// Closes the open menu, if any.
+    pub fn close(&mut self, container: &mut Container<Cell>) {
+        self.open = None;
+        self.sync(container);
+    }
+
+    /// Moves the highlight to the next enabled entry in the given direction.
+    fn move_highlight(&mut self, forward: bool) {
+        let Some(menu) = self.open else {
+            return;
+        };
+        let entries = &self.menus[menu].entries;
+        let len = entries.len();
+        for step in 1..=len {
+            let idx = if forward {
+                (self.highlighted + step) % len
+            } else {
+                (self.highlighted + len * step - step) % len
+            };
+            if entries[idx].is_enabled() {
+                self.highlighted = idx;
+                return;
+            }
+        }
+    }
+
+    /// Draws the open menu into its dropdown window, adding or removing the window as
+    /// needed.
+    fn sync(&mut self, container: &mut Container<Cell>) {
+        let Some(menu) = self.open else {
+            if let Some(popup) = self.popup.take().and_then(|p| container.untrack(p)) {
+                container.remove_win(popup);
+            }
+            return;
+        };
+
+        let entries = &self.menus[menu].entries;
+        let inner = entries
+            .iter()
+            .map(|entry| match entry {
+                MenuEntry::Item { label, .. } => label.chars().count() + 2,
+                MenuEntry::Separator => 0,
+            })
+            .max()
+            .unwrap_or(0);
+        let wid = inner + 2;
+        let mut data = blank(wid, entries.len() + 2, self.style);
+
+        let last = data.len() - 1;
+        put_str(
+            &mut data[0],
+            0,
+            &format!("┌{}┐", "─".repeat(inner)),
+            self.style,
+        );
+        put_str(
+            &mut data[last],
+            0,
+            &format!("└{}┘", "─".repeat(inner)),
+            self.style,
+        );
+        for (idx, entry) in entries.iter().enumerate() {
+            let row = &mut data[idx + 1];
+            match entry {
+                MenuEntry::Separator => {
+                    put_str(row, 0, &format!("├{}┤", "─".repeat(inner)), self.style);
+                }
+                MenuEntry::Item { label, enabled } => {
+                    let style = if idx == self.highlighted {
+                        self.highlight
+                    } else if *enabled {
+                        self.style
+                    } else {
+                        self.disabled
+                    };
+                    put_str(row, 0, "│", self.style);
+                    for cell in row[1..=inner].iter_mut() {
+                        cell.style = style;
+                    }
+                    put_str(row, 2, label, style);
+                    put_str(row, inner + 1, "│", self.style);
+                }
+            }
+        }
+
+        let x = self.title_columns()[menu] as Coord;
+        let mut win = Window::new(Point::new(self.top_left.x + x, self.top_left.y + 1));
+        win.data = data;
+        win.inset = 1;
+
+        match self.popup(container) {
+            Some(popup) => {
+                container.mark_dirty(container.windows[popup].footprint().collect::<Vec<_>>());
+                container.windows[popup] = win;
+            }
+            None => {
+                let popup = container.add_win(win);
+                self.popup = Some(container.track(popup));
+            }
+        }
+    }
+
+    /// Handles an event, opening, navigating and closing menus as needed.
+    pub fn handle_event(&mut self, event: &Event, container: &mut Container<Cell>) -> MenuResponse {
+        match event {
+            Event::Key(key) => {
+                if self.open.is_none() {
+                    let menu = match key.code {
+                        KeyCode::F(10) => Some(0),
+                        KeyCode::Char(ch) if key.modifiers.alt => self.menus.iter().position(|m| {
+                            m.title
+                                .chars()
+                                .next()
+                                .is_some_and(|c| c.eq_ignore_ascii_case(&ch))
+                        }),
+                        _ => None,
+                    };
+                    return match menu {
+                        Some(menu) => {
+                            self.open(menu, container);
+                            MenuResponse::Handled
+                        }
+                        None => MenuResponse::Ignored,
+                    };
+                }
+
+                let menu = self.open.unwrap();
+                match key.code {
+                    KeyCode::Esc | KeyCode::F(10) => self.close(container),
+                    KeyCode::Left => {
+                        let len = self.menus.len();
+                        self.open((menu + len - 1) % len, container);
+                    }
+                    KeyCode::Right => self.open((menu + 1) % self.menus.len(), container),
+                    KeyCode::Up => {
+                        self.move_highlight(false);
+                        self.sync(container);
+                    }
+                    KeyCode::Down => {
+                        self.move_highlight(true);
+                        self.sync(container);
+                    }
+                    KeyCode::Enter => {
+                        let item = self.highlighted;
+                        if self.menus[menu]
+                            .entries
+                            .get(item)
+                            .is_some_and(MenuEntry::is_enabled)
+                        {
+                            self.close(container);
+                            return MenuResponse::Selected { menu, item };
+                        }
+                    }
+                    _ => {}
+                }
+                // Keys don't reach anything else while a menu is open.
+                MenuResponse::Handled
+            }
+            Event::Mouse(ev) if ev.kind == MouseKind::Down(MouseButton::Left) => {
+                let pos = ev.pos;
+
+                // Clicking an item of the open menu.
+                if let (Some(menu), Some(popup)) = (self.open, self.popup(container)) {
+                    let win = &container.windows[popup];
+                    if win.contains(pos) {
+                        let item = win.to_local(pos).map_or(usize::MAX, |p| p.y as usize);
+                        if self.menus[menu]
+                            .entries
+                            .get(item)
+                            .is_some_and(MenuEntry::is_enabled)
+                        {
+                            self.close(container);
+                            return MenuResponse::Selected { menu, item };
+                        }
+                        return MenuResponse::Handled;
+                    }
+                }
+
+                // Clicking a title toggles its menu.
+                if pos.y == self.top_left.y && pos.x >= self.top_left.x {
+                    let x = (pos.x - self.top_left.x) as usize;
+                    let clicked = self.title_columns().iter().zip(self.menus.iter()).position(
+                        |(&start, menu)| x >= start && x < start + menu.title.chars().count() + 2,
+                    );
+                    if let Some(menu) = clicked {
+                        if self.open == Some(menu) {
+                            self.close(container);
+                        } else {
+                            self.open(menu, container);
+                        }
+                        return MenuResponse::Handled;
+                    }
+                }
+
+                // Clicking anywhere else closes the open menu.
+                if self.open.is_some() {
+                    self.close(container);
+                    return MenuResponse::Handled;
+                }
+                MenuResponse::Ignored
+            }
+            _ => MenuResponse::Ignored,
+        }
+    }
+}
+
+impl Widget for MenuBar {
+    fn render(&self, win: &mut Window<Cell>, wid: usize, hgt: usize) {
+        let mut data = blank(wid, hgt, self.style);
+
+        if let Some(row) = data.first_mut() {
+            for (idx, (menu, x)) in self.menus.iter().zip(self.title_columns()).enumerate() {
+                let style = if self.open == Some(idx) {
+                    self.highlight
+                } else {
+                    self.style
+                };
+                put_str(row, x, &format!(" {} ", menu.title), style);
+            }
+        }
+
+        win.data = data;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::KeyEvent;
+
+    #[test]
+    fn keeps_its_popup_when_a_window_below_is_removed() {
+        let mut container: Container<Cell> = Container::new();
+        let below = container.add_win(Window::new(Point::new(0, 5)));
+        let other = Window::new(Point::new(0, 6));
+        let mut bar = MenuBar::new([Menu::new("File", [MenuEntry::item("Open")])]);
+        bar.open(0, &mut container);
+        container.add_win(other);
+        container.remove_win(below);
+        assert_eq!(bar.popup(&container), Some(0));
+
+        let esc = Event::Key(KeyEvent::from(KeyCode::Esc));
+        assert_eq!(
+            bar.handle_event(&esc, &mut container),
+            MenuResponse::Handled
+        );
+        assert_eq!(container.windows.len(), 1);
+        assert_eq!(container.windows[0].top_left, Point::new(0, 6));
+    }
+}
@@ -0,0 +1,207 @@
+use super::{Widget, blank};
+use crate::geometry::{Coord, Point, Rect};
+use crate::style::{Cell, Color, Style};
+use crate::zoom::{Sampling, sample};
+use crate::{ChunkMap, Container, Window, WindowId};
+use std::fmt;
+
+/// Colours windows are shown in by id, going back to the start after the last.
+const PALETTE: [Color; 6] = [
+    Color::Blue,
+    Color::Green,
+    Color::Cyan,
+    Color::Magenta,
+    Color::Yellow,
+    Color::Red,
+];
+
+/// A scaled down overview of everything a container shows, with the part currently
+/// on screen outlined. Each window is shown as a block of colour picked by its id,
+/// or, if created with [`of_cells`](Self::of_cells), by the cells most of each area
+/// holds.
+///
+/// The minimap is a snapshot of the container as of its last refresh, so create a
+/// new one to pick up changes.
+#[derive(Clone, Debug)]
+pub struct Minimap {
+    owners: ChunkMap<WindowId>,
+    cells: Option<ChunkMap<Cell>>,
+    viewport: Rect,
+    exclude: Option<WindowId>,
+    sampling: Sampling,
+    style: Style,
+    viewport_style: Style,
+}
+
+impl Minimap {
+    /// Create an overview of a container with windows coloured by id, outlining the
+    /// screen.
+    pub fn new<T: fmt::Display>(container: &Container<T>) -> Self {
+        let (wid, hgt) = container.screen_size();
+        Self {
+            owners: container.owners.clone(),
+            cells: None,
+            viewport: Rect::new(Point::ORIGIN, wid as usize, hgt as usize),
+            exclude: None,
+            sampling: Sampling::Majority,
+            style: Style::new(),
+            viewport_style: Style::new().bold(),
+        }
+    }
+
+    /// Create an overview of a container showing the cell that most of each area
+    /// holds.
+    pub fn of_cells(container: &Container<Cell>) -> Self {
+        Self {
+            cells: Some(container.get_buffer().clone()),
+            ..Self::new(container)
+        }
+    }
+
+    /// Sets the area of the container outlined as the part in view.
+    pub fn viewport(mut self, viewport: Rect) -> Self {
+        self.viewport = viewport;
+        self
+    }
+
+    /// Leaves a window out of the overview, such as the one the minimap is shown in.
+    pub fn exclude(mut self, win: WindowId) -> Self {
+        self.exclude = Some(win);
+        self
+    }
+
+    /// Sets how each area of the container is shown as one cell.
+    pub fn sampling(mut self, sampling: Sampling) -> Self {
+        self.sampling = sampling;
+        self
+    }
+
+    /// Sets the style of empty space.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets the style of the outline of the viewport.
+    pub fn viewport_style(mut self, style: Style) -> Self {
+        self.viewport_style = style;
+        self
+    }
+
+    /// Returns the window shown at a position, leaving out the excluded one.
+    fn owner(&self, p: Point) -> Option<WindowId> {
+        self.owners
+            .get(&p)
+            .copied()
+            .filter(|&win| Some(win) != self.exclude)
+    }
+
+    /// Returns the area of the container the overview covers, which holds every
+    /// window and the viewport.
+    pub fn bounds(&self) -> Rect {
+        self.owners
+            .iter()
+            .filter(|&(_, &win)| Some(win) != self.exclude)
+            .fold(self.viewport, |bounds, (p, _)| {
+                bounds.union(&Rect::new(p, 1, 1))
+            })
+    }
+
+    /// Returns how many cells of the container across and down each cell of the
+    /// overview stands for when it is `wid` by `hgt`.
+    pub fn scale(&self, wid: usize, hgt: usize) -> usize {
+        let bounds = self.bounds();
+        bounds
+            .wid
+            .div_ceil(wid.max(1))
+            .max(bounds.hgt.div_ceil(hgt.max(1)))
+            .max(1)
+    }
+
+    /// Returns the position in the container that a cell of the overview rendered
+    /// `wid` by `hgt` stands for, such as to move the view there when it is clicked.
+    pub fn to_scene(&self, p: Point, wid: usize, hgt: usize) -> Point {
+        let scale = self.scale(wid, hgt) as Coord;
+        self.bounds().top_left + Point::new(p.x * scale, p.y * scale)
+    }
+
+    /// Outlines the viewport, shrunk to the scale of the overview.
+    fn outline_viewport(&self, data: &mut [Vec<Cell>], bounds: Rect, scale: usize) {
+        if self.viewport.is_empty() {
+            return;
+        }
+        let top_left = self.viewport.top_left - bounds.top_left;
+        let left = (top_left.x as usize) / scale;
+        let top = (top_left.y as usize) / scale;
+        let right = (top_left.x as usize + self.viewport.wid)
+            .div_ceil(scale)
+            .max(left + 1)
+            - 1;
+        let bottom = (top_left.y as usize + self.viewport.hgt)
+            .div_ceil(scale)
+            .max(top + 1)
+            - 1;
+        let mut outline = |x: usize, y: usize, ch: char| {
+            if let Some(cell) = data.get_mut(y).and_then(|row| row.get_mut(x)) {
+                *cell = Cell::new(ch, cell.style.patch(self.viewport_style));
+            }
+        };
+        for x in left..=right {
+            outline(x, top, '─');
+            outline(x, bottom, '─');
+        }
+        for y in top..=bottom {
+            outline(left, y, '│');
+            outline(right, y, '│');
+        }
+        outline(left, top, '┌');
+        outline(right, top, '┐');
+        outline(left, bottom, '└');
+        outline(right, bottom, '┘');
+    }
+}
+
+impl Widget for Minimap {
+    fn render(&self, win: &mut Window<Cell>, wid: usize, hgt: usize) {
+        let mut data = blank(wid, hgt, self.style);
+        let bounds = self.bounds();
+        let scale = self.scale(wid, hgt);
+
+        let blank = Cell::new(' ', self.style);
+        let shown = match &self.cells {
+            Some(cells) => sample(
+                bounds,
+                scale,
+                (wid, hgt),
+                self.sampling,
+                self.owners.keys(),
+                |p| self.owner(p).and_then(|_| cells.get(&p).copied()),
+            ),
+            None => sample(
+                bounds,
+                scale,
+                (wid, hgt),
+                self.sampling,
+                self.owners.keys(),
+                |p| self.owner(p),
+            )
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|win| {
+                        win.map(|win| Cell::new(' ', self.style.bg(PALETTE[win % PALETTE.len()])))
+                    })
+                    .collect()
+            })
+            .collect(),
+        };
+        for (row, shown) in data.iter_mut().zip(shown) {
+            for (cell, shown) in row.iter_mut().zip(shown) {
+                *cell = shown.unwrap_or(blank);
+            }
+        }
+        self.outline_viewport(&mut data, bounds, scale);
+
+        win.data = data;
+    }
+}
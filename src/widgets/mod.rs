@@ -0,0 +1,129 @@
+//! Ready made components that render themselves into a window of styled cells.
+
+use crate::Window;
+use crate::style::{Cell, Style};
+
+mod bar_chart;
+mod big_text;
+mod calendar;
+mod canvas;
+mod chart;
+mod dialog;
+mod gauge;
+mod heatmap;
+mod histogram;
+mod input;
+mod list;
+mod menu;
+mod minimap;
+mod paragraph;
+mod profiler;
+mod progress;
+mod sparkline;
+mod spinner;
+mod status;
+mod table;
+mod tabs;
+mod taskbar;
+mod tree;
+
+pub use bar_chart::{BarChart, BarGroup};
+pub use big_text::{BigText, Font};
+pub use calendar::{Calendar, Date};
+pub use canvas::{Canvas, Shape};
+pub use chart::{Chart, Dataset};
+pub use dialog::Dialog;
+pub use gauge::Gauge;
+pub use heatmap::Heatmap;
+pub use histogram::Histogram;
+pub use input::Input;
+pub use list::List;
+pub use menu::{Menu, MenuBar, MenuEntry, MenuResponse};
+pub use minimap::Minimap;
+pub use paragraph::Paragraph;
+pub use profiler::Profiler;
+pub use progress::ProgressBar;
+pub use sparkline::Sparkline;
+pub use spinner::Spinner;
+pub use status::{Segment, StatusBar};
+pub use table::{Column, ColumnWidth, Table};
+pub use tabs::Tabs;
+pub use taskbar::Taskbar;
+pub use tree::{Tree, TreeNode};
+
+/// Something that can draw itself into a window.
+pub trait Widget {
+    /// Replaces the contents of the window with the widget, drawn `wid` columns wide
+    /// and `hgt` rows tall.
+    fn render(&self, win: &mut Window<Cell>, wid: usize, hgt: usize);
+}
+
+/// Where text is placed within the space available to it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Alignment {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+impl Alignment {
+    /// Returns how far from the left text `len` long should start in a space `wid`
+    /// long.
+    pub fn offset(self, len: usize, wid: usize) -> usize {
+        let spare = wid.saturating_sub(len);
+        match self {
+            Alignment::Left => 0,
+            Alignment::Center => spare / 2,
+            Alignment::Right => spare,
+        }
+    }
+}
+
+/// Returns `hgt` rows of `wid` spaces in the given style.
+pub(crate) fn blank(wid: usize, hgt: usize, style: Style) -> Vec<Vec<Cell>> {
+    vec![vec![Cell::new(' ', style); wid]; hgt]
+}
+
+/// Writes text into a row starting at `x`, cutting it off at the end of the row.
+/// Returns the column after the last character written.
+pub(crate) fn put_str(row: &mut [Cell], x: usize, text: &str, style: Style) -> usize {
+    let mut x = x;
+    for ch in text.chars() {
+        let Some(cell) = row.get_mut(x) else {
+            break;
+        };
+        *cell = Cell::new(ch, style);
+        x += 1;
+    }
+    x
+}
+
+/// Writes text into a row starting at `x` and at most `wid` long, replacing the end
+/// with an ellipsis if it is too long.
+pub(crate) fn put_truncated(row: &mut [Cell], x: usize, wid: usize, text: &str, style: Style) {
+    let end = (x + wid).min(row.len());
+    let row = &mut row[..end];
+    let len = text.chars().count();
+
+    if len <= wid {
+        put_str(row, x, text, style);
+    } else if wid > 0 {
+        let cut: String = text.chars().take(wid - 1).chain(['…']).collect();
+        put_str(row, x, &cut, style);
+    }
+}
+
+/// Returns the index of the first of `len` items to show in a space `hgt` items
+/// tall, scrolling as little as possible from `offset` to show the selected item.
+pub(crate) fn scroll_to(offset: usize, selected: Option<usize>, len: usize, hgt: usize) -> usize {
+    let mut offset = offset.min(len.saturating_sub(hgt));
+    if let Some(sel) = selected {
+        if sel < offset {
+            offset = sel;
+        } else if hgt > 0 && sel >= offset + hgt {
+            offset = sel + 1 - hgt;
+        }
+    }
+    offset
+}
@@ -0,0 +1,120 @@
+use super::{Alignment, Widget, blank};
+use crate::Window;
+use crate::style::{Cell, Span, Style};
+
+/// A block of styled text that can be wrapped, aligned and scrolled. Line breaks in
+/// the text start new lines.
+#[derive(Clone, Debug, Default)]
+pub struct Paragraph {
+    spans: Vec<Span>,
+    style: Style,
+    wrap: bool,
+    align: Alignment,
+    scroll: usize,
+}
+
+impl Paragraph {
+    /// Create a paragraph from some spans of text.
+    pub fn new<S: Into<Span>>(spans: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            spans: spans.into_iter().map(Into::into).collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the style of the space not covered by text.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets whether lines too long to fit are wrapped at spaces rather than cut off.
+    pub fn wrap(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    /// Sets how each line is aligned.
+    pub fn align(mut self, align: Alignment) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Sets how many lines are skipped from the top.
+    pub fn scroll(mut self, scroll: usize) -> Self {
+        self.scroll = scroll;
+        self
+    }
+
+    /// Sets how many lines are skipped from the top.
+    pub fn set_scroll(&mut self, scroll: usize) {
+        self.scroll = scroll;
+    }
+
+    /// Returns the lines of the paragraph when drawn `wid` columns wide, before
+    /// scrolling.
+    pub fn lines(&self, wid: usize) -> Vec<Vec<Cell>> {
+        let mut lines = vec![Vec::new()];
+        for span in self.spans.iter() {
+            for cell in span.cells() {
+                if cell.ch == '\n' {
+                    lines.push(Vec::new());
+                } else {
+                    lines.last_mut().unwrap().push(cell);
+                }
+            }
+        }
+
+        if !self.wrap {
+            return lines;
+        }
+
+        lines.iter().flat_map(|line| wrap_line(line, wid)).collect()
+    }
+}
+
+/// Splits a line into lines at most `wid` long, breaking at spaces where possible.
+fn wrap_line(line: &[Cell], wid: usize) -> Vec<Vec<Cell>> {
+    if wid == 0 {
+        return Vec::new();
+    }
+
+    let mut out = Vec::new();
+    let mut start = 0;
+
+    while line.len() - start > wid {
+        let next = &line[start..=start + wid];
+        match next.iter().rposition(|c| c.ch == ' ') {
+            Some(space) if space > 0 => {
+                out.push(line[start..start + space].to_vec());
+                start += space + 1;
+            }
+            // A word too long for a line of its own is broken wherever it must be.
+            _ => {
+                out.push(line[start..start + wid].to_vec());
+                start += wid;
+            }
+        }
+    }
+
+    out.push(line[start..].to_vec());
+    out
+}
+
+impl Widget for Paragraph {
+    fn render(&self, win: &mut Window<Cell>, wid: usize, hgt: usize) {
+        let mut data = blank(wid, hgt, self.style);
+
+        for (row, line) in data
+            .iter_mut()
+            .zip(self.lines(wid).into_iter().skip(self.scroll))
+        {
+            let x = self.align.offset(line.len(), wid);
+            for (cell, ch) in row[x..].iter_mut().zip(line) {
+                *cell = ch;
+            }
+        }
+
+        win.data = data;
+    }
+}
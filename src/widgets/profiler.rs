@@ -0,0 +1,108 @@
+use super::{Widget, blank, put_str, put_truncated};
+use crate::style::{Cell, Color, Style};
+use crate::{Container, Profile, Window};
+use std::fmt;
+use std::time::Duration;
+
+/// An overlay showing how long the latest frame of a container took, by phase and
+/// for its slowest windows, against a frame budget. Nothing is shown unless
+/// profiling is [enabled](Container::enable_profiling).
+#[derive(Clone, Debug)]
+pub struct Profiler {
+    profile: Profile,
+    // Title of each window, or its id if it has none.
+    names: Vec<String>,
+    budget: Duration,
+    style: Style,
+}
+
+impl Profiler {
+    /// Create an overlay for the latest frame of a container, with a budget of 60
+    /// frames per second.
+    pub fn new<T: fmt::Display>(container: &Container<T>) -> Self {
+        Self {
+            profile: container.profile().cloned().unwrap_or_default(),
+            names: container
+                .windows
+                .iter()
+                .enumerate()
+                .map(|(id, win)| win.title.clone().unwrap_or_else(|| format!("#{id}")))
+                .collect(),
+            budget: Duration::from_secs(1) / 60,
+            style: Style::new(),
+        }
+    }
+
+    /// Sets the time a frame should take at most.
+    pub fn budget(mut self, budget: Duration) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    /// Sets the style of the overlay.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Returns the colour a time is shown in, given the share of the budget it may
+    /// take before it is too slow.
+    fn color(&self, time: Duration, share: f64) -> Color {
+        let ratio = time.as_secs_f64() / self.budget.as_secs_f64().max(f64::EPSILON);
+        if ratio > share {
+            Color::Red
+        } else if ratio > share * 0.75 {
+            Color::Yellow
+        } else {
+            Color::Green
+        }
+    }
+}
+
+/// Formats a time in milliseconds.
+fn millis(time: Duration) -> String {
+    format!("{:.2}ms", time.as_secs_f64() * 1000.0)
+}
+
+impl Widget for Profiler {
+    fn render(&self, win: &mut Window<Cell>, wid: usize, hgt: usize) {
+        let mut data = blank(wid, hgt, self.style);
+        let profile = &self.profile;
+        let mut rows = data.iter_mut();
+
+        if let Some(row) = rows.next() {
+            let total = profile.total();
+            let x = put_str(row, 0, &format!("frame {} ", profile.frames), self.style);
+            let x = put_str(
+                row,
+                x,
+                &millis(total),
+                self.style.fg(self.color(total, 1.0)),
+            );
+            put_str(row, x, &format!(" / {}", millis(self.budget)), self.style);
+        }
+        if let Some(row) = rows.next() {
+            let phases = format!(
+                "bind {} upd {} comp {} pres {}",
+                millis(profile.bindings),
+                millis(profile.updates),
+                millis(profile.composite),
+                millis(profile.present),
+            );
+            put_truncated(row, 0, wid, &phases, self.style);
+        }
+
+        // Each window's share of the budget, assuming they should all fit in it.
+        let share = 1.0 / profile.windows.len().max(1) as f64;
+        for (row, (id, time)) in rows.zip(profile.slowest(hgt)) {
+            let total = millis(time.total());
+            let name_wid = wid.saturating_sub(total.len() + 1);
+            let name = self.names.get(id).map_or("", String::as_str);
+            put_truncated(row, 0, name_wid, name, self.style);
+            let style = self.style.fg(self.color(time.total(), share));
+            put_str(row, wid.saturating_sub(total.len()), &total, style);
+        }
+
+        win.data = data;
+    }
+}
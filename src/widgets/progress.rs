@@ -0,0 +1,136 @@
+use super::{Widget, blank};
+use crate::Window;
+use crate::style::{Cell, Color, Style};
+
+/// Characters filling one to seven eighths of a cell from the left.
+const PARTIAL_BLOCKS: [char; 7] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+/// A horizontal bar filled in proportion to how far along something is.
+#[derive(Clone, Debug)]
+pub struct ProgressBar {
+    ratio: f64,
+    label: Option<String>,
+    show_percent: bool,
+    filled: char,
+    empty: char,
+    partial: bool,
+    style: Style,
+    fill_style: Style,
+}
+
+impl Default for ProgressBar {
+    fn default() -> Self {
+        Self::new(0.0)
+    }
+}
+
+impl ProgressBar {
+    /// Create a bar filled to the given ratio, which is clamped between 0 and 1.
+    pub fn new(ratio: f64) -> Self {
+        Self {
+            ratio: ratio.clamp(0.0, 1.0),
+            label: None,
+            show_percent: true,
+            filled: '█',
+            empty: ' ',
+            partial: true,
+            style: Style::new(),
+            fill_style: Style::new().fg(Color::Green),
+        }
+    }
+
+    /// Sets how full the bar is, clamped between 0 and 1.
+    pub fn set_ratio(&mut self, ratio: f64) {
+        self.ratio = ratio.clamp(0.0, 1.0);
+    }
+
+    /// Returns how full the bar is.
+    pub fn ratio(&self) -> f64 {
+        self.ratio
+    }
+
+    /// Sets text shown in the middle of the bar instead of the percentage.
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Sets whether the percentage is shown in the middle of the bar when there is no
+    /// label.
+    pub fn show_percent(mut self, show: bool) -> Self {
+        self.show_percent = show;
+        self
+    }
+
+    /// Sets the characters used for the filled and empty parts of the bar.
+    pub fn chars(mut self, filled: char, empty: char) -> Self {
+        self.filled = filled;
+        self.empty = empty;
+        self
+    }
+
+    /// Sets whether partially filled cells are drawn with eighth block characters.
+    pub fn partial(mut self, partial: bool) -> Self {
+        self.partial = partial;
+        self
+    }
+
+    /// Sets the style of the empty part of the bar.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets the style of the filled part of the bar.
+    pub fn fill_style(mut self, style: Style) -> Self {
+        self.fill_style = style;
+        self
+    }
+
+    /// Returns the text shown in the middle of the bar, if any.
+    fn text(&self) -> Option<String> {
+        match &self.label {
+            Some(label) => Some(label.clone()),
+            None if self.show_percent => Some(format!("{:.0}%", self.ratio * 100.0)),
+            None => None,
+        }
+    }
+}
+
+impl Widget for ProgressBar {
+    fn render(&self, win: &mut Window<Cell>, wid: usize, hgt: usize) {
+        let mut data = blank(wid, hgt, self.style);
+        let exact = self.ratio * wid as f64;
+        let full = exact.floor() as usize;
+        let eighths = ((exact - full as f64) * 8.0).floor() as usize;
+
+        for row in data.iter_mut() {
+            for (x, cell) in row.iter_mut().enumerate() {
+                *cell = if x < full {
+                    Cell::new(self.filled, self.fill_style)
+                } else if x == full && self.partial && eighths > 0 {
+                    Cell::new(PARTIAL_BLOCKS[eighths - 1], self.fill_style)
+                } else {
+                    Cell::new(self.empty, self.style)
+                };
+            }
+        }
+
+        if let (Some(text), Some(row)) = (self.text(), data.get_mut(hgt / 2)) {
+            let len = text.chars().count();
+            let start = wid.saturating_sub(len) / 2;
+            for (x, ch) in (start..wid).zip(text.chars()) {
+                // Text over the filled part is drawn in the fill colour's inverse so
+                // that it stays readable.
+                let style = if x < full {
+                    self.fill_style.reversed()
+                } else {
+                    self.style
+                };
+                row[x] = Cell::new(ch, style);
+            }
+        }
+
+        win.data = data;
+    }
+}
@@ -0,0 +1,95 @@
+use super::{Widget, blank};
+use crate::Window;
+use crate::style::{Cell, Style};
+use std::collections::VecDeque;
+
+/// Characters filling one to eight eighths of a cell from the bottom.
+pub(crate) const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// A compact chart of recent values, one column per value, drawn with block
+/// characters. The newest value is on the right.
+#[derive(Clone, Debug)]
+pub struct Sparkline {
+    data: VecDeque<f64>,
+    capacity: usize,
+    max: Option<f64>,
+    style: Style,
+}
+
+impl Sparkline {
+    /// Create an empty sparkline that keeps at most `capacity` values.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            data: VecDeque::with_capacity(capacity),
+            capacity,
+            max: None,
+            style: Style::new(),
+        }
+    }
+
+    /// Sets the value drawn as a full column. If None, the largest value shown is used.
+    pub fn max(mut self, max: Option<f64>) -> Self {
+        self.max = max;
+        self
+    }
+
+    /// Sets the style of the sparkline.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Adds a value, dropping the oldest one if there are too many.
+    pub fn push(&mut self, value: f64) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.data.len() == self.capacity {
+            self.data.pop_front();
+        }
+        self.data.push_back(value);
+    }
+
+    /// Adds several values in order.
+    pub fn extend(&mut self, values: impl IntoIterator<Item = f64>) {
+        for value in values {
+            self.push(value);
+        }
+    }
+
+    /// Returns the values kept, oldest first.
+    pub fn data(&self) -> &VecDeque<f64> {
+        &self.data
+    }
+
+    /// Removes all values.
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+}
+
+impl Widget for Sparkline {
+    fn render(&self, win: &mut Window<Cell>, wid: usize, hgt: usize) {
+        let mut data = blank(wid, hgt, self.style);
+        let shown: Vec<f64> = self.data.iter().rev().take(wid).rev().copied().collect();
+        let max = self
+            .max
+            .unwrap_or_else(|| shown.iter().copied().fold(0.0, f64::max));
+
+        if max > 0.0 {
+            let start = wid - shown.len();
+            for (x, value) in (start..).zip(shown) {
+                // Height of the column in eighths of a cell.
+                let eighths = ((value / max).clamp(0.0, 1.0) * (hgt * 8) as f64).round() as usize;
+                for (y, row) in data.iter_mut().rev().enumerate() {
+                    let fill = eighths.saturating_sub(y * 8).min(8);
+                    if fill > 0 {
+                        row[x] = Cell::new(BARS[fill - 1], self.style);
+                    }
+                }
+            }
+        }
+
+        win.data = data;
+    }
+}
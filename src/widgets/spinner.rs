@@ -0,0 +1,88 @@
+use super::{Widget, blank, put_str};
+use crate::Window;
+use crate::event::Event;
+use crate::style::{Cell, Style};
+
+/// An animation that shows something is happening, followed by a label.
+#[derive(Clone, Debug)]
+pub struct Spinner {
+    frames: &'static [&'static str],
+    frame: usize,
+    label: String,
+    style: Style,
+}
+
+impl Spinner {
+    /// Braille dots going round.
+    pub const DOTS: &'static [&'static str] = &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+    /// A line turning.
+    pub const LINE: &'static [&'static str] = &["-", "\\", "|", "/"];
+    /// A dot bouncing between brackets.
+    pub const BOUNCE: &'static [&'static str] =
+        &["[●   ]", "[ ●  ]", "[  ● ]", "[   ●]", "[  ● ]", "[ ●  ]"];
+
+    /// Create a spinner using the [`DOTS`](Self::DOTS) frames.
+    pub fn new(label: impl Into<String>) -> Self {
+        Self {
+            frames: Self::DOTS,
+            frame: 0,
+            label: label.into(),
+            style: Style::new(),
+        }
+    }
+
+    /// Sets the frames of the animation.
+    pub fn frames(mut self, frames: &'static [&'static str]) -> Self {
+        self.frames = frames;
+        self.frame = 0;
+        self
+    }
+
+    /// Sets the style of the spinner.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets the label shown after the animation.
+    pub fn set_label(&mut self, label: impl Into<String>) {
+        self.label = label.into();
+    }
+
+    /// Moves on to the next frame.
+    pub fn advance(&mut self) {
+        if !self.frames.is_empty() {
+            self.frame = (self.frame + 1) % self.frames.len();
+        }
+    }
+
+    /// Moves on to the next frame if the event is a tick. Returns true if it was.
+    pub fn tick(&mut self, event: &Event) -> bool {
+        let is_tick = matches!(event, Event::Tick);
+        if is_tick {
+            self.advance();
+        }
+        is_tick
+    }
+
+    /// Returns the current frame.
+    pub fn frame(&self) -> &str {
+        self.frames.get(self.frame).copied().unwrap_or("")
+    }
+}
+
+impl Widget for Spinner {
+    fn render(&self, win: &mut Window<Cell>, wid: usize, hgt: usize) {
+        let mut data = blank(wid, hgt, self.style);
+
+        if let Some(row) = data.get_mut(hgt / 2) {
+            let x = put_str(row, 0, self.frame(), self.style);
+            if !self.label.is_empty() {
+                let x = put_str(row, x, " ", self.style);
+                put_str(row, x, &self.label, self.style);
+            }
+        }
+
+        win.data = data;
+    }
+}
@@ -0,0 +1,172 @@
+use super::{Widget, blank, put_str, put_truncated};
+use crate::Window;
+use crate::geometry::{Coord, Point};
+use crate::style::{Cell, Style};
+
+/// A piece of text in a [`StatusBar`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Segment {
+    pub text: String,
+    pub style: Style,
+    /// Segments with lower priorities are hidden first when the bar is too narrow.
+    pub priority: u8,
+}
+
+impl Segment {
+    /// Create a segment in the bar's style with priority 0.
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the style of the segment.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets the priority of the segment.
+    pub fn priority(mut self, priority: u8) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    fn len(&self) -> usize {
+        self.text.chars().count()
+    }
+}
+
+/// A single row of segments grouped on the left, centre and right, usually docked to
+/// the bottom of the screen. Segments are hidden in order of priority when they
+/// don't all fit.
+#[derive(Clone, Debug)]
+pub struct StatusBar {
+    pub left: Vec<Segment>,
+    pub center: Vec<Segment>,
+    pub right: Vec<Segment>,
+    style: Style,
+    separator: String,
+}
+
+impl Default for StatusBar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StatusBar {
+    /// Create an empty status bar.
+    pub fn new() -> Self {
+        Self {
+            left: Vec::new(),
+            center: Vec::new(),
+            right: Vec::new(),
+            style: Style::new().reversed(),
+            separator: String::from(" │ "),
+        }
+    }
+
+    /// Sets the style of the bar.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets the text between segments in the same group.
+    pub fn separator(mut self, separator: &str) -> Self {
+        self.separator = separator.to_string();
+        self
+    }
+
+    /// Renders the bar as the bottom row of a screen `screen_wid` by `screen_hgt`,
+    /// moving the window there.
+    pub fn render_docked(&self, win: &mut Window<Cell>, screen_wid: u16, screen_hgt: u16) {
+        win.top_left = Point::new(0, screen_hgt as Coord - 1);
+        self.render(win, screen_wid as usize, 1);
+    }
+
+    /// Width of a group when only the segments marked as shown are included.
+    fn group_len(&self, group: &[Segment], shown: &[bool]) -> usize {
+        let lens: Vec<usize> = group
+            .iter()
+            .zip(shown)
+            .filter(|(_, shown)| **shown)
+            .map(|(seg, _)| seg.len())
+            .collect();
+        lens.iter().sum::<usize>() + self.separator.chars().count() * lens.len().saturating_sub(1)
+    }
+
+    /// Draws the shown segments of a group starting at `x`, stopping at `end`.
+    fn put_group(&self, row: &mut [Cell], x: usize, end: usize, group: &[Segment], shown: &[bool]) {
+        let mut x = x;
+        let mut first = true;
+        for (seg, _) in group.iter().zip(shown).filter(|(_, shown)| **shown) {
+            if !first {
+                x = put_str(&mut row[..end], x, &self.separator, self.style);
+            }
+            first = false;
+            put_truncated(
+                row,
+                x,
+                end.saturating_sub(x),
+                &seg.text,
+                self.style.patch(seg.style),
+            );
+            x += seg.len();
+        }
+    }
+}
+
+impl Widget for StatusBar {
+    fn render(&self, win: &mut Window<Cell>, wid: usize, hgt: usize) {
+        let mut data = blank(wid, hgt, self.style);
+        let groups = [&self.left, &self.center, &self.right];
+        let mut shown: Vec<Vec<bool>> = groups.iter().map(|g| vec![true; g.len()]).collect();
+
+        // Hide the lowest priority segments until everything fits with a space
+        // between each group.
+        loop {
+            let total: usize = groups
+                .iter()
+                .zip(&shown)
+                .map(|(g, s)| self.group_len(g, s))
+                .sum::<usize>()
+                + 2;
+            if total <= wid {
+                break;
+            }
+
+            let lowest = groups
+                .iter()
+                .enumerate()
+                .flat_map(|(g, group)| group.iter().enumerate().map(move |(s, seg)| (g, s, seg)))
+                .filter(|(g, s, _)| shown[*g][*s])
+                .min_by_key(|(_, _, seg)| seg.priority);
+            let Some((g, s, _)) = lowest else {
+                break;
+            };
+            // Keep the last segment and cut it off instead.
+            if shown.iter().flatten().filter(|s| **s).count() == 1 {
+                break;
+            }
+            shown[g][s] = false;
+        }
+
+        if let Some(row) = data.first_mut() {
+            let left_len = self.group_len(&self.left, &shown[0]);
+            let center_len = self.group_len(&self.center, &shown[1]);
+            let right_len = self.group_len(&self.right, &shown[2]);
+
+            let right_x = wid.saturating_sub(right_len);
+            let center_x = (wid.saturating_sub(center_len) / 2).max(left_len + 1);
+
+            self.put_group(row, 0, wid, &self.left, &shown[0]);
+            self.put_group(row, center_x.min(wid), wid, &self.center, &shown[1]);
+            self.put_group(row, right_x, wid, &self.right, &shown[2]);
+        }
+
+        win.data = data;
+    }
+}
@@ -0,0 +1,280 @@
+use super::{Alignment, Widget, blank, put_truncated, scroll_to};
+use crate::Window;
+use crate::style::{Cell, Style, Theme};
+
+/// How wide a column of a [`Table`] should be.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ColumnWidth {
+    /// Exactly this many columns.
+    Fixed(usize),
+    /// As wide as the widest value or header in the column.
+    #[default]
+    Auto,
+    /// An equal share of whatever space the other columns leave.
+    Fill,
+}
+
+/// A column of a [`Table`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Column {
+    pub header: String,
+    pub width: ColumnWidth,
+    pub align: Alignment,
+}
+
+impl Column {
+    /// Create a left aligned column that is as wide as its contents.
+    pub fn new(header: impl Into<String>) -> Self {
+        Self {
+            header: header.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Sets how wide the column should be.
+    pub fn width(mut self, width: ColumnWidth) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Sets how values are aligned within the column.
+    pub fn align(mut self, align: Alignment) -> Self {
+        self.align = align;
+        self
+    }
+}
+
+/// Rows of values arranged in columns under a header row, with one row that may be
+/// selected. Columns are shrunk and their values cut off if they don't all fit.
+#[derive(Clone, Debug, Default)]
+pub struct Table {
+    columns: Vec<Column>,
+    rows: Vec<Vec<String>>,
+    selected: Option<usize>,
+    style: Style,
+    header_style: Style,
+    highlight: Style,
+    spacing: usize,
+    offset: std::cell::Cell<usize>,
+}
+
+impl Table {
+    /// Create a table with the given columns and no rows.
+    pub fn new(columns: impl IntoIterator<Item = Column>) -> Self {
+        Self {
+            columns: columns.into_iter().collect(),
+            header_style: Style::new().bold(),
+            highlight: Style::new().reversed(),
+            spacing: 1,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the rows of the table, keeping the selection within them.
+    pub fn rows<R, S>(mut self, rows: impl IntoIterator<Item = R>) -> Self
+    where
+        R: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.set_rows(rows);
+        self
+    }
+
+    /// Sets the rows of the table, keeping the selection within them.
+    pub fn set_rows<R, S>(&mut self, rows: impl IntoIterator<Item = R>)
+    where
+        R: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.rows = rows
+            .into_iter()
+            .map(|row| row.into_iter().map(Into::into).collect())
+            .collect();
+        self.select(self.selected);
+    }
+
+    /// Sets the style of the table.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets the style applied on top of the header row.
+    pub fn header_style(mut self, style: Style) -> Self {
+        self.header_style = style;
+        self
+    }
+
+    /// Sets the style applied on top of the selected row.
+    pub fn highlight_style(mut self, style: Style) -> Self {
+        self.highlight = style;
+        self
+    }
+
+    /// Sets the style and highlight from a theme.
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.style = theme.text;
+        self.highlight = theme.highlight;
+        self
+    }
+
+    /// Sets the number of blank columns between each column.
+    pub fn spacing(mut self, spacing: usize) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    /// Returns the index of the selected row, if any.
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    /// Selects the row at the given index, or the last row if it is past the end.
+    pub fn select(&mut self, idx: Option<usize>) {
+        self.selected = match idx {
+            Some(_) if self.rows.is_empty() => None,
+            Some(idx) => Some(idx.min(self.rows.len() - 1)),
+            None => None,
+        };
+    }
+
+    /// Selects the row after the selected one, staying on the last row.
+    pub fn select_next(&mut self) {
+        self.select(Some(self.selected.map_or(0, |idx| idx + 1)));
+    }
+
+    /// Selects the row before the selected one, staying on the first row.
+    pub fn select_prev(&mut self) {
+        self.select(Some(self.selected.map_or(0, |idx| idx.saturating_sub(1))));
+    }
+
+    /// Returns the width of each column when the table is drawn `wid` columns wide.
+    pub fn column_widths(&self, wid: usize) -> Vec<usize> {
+        let mut widths: Vec<usize> = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(idx, col)| match col.width {
+                ColumnWidth::Fixed(w) => w,
+                ColumnWidth::Auto => self
+                    .rows
+                    .iter()
+                    .filter_map(|row| row.get(idx))
+                    .chain([&col.header])
+                    .map(|val| val.chars().count())
+                    .max()
+                    .unwrap_or(0),
+                ColumnWidth::Fill => 0,
+            })
+            .collect();
+
+        let gaps = self.spacing * self.columns.len().saturating_sub(1);
+        let used = widths.iter().sum::<usize>() + gaps;
+
+        if used < wid {
+            // Share out the spare space between the filling columns.
+            let fills: Vec<usize> = (0..self.columns.len())
+                .filter(|&idx| self.columns[idx].width == ColumnWidth::Fill)
+                .collect();
+            let spare = wid - used;
+            for (n, &idx) in fills.iter().enumerate() {
+                widths[idx] = spare / fills.len() + usize::from(n < spare % fills.len());
+            }
+        } else {
+            // Take space from the widest column until everything fits.
+            let mut over = used - wid;
+            while over > 0 {
+                let Some(widest) = (0..widths.len()).max_by_key(|&idx| widths[idx]) else {
+                    break;
+                };
+                if widths[widest] == 0 {
+                    break;
+                }
+                widths[widest] -= 1;
+                over -= 1;
+            }
+        }
+
+        widths
+    }
+
+    /// Writes one row of values into a row of cells.
+    fn put_row<'a>(
+        &self,
+        row: &mut [Cell],
+        widths: &[usize],
+        values: impl Iterator<Item = &'a str>,
+        style: Style,
+    ) {
+        for cell in row.iter_mut() {
+            cell.style = style;
+        }
+
+        let mut x = 0;
+        for ((col, &w), val) in self.columns.iter().zip(widths).zip(values) {
+            // Values too wide for the column fill it, so are never offset.
+            let offset = col.align.offset(val.chars().count().min(w), w);
+            put_truncated(row, x + offset, w - offset, val, style);
+            x += w + self.spacing;
+        }
+    }
+}
+
+impl Widget for Table {
+    fn render(&self, win: &mut Window<Cell>, wid: usize, hgt: usize) {
+        let mut data = blank(wid, hgt, self.style);
+        let widths = self.column_widths(wid);
+
+        if let Some(header) = data.first_mut() {
+            let headers = self.columns.iter().map(|col| col.header.as_str());
+            self.put_row(
+                header,
+                &widths,
+                headers,
+                self.style.patch(self.header_style),
+            );
+        }
+
+        let body_hgt = hgt.saturating_sub(1);
+        let offset = scroll_to(self.offset.get(), self.selected, self.rows.len(), body_hgt);
+        self.offset.set(offset);
+
+        for (idx, (row, values)) in data
+            .iter_mut()
+            .skip(1)
+            .zip(self.rows.iter().skip(offset))
+            .enumerate()
+        {
+            let style = if self.selected == Some(idx + offset) {
+                self.style.patch(self.highlight)
+            } else {
+                self.style
+            };
+            self.put_row(row, &widths, values.iter().map(String::as_str), style);
+        }
+
+        win.data = data;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::Point;
+
+    #[test]
+    fn values_are_aligned_or_cut_off() {
+        let table = Table::new([Column::new("N")
+            .width(ColumnWidth::Fixed(4))
+            .align(Alignment::Right)])
+        .rows([["ab"], ["abcdef"]]);
+        let mut win = Window::new(Point::new(0, 0));
+        table.render(&mut win, 4, 3);
+        let text: Vec<String> = win
+            .data
+            .iter()
+            .map(|row| row.iter().map(|cell| cell.ch).collect())
+            .collect();
+        assert_eq!(text, ["   N", "  ab", "abc…"]);
+    }
+}
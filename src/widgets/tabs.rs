@@ -0,0 +1,124 @@
+use super::{Widget, blank, put_str};
+use crate::Window;
+use crate::style::{Cell, Span, Style, Theme};
+
+/// A row of tab titles with one active tab.
+#[derive(Clone, Debug)]
+pub struct Tabs {
+    titles: Vec<Span>,
+    selected: usize,
+    style: Style,
+    highlight: Style,
+    divider: String,
+}
+
+impl Tabs {
+    /// Create tabs with the given titles, with the first one active.
+    pub fn new<S: Into<Span>>(titles: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            titles: titles.into_iter().map(Into::into).collect(),
+            selected: 0,
+            style: Style::new(),
+            highlight: Style::new().reversed(),
+            divider: String::from("│"),
+        }
+    }
+
+    /// Sets the style of the row.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets the style applied on top of the active tab's title.
+    pub fn highlight_style(mut self, style: Style) -> Self {
+        self.highlight = style;
+        self
+    }
+
+    /// Sets the style and highlight from a theme.
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.style = theme.text;
+        self.highlight = theme.highlight;
+        self
+    }
+
+    /// Sets the text drawn between titles.
+    pub fn divider(mut self, divider: &str) -> Self {
+        self.divider = divider.to_string();
+        self
+    }
+
+    /// Returns the index of the active tab.
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Number of tabs.
+    pub fn len(&self) -> usize {
+        self.titles.len()
+    }
+
+    /// Returns true if there are no tabs.
+    pub fn is_empty(&self) -> bool {
+        self.titles.is_empty()
+    }
+
+    /// Makes the tab at the given index active, or the last tab if it is past the end.
+    pub fn select(&mut self, idx: usize) {
+        self.selected = idx.min(self.titles.len().saturating_sub(1));
+    }
+
+    /// Makes the next tab active, going back to the first after the last.
+    pub fn next(&mut self) {
+        if !self.titles.is_empty() {
+            self.selected = (self.selected + 1) % self.titles.len();
+        }
+    }
+
+    /// Makes the previous tab active, going round to the last before the first.
+    pub fn prev(&mut self) {
+        if !self.titles.is_empty() {
+            self.selected = (self.selected + self.titles.len() - 1) % self.titles.len();
+        }
+    }
+
+    /// Returns the index of the tab whose title covers the given column, if any.
+    pub fn tab_at(&self, x: usize) -> Option<usize> {
+        let div = self.divider.chars().count();
+        let mut start = 0;
+        for (idx, title) in self.titles.iter().enumerate() {
+            let end = start + title.text.chars().count() + 2;
+            if x < end {
+                return (x >= start).then_some(idx);
+            }
+            start = end + div;
+        }
+        None
+    }
+}
+
+impl Widget for Tabs {
+    fn render(&self, win: &mut Window<Cell>, wid: usize, hgt: usize) {
+        let mut data = blank(wid, hgt, self.style);
+
+        if let Some(row) = data.first_mut() {
+            let mut x = 0;
+            for (idx, title) in self.titles.iter().enumerate() {
+                if idx > 0 {
+                    x = put_str(row, x, &self.divider, self.style);
+                }
+                let style = if idx == self.selected {
+                    self.style.patch(self.highlight)
+                } else {
+                    self.style
+                };
+                x = put_str(row, x, " ", style);
+                x = put_str(row, x, &title.text, style.patch(title.style));
+                x = put_str(row, x, " ", style);
+            }
+        }
+
+        win.data = data;
+    }
+}
@@ -0,0 +1,175 @@
+use super::{Widget, blank, put_str};
+use crate::event::{Event, MouseButton, MouseKind};
+use crate::geometry::{Coord, Point};
+use crate::style::{Cell, Style, Theme};
+use crate::{Container, Window, WindowId};
+use std::fmt;
+
+/// An entry of a [`Taskbar`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct Entry {
+    win: WindowId,
+    title: String,
+    hidden: bool,
+    focused: bool,
+}
+
+/// A row listing every window of a container that has a title. The focused window
+/// is highlighted and hidden windows are shown in brackets. Activating an entry shows
+/// and focuses its window.
+///
+/// Call [`update`](Self::update) before rendering to pick up changes to the
+/// container's windows.
+#[derive(Clone, Debug)]
+pub struct Taskbar {
+    entries: Vec<Entry>,
+    top_left: Point,
+    style: Style,
+    highlight: Style,
+    hidden_style: Style,
+}
+
+impl Default for Taskbar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Taskbar {
+    /// Create an empty taskbar at the top left of the screen.
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            top_left: Point::new(0, 0),
+            style: Style::new().reversed(),
+            highlight: Style::new().bold(),
+            hidden_style: Style::new().reversed().italic(),
+        }
+    }
+
+    /// Sets where the window the taskbar is rendered into is.
+    pub fn at(mut self, top_left: Point) -> Self {
+        self.top_left = top_left;
+        self
+    }
+
+    /// Sets the style of the taskbar.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets the style of the focused window's entry.
+    pub fn highlight_style(mut self, style: Style) -> Self {
+        self.highlight = style;
+        self
+    }
+
+    /// Sets the style of hidden windows' entries.
+    pub fn hidden_style(mut self, style: Style) -> Self {
+        self.hidden_style = style;
+        self
+    }
+
+    /// Sets the style, highlight and style of hidden windows from a theme.
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.style = theme.text;
+        self.highlight = theme.highlight;
+        self.hidden_style = theme.muted;
+        self
+    }
+
+    /// Reads the titled windows of the container.
+    pub fn update<T: fmt::Display>(&mut self, container: &Container<T>) {
+        self.entries = container
+            .windows
+            .iter()
+            .enumerate()
+            .filter_map(|(win, window)| {
+                Some(Entry {
+                    win,
+                    title: window.title.clone()?,
+                    hidden: window.hidden,
+                    focused: container.focused() == Some(win),
+                })
+            })
+            .collect();
+    }
+
+    /// Returns the ids of the windows listed, in order.
+    pub fn windows(&self) -> Vec<WindowId> {
+        self.entries.iter().map(|e| e.win).collect()
+    }
+
+    /// Shows and focuses the window of the entry at the given index, returning its id.
+    pub fn activate<T: fmt::Display>(
+        &mut self,
+        idx: usize,
+        container: &mut Container<T>,
+    ) -> Option<WindowId> {
+        let win = self.entries.get(idx)?.win;
+        container.set_hidden(win, false);
+        container.set_focus(Some(win));
+        self.update(container);
+        Some(win)
+    }
+
+    /// Returns the text of each entry and the column it starts at.
+    fn labels(&self) -> Vec<(String, usize)> {
+        let mut x = 0;
+        self.entries
+            .iter()
+            .map(|entry| {
+                let label = if entry.hidden {
+                    format!(" ({}) ", entry.title)
+                } else {
+                    format!(" {} ", entry.title)
+                };
+                let start = x;
+                x += label.chars().count();
+                (label, start)
+            })
+            .collect()
+    }
+
+    /// Activates the entry that was clicked, if any, returning its window's id.
+    pub fn handle_event<T: fmt::Display>(
+        &mut self,
+        event: &Event,
+        container: &mut Container<T>,
+    ) -> Option<WindowId> {
+        let Event::Mouse(ev) = event else {
+            return None;
+        };
+        if ev.kind != MouseKind::Down(MouseButton::Left) || ev.pos.y != self.top_left.y {
+            return None;
+        }
+
+        let x = ev.pos.x - self.top_left.x;
+        let idx = self.labels().iter().position(|(label, start)| {
+            x >= *start as Coord && x < (*start + label.chars().count()) as Coord
+        })?;
+        self.activate(idx, container)
+    }
+}
+
+impl Widget for Taskbar {
+    fn render(&self, win: &mut Window<Cell>, wid: usize, hgt: usize) {
+        let mut data = blank(wid, hgt, self.style);
+
+        if let Some(row) = data.first_mut() {
+            for (entry, (label, x)) in self.entries.iter().zip(self.labels()) {
+                let style = if entry.focused {
+                    self.highlight
+                } else if entry.hidden {
+                    self.hidden_style
+                } else {
+                    self.style
+                };
+                put_str(row, x, &label, style);
+            }
+        }
+
+        win.data = data;
+    }
+}
@@ -0,0 +1,266 @@
+use super::{Widget, blank, put_str, scroll_to};
+use crate::Window;
+use crate::style::{Cell, Span, Style, Theme};
+
+/// A node of a [`Tree`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TreeNode {
+    pub label: Span,
+    pub children: Vec<TreeNode>,
+    /// Whether the children of the node are shown.
+    pub expanded: bool,
+}
+
+impl TreeNode {
+    /// Create a collapsed node with no children.
+    pub fn new(label: impl Into<Span>) -> Self {
+        Self {
+            label: label.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the node with the given children.
+    pub fn with_children(mut self, children: impl IntoIterator<Item = TreeNode>) -> Self {
+        self.children = children.into_iter().collect();
+        self
+    }
+
+    /// Returns the node expanded.
+    pub fn expanded(mut self) -> Self {
+        self.expanded = true;
+        self
+    }
+}
+
+/// A row of a tree as it is shown.
+struct Row<'a> {
+    path: Vec<usize>,
+    // Indentation guides drawn before the node.
+    guides: String,
+    node: &'a TreeNode,
+}
+
+/// Nested nodes that can be expanded and collapsed, drawn with indentation guides.
+/// One shown node is always selected while the tree is not empty.
+#[derive(Clone, Debug, Default)]
+pub struct Tree {
+    roots: Vec<TreeNode>,
+    // Path of indices from the roots to the selected node.
+    selected: Vec<usize>,
+    style: Style,
+    highlight: Style,
+    offset: std::cell::Cell<usize>,
+}
+
+impl Tree {
+    /// Create a tree with the given root nodes, selecting the first.
+    pub fn new(roots: impl IntoIterator<Item = TreeNode>) -> Self {
+        let roots: Vec<TreeNode> = roots.into_iter().collect();
+        let selected = if roots.is_empty() {
+            Vec::new()
+        } else {
+            vec![0]
+        };
+        Self {
+            roots,
+            selected,
+            highlight: Style::new().reversed(),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the style of the tree.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets the style applied on top of the selected node.
+    pub fn highlight_style(mut self, style: Style) -> Self {
+        self.highlight = style;
+        self
+    }
+
+    /// Sets the style and highlight from a theme.
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.style = theme.text;
+        self.highlight = theme.highlight;
+        self
+    }
+
+    /// Returns the root nodes of the tree.
+    pub fn roots(&self) -> &[TreeNode] {
+        &self.roots
+    }
+
+    /// Returns the indices leading from the roots to the selected node.
+    pub fn selected_path(&self) -> &[usize] {
+        &self.selected
+    }
+
+    /// Returns the node at the given path of indices.
+    pub fn node(&self, path: &[usize]) -> Option<&TreeNode> {
+        let (first, rest) = path.split_first()?;
+        rest.iter()
+            .try_fold(self.roots.get(*first)?, |node, &idx| node.children.get(idx))
+    }
+
+    /// Returns the node at the given path of indices mutably.
+    pub fn node_mut(&mut self, path: &[usize]) -> Option<&mut TreeNode> {
+        let (first, rest) = path.split_first()?;
+        rest.iter()
+            .try_fold(self.roots.get_mut(*first)?, |node, &idx| {
+                node.children.get_mut(idx)
+            })
+    }
+
+    /// Returns the selected node.
+    pub fn selected(&self) -> Option<&TreeNode> {
+        self.node(&self.selected)
+    }
+
+    /// Selects the next shown node.
+    pub fn next(&mut self) {
+        let rows = self.rows();
+        if let Some(pos) = rows.iter().position(|row| row.path == self.selected)
+            && let Some(row) = rows.get(pos + 1)
+        {
+            self.selected = row.path.clone();
+        }
+    }
+
+    /// Selects the previous shown node.
+    pub fn prev(&mut self) {
+        let rows = self.rows();
+        if let Some(pos) = rows.iter().position(|row| row.path == self.selected)
+            && pos > 0
+        {
+            self.selected = rows[pos - 1].path.clone();
+        }
+    }
+
+    /// Expands the selected node, or selects its first child if it is already
+    /// expanded.
+    pub fn expand(&mut self) {
+        let mut path = self.selected.clone();
+        let Some(node) = self.node_mut(&path) else {
+            return;
+        };
+
+        if node.children.is_empty() {
+            return;
+        }
+        if node.expanded {
+            path.push(0);
+            self.selected = path;
+        } else {
+            node.expanded = true;
+        }
+    }
+
+    /// Collapses the selected node, or selects its parent if it is already collapsed.
+    pub fn collapse(&mut self) {
+        let Some(node) = self.node_mut(&self.selected.clone()) else {
+            return;
+        };
+
+        if node.expanded && !node.children.is_empty() {
+            node.expanded = false;
+        } else if self.selected.len() > 1 {
+            self.selected.pop();
+        }
+    }
+
+    /// Expands the selected node if it is collapsed, or collapses it otherwise.
+    pub fn toggle(&mut self) {
+        if let Some(node) = self.node_mut(&self.selected.clone()) {
+            node.expanded = !node.expanded;
+        }
+    }
+
+    /// Returns every shown node in order.
+    fn rows(&self) -> Vec<Row<'_>> {
+        let mut rows = Vec::new();
+        for (idx, node) in self.roots.iter().enumerate() {
+            push_rows(&mut rows, node, vec![idx], String::new(), String::new());
+        }
+        rows
+    }
+}
+
+/// Adds a node and its shown descendants to the rows.
+fn push_rows<'a>(
+    rows: &mut Vec<Row<'a>>,
+    node: &'a TreeNode,
+    path: Vec<usize>,
+    guides: String,
+    child_guides: String,
+) {
+    rows.push(Row {
+        path: path.clone(),
+        guides,
+        node,
+    });
+
+    if !node.expanded {
+        return;
+    }
+
+    let last = node.children.len().saturating_sub(1);
+    for (idx, child) in node.children.iter().enumerate() {
+        let mut child_path = path.clone();
+        child_path.push(idx);
+        let (branch, rest) = if idx == last {
+            ("└─ ", "   ")
+        } else {
+            ("├─ ", "│  ")
+        };
+        push_rows(
+            rows,
+            child,
+            child_path,
+            format!("{child_guides}{branch}"),
+            format!("{child_guides}{rest}"),
+        );
+    }
+}
+
+impl Widget for Tree {
+    fn render(&self, win: &mut Window<Cell>, wid: usize, hgt: usize) {
+        let mut data = blank(wid, hgt, self.style);
+        let rows = self.rows();
+        let selected = rows.iter().position(|row| row.path == self.selected);
+        let offset = scroll_to(self.offset.get(), selected, rows.len(), hgt);
+        self.offset.set(offset);
+
+        for (line, row) in data.iter_mut().zip(rows.iter().skip(offset)) {
+            let style = if row.path == self.selected {
+                self.style.patch(self.highlight)
+            } else {
+                self.style
+            };
+            let marker = match (row.node.children.is_empty(), row.node.expanded) {
+                (true, _) => "  ",
+                (false, true) => "▾ ",
+                (false, false) => "▸ ",
+            };
+
+            let x = put_str(line, 0, &row.guides, self.style);
+            let x = put_str(line, x, marker, style);
+            let end = put_str(
+                line,
+                x,
+                &row.node.label.text,
+                style.patch(row.node.label.style),
+            );
+            if row.path == self.selected {
+                for cell in line[x..end].iter_mut() {
+                    cell.style = cell.style.patch(self.highlight);
+                }
+            }
+        }
+
+        win.data = data;
+    }
+}
@@ -0,0 +1,232 @@
+//! Drawing the container zoomed out, with each block of cells shown as one.
+
+use crate::geometry::{Coord, Point, Rect};
+use crate::{Container, WindowId};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Write};
+
+/// How a block of cells is shown as one when zoomed out.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Sampling {
+    /// The cell at the top left of the block, which is quickest.
+    #[default]
+    TopLeft,
+    /// Whatever most of the block holds, with the first found winning ties. Gaps
+    /// count too, so a block that is mostly empty is shown empty.
+    Majority,
+}
+
+/// Each value found in a block, with how many times it appears and the row and
+/// column where it first does.
+type Found<V> = Vec<(V, usize, (Coord, Coord))>;
+
+/// Returns what each block of `area`, `scale` cells across, is shown as, row by row
+/// of blocks, for at most `wid` by `hgt` blocks. Blocks at the right and bottom edges
+/// are cut off by the area. `get` returns what is at a position, and `occupied` every
+/// position it might return something for. Only those positions and the blocks
+/// returned are looked at, so a large area with little in it is sampled quickly.
+pub(crate) fn sample<V: PartialEq>(
+    area: Rect,
+    scale: usize,
+    (wid, hgt): (usize, usize),
+    sampling: Sampling,
+    occupied: impl IntoIterator<Item = Point>,
+    get: impl Fn(Point) -> Option<V>,
+) -> Vec<Vec<Option<V>>> {
+    let scale = scale.max(1);
+    let blocks_wid = area.wid.div_ceil(scale).min(wid);
+    let blocks_hgt = area.hgt.div_ceil(scale).min(hgt);
+    let block = |bx: usize, by: usize| {
+        let top_left = area.top_left + Point::new((bx * scale) as Coord, (by * scale) as Coord);
+        Rect::new(top_left, scale, scale)
+            .intersection(&area)
+            .unwrap_or_default()
+    };
+
+    if sampling == Sampling::TopLeft {
+        return (0..blocks_hgt)
+            .map(|by| {
+                (0..blocks_wid)
+                    .map(|bx| get(block(bx, by).top_left))
+                    .collect()
+            })
+            .collect();
+    }
+
+    let mut found: HashMap<(usize, usize), Found<V>> = HashMap::new();
+    for p in occupied {
+        if !area.contains(p) {
+            continue;
+        }
+        let rel = p - area.top_left;
+        let (bx, by) = (rel.x as usize / scale, rel.y as usize / scale);
+        if bx >= blocks_wid || by >= blocks_hgt {
+            continue;
+        }
+        let Some(value) = get(p) else {
+            continue;
+        };
+        let at = (p.y, p.x);
+        let values = found.entry((bx, by)).or_default();
+        match values.iter_mut().find(|(v, ..)| *v == value) {
+            Some((_, count, first)) => {
+                *count += 1;
+                *first = (*first).min(at);
+            }
+            None => values.push((value, 1, at)),
+        }
+    }
+
+    (0..blocks_hgt)
+        .map(|by| {
+            (0..blocks_wid)
+                .map(|bx| {
+                    let values = found.remove(&(bx, by)).unwrap_or_default();
+                    let rect = block(bx, by);
+                    let gaps = rect
+                        .wid
+                        .saturating_mul(rect.hgt)
+                        .saturating_sub(values.iter().map(|(_, count, _)| count).sum());
+                    // Ties go to whatever appears first.
+                    let best = values
+                        .into_iter()
+                        .min_by_key(|&(_, count, first)| (std::cmp::Reverse(count), first))?;
+                    if gaps > best.1 {
+                        return None;
+                    }
+                    if gaps == best.1 {
+                        // Every position before the first gap holds something, so
+                        // this stops after no more positions than were found.
+                        let first_gap = rect.points().find(|&p| get(p).is_none());
+                        if first_gap.is_some_and(|gap| (gap.y, gap.x) < best.2) {
+                            return None;
+                        }
+                    }
+                    Some(best.0)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+impl<T: fmt::Display> Container<T> {
+    /// Returns the area of the container shrunk by `scale` in each direction as of the
+    /// last refresh, so that each value stands for a `scale` by `scale` block. This is
+    /// None for blocks shown empty. A scale of 0 is taken as 1.
+    pub fn zoomed(&self, area: Rect, scale: usize, sampling: Sampling) -> Vec<Vec<Option<T>>>
+    where
+        T: Clone + PartialEq,
+    {
+        sample(
+            area,
+            scale,
+            (usize::MAX, usize::MAX),
+            sampling,
+            self.buffer.keys(),
+            |p| self.cell_at(p).cloned(),
+        )
+    }
+
+    /// Returns the window shown over most of each block, or at its top left, like
+    /// [`zoomed`](Self::zoomed).
+    pub fn zoomed_owners(
+        &self,
+        area: Rect,
+        scale: usize,
+        sampling: Sampling,
+    ) -> Vec<Vec<Option<WindowId>>> {
+        sample(
+            area,
+            scale,
+            (usize::MAX, usize::MAX),
+            sampling,
+            self.owners.keys(),
+            |p| self.owner_at(p),
+        )
+    }
+
+    /// Creates a string representation of the area of the container zoomed out by
+    /// `scale`, such as 2 or 4, using the provided default for empty blocks. This
+    /// fits an overview of a large scene into a smaller screen.
+    pub fn to_string_zoomed(
+        &self,
+        area: Rect,
+        scale: usize,
+        sampling: Sampling,
+        default: T,
+    ) -> String
+    where
+        T: Clone + PartialEq,
+    {
+        let mut out = String::new();
+        for row in self.zoomed(area, scale, sampling) {
+            for value in row {
+                out.push_str(&value.as_ref().unwrap_or(&default).to_string());
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Draws the area of the container zoomed out by `scale` to the screen, like
+    /// [`draw_with_default`](Self::draw_with_default).
+    pub fn draw_zoomed(&self, area: Rect, scale: usize, sampling: Sampling, default: T)
+    where
+        T: Clone + PartialEq,
+    {
+        #[cfg(any(unix, windows))]
+        let _ = crate::sys::enable_ansi();
+
+        let frame = self.to_string_zoomed(area, scale, sampling, default);
+        let mut out = io::stdout().lock();
+        let _ = out.write_all(frame.as_bytes()).and_then(|()| out.flush());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn majority_of_each_block() {
+        // a a b .
+        // a . b b
+        let cells: HashMap<Point, char> = [
+            (0, 0, 'a'),
+            (1, 0, 'a'),
+            (0, 1, 'a'),
+            (2, 0, 'b'),
+            (2, 1, 'b'),
+            (3, 1, 'b'),
+        ]
+        .into_iter()
+        .map(|(x, y, ch)| (Point::new(x, y), ch))
+        .collect();
+        let area = Rect::new(Point::new(0, 0), 5, 2);
+        let get = |p: Point| cells.get(&p).copied();
+        let keys = || cells.keys().copied();
+
+        let shown = sample(area, 2, (10, 10), Sampling::Majority, keys(), get);
+        assert_eq!(shown, [[Some('a'), Some('b'), None]]);
+        let shown = sample(area, 2, (2, 10), Sampling::TopLeft, keys(), get);
+        assert_eq!(shown, [[Some('a'), Some('b')]]);
+        // Two of each, with the value found first winning.
+        let shown = sample(area, 4, (10, 10), Sampling::Majority, keys(), get);
+        assert_eq!(shown, [[Some('a'), None]]);
+    }
+
+    #[test]
+    fn far_apart_points() {
+        let far = Point::new(Coord::MAX / 2, Coord::MAX / 2);
+        let area = Rect::from_corners(Point::new(0, 0), far);
+        let scale = area.wid.div_ceil(2);
+        let get = |p: Point| (p == Point::new(0, 0) || p == far).then_some(1);
+        let occupied = [Point::new(0, 0), far];
+
+        let shown = sample(area, scale, (2, 2), Sampling::Majority, occupied, get);
+        assert_eq!(shown, [[None, None], [None, None]]);
+        let shown = sample(area, scale, (2, 2), Sampling::TopLeft, occupied, get);
+        assert_eq!(shown, [[Some(1), None], [None, None]]);
+    }
+}